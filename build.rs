@@ -4,6 +4,9 @@
 //! them, so that they can be compiled into the application and won't need to
 //! be parsed at runtime.
 //! 
+//! How the Markdown is processed can be configured in an optional
+//! `Rustmark.toml` file at the root of the project.
+//! 
 
 
 
@@ -21,37 +24,84 @@
 //		Modules																											
 
 #[path = "src/lib.rs"]
+#[expect(dead_code, reason = "Not all library functionality is needed at build time")]
 mod rustmark;
 
 
 
 //		Packages																										
 
+use serde::{Deserialize, Serialize};
 use std::{
+	collections::BTreeMap,
 	env,
-	fs::{File, self},
-	io::prelude::*,
+	fs,
 	path::Path,
+	sync::Arc,
 	time,
 };
+use rustmark::{
+	CompiledPage,
+	MarkdownOptions,
+	links::{LinkChecker, LinkGraph},
+	nav::NavTree,
+	pipeline::{Pipeline, PipelineConfig},
+	search::SearchIndex,
+	wiki::{PageIndex, resolve_wiki_links},
+};
 use tokio::task::spawn_blocking;
 use walkdir::WalkDir;
 
 
 
+//		Structs																											
+
+//		BuildConfig																
+/// The build configuration, as read from `Rustmark.toml`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct BuildConfig {
+	//		Private properties													
+	/// The options controlling how the Markdown content is converted to HTML.
+	markdown: MarkdownOptions,
+	
+	/// The configuration options for the processors run over the HTML
+	/// generated from the Markdown content.
+	pipeline: PipelineConfig,
+}
+
+
+
 //		Functions																										
 
 //		main																	
 #[tokio::main]
 async fn main() {
 	println!("cargo:rerun-if-changed=content");
+	println!("cargo:rerun-if-changed=Rustmark.toml");
+	println!("cargo:rerun-if-env-changed=RUSTMARK_FAIL_ON_BROKEN_LINKS");
 	println!("cargo:rustc-cfg=build_script");
 	//	We use unwrap throughout because this is a build script, and if there
 	//	are any errors, we want the build to fail and for us to see the error.
 	let env_out_dir = env::var("OUT_DIR").unwrap();
 	let input_root  = Path::new("content");
-	let output_root = Path::new(&env_out_dir);
+	let out_dir     = Path::new(&env_out_dir);
+	let output_dir  = out_dir.join("content");
+	let output_root = output_dir.as_path();
 	let mut tasks   = vec![];
+	fs::create_dir_all(output_root).unwrap();
+	
+	//		Load configuration													
+	//	All the pages need to be regenerated if the configuration has changed
+	//	since the last build, so the configuration used is recorded alongside
+	//	the output.
+	let config      = load_config(Path::new("Rustmark.toml"));
+	let config_json = serde_json::to_string(&config).unwrap();
+	let config_path = out_dir.join("config.json");
+	let changed     = fs::read_to_string(&config_path).ok().as_ref() != Some(&config_json);
+	config.markdown.check().unwrap();
+	let options     = Arc::new(config.markdown.clone());
+	let pipeline    = Arc::new(Pipeline::builder().config(&config.pipeline).build().unwrap());
 	
 	//		Traverse output directory											
 	//	We do this first so that we can delete any files that are no longer
@@ -111,7 +161,9 @@ async fn main() {
 				.duration_since(time::UNIX_EPOCH).unwrap()
 				.as_secs()
 			;
-			if input_mtime < output_mtime {
+			//	Compiled pages written by an incompatible version of Rustmark need
+			//	to be regenerated, regardless of their age.
+			if input_mtime < output_mtime && !changed && is_current(&input_path, &output_path) {
 				println!("Skipping file: {}", input_path.display());
 				continue;
 			}
@@ -119,9 +171,11 @@ async fn main() {
 		//		Handle files													
 		//	We spawn a new task for each file, so that we can process them in
 		//	parallel to whatever degree is allowed by the runtime.
-		let task = spawn_blocking(move ||
+		let settings   = Arc::clone(&options);
+		let processors = Arc::clone(&pipeline);
+		let task       = spawn_blocking(move ||
 			if input_path.extension().is_some() && input_path.extension().unwrap() == "md" {
-				parse(&input_path, &output_path);
+				parse(&input_path, &output_path, &settings, &processors);
 			} else {
 				copy(&input_path, &output_path);
 			}
@@ -132,6 +186,31 @@ async fn main() {
 	for task in tasks {
 		task.await.unwrap();
 	}
+	fs::write(config_path, config_json).unwrap();
+	
+	//		Index content														
+	//	This is done from the output directory rather than from the tasks above,
+	//	so that it includes the files that were skipped as unchanged.
+	let (mut pages, files) = load(output_root);
+	link(&mut pages, output_root, &out_dir.join("pages.json"));
+	index(&pages, out_dir);
+	check(&pages, &files, &out_dir.join("links.json"));
+}
+
+//		is_current																
+/// Checks whether an output file can be used as it is.
+/// 
+/// Copied files are always usable, but compiled pages are only usable if they
+/// can be read using the current format version.
+/// 
+/// # Parameters
+/// 
+/// * `input_path`  - The path to the input file.
+/// * `output_path` - The path to the output file.
+/// 
+fn is_current(input_path: &Path, output_path: &Path) -> bool {
+	input_path.extension().is_none_or(|ext| ext != "md")
+	||	fs::read_to_string(output_path).is_ok_and(|json| CompiledPage::from_json(&json).is_ok())
 }
 
 //		copy																	
@@ -157,22 +236,180 @@ fn copy(input_path: &Path, output_path: &Path) {
 /// 
 /// * `input_path`  - The path to the input file.
 /// * `output_path` - The path to the output file.
+/// * `options`     - The options controlling how the Markdown is converted.
+/// * `pipeline`    - The processors to run over the generated HTML.
 /// 
-fn parse(input_path: &Path, output_path: &Path) {
+fn parse(input_path: &Path, output_path: &Path, options: &MarkdownOptions, pipeline: &Pipeline) {
 	println!("Parsing file: {}", input_path.display());
-	let (title, toc, html) = rustmark::parse(
+	let page = rustmark::parse_with_options(
 		&fs::read_to_string(input_path).unwrap(),
 		//	Remove the title from the index page, as it will have one added showing
 		//	the application title.
 		input_path == Path::new("content/index.md"),
+		options,
+		pipeline,
 	);
-	//	We use a custom format - the first line of the file is the title we
-	//	extracted, the second line is a JSON array with the table of contents,
-	//	and the rest is the HTML.
-	let mut output_file = File::create(output_path).unwrap();
-	output_file.write_all(format!("{}\n", &title).as_bytes()).unwrap();
-	output_file.write_all(format!("{}\n", serde_json::to_string(&toc).unwrap()).as_bytes()).unwrap();
-	output_file.write_all(html.as_bytes()).unwrap();
+	fs::write(output_path, page.to_json().unwrap()).unwrap();
+}
+
+//		load_config																
+/// Loads the build configuration.
+/// 
+/// If the file does not exist, the default configuration is used.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path to the configuration file.
+/// 
+fn load_config(path: &Path) -> BuildConfig {
+	if !path.exists() {
+		return BuildConfig::default();
+	}
+	toml::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+//		load																	
+/// Loads the parsed pages and lists the other files in the output directory.
+/// 
+/// # Parameters
+/// 
+/// * `output_root` - The path to the output directory containing parsed files.
+/// 
+/// # Returns
+/// 
+/// * `pages` - The parsed pages, keyed by their paths relative to the output
+///             root.
+/// * `files` - The paths of all other files, relative to the output root.
+/// 
+fn load(output_root: &Path) -> (BTreeMap<String, CompiledPage>, Vec<String>) {
+	let mut pages = BTreeMap::new();
+	let mut files = vec![];
+	for entry in WalkDir::new(output_root).follow_links(true).sort_by_file_name() {
+		let path          = entry.unwrap().path().to_path_buf();
+		if !path.is_file() {
+			continue;
+		}
+		let relative_path = path.strip_prefix(output_root).unwrap().to_string_lossy().replace('\\', "/");
+		if path.extension().is_some_and(|ext| ext == "md") {
+			drop(pages.insert(relative_path, CompiledPage::from_json(&fs::read_to_string(&path).unwrap()).unwrap()));
+		} else {
+			files.push(relative_path);
+		}
+	}
+	(pages, files)
+}
+
+//		link																	
+/// Resolves the wiki links in the parsed pages, and writes the page index used
+/// to a file.
+/// 
+/// Wiki links can refer to any page, so they can only be resolved once all the
+/// pages have been parsed. Any pages that change as a result are written back
+/// to the output directory, and any wiki links that cannot be resolved are
+/// reported as Cargo warnings. The page index is written out so that the
+/// server can resolve the wiki links in local content.
+/// 
+/// # Parameters
+/// 
+/// * `pages`       - The parsed pages, keyed by path.
+/// * `output_root` - The path to the output directory containing parsed files.
+/// * `index_path`  - The path to the page index file to write.
+/// 
+fn link(pages: &mut BTreeMap<String, CompiledPage>, output_root: &Path, index_path: &Path) {
+	println!("Resolving wiki links");
+	let mut index = PageIndex::new();
+	for (path, page) in &*pages {
+		index.add(path, &page.title);
+	}
+	for (path, page) in pages.iter_mut() {
+		let html = page.html.clone();
+		for reference in resolve_wiki_links(page, &index) {
+			println!("cargo:warning=Unresolved wiki link in {path}: [[{reference}]]");
+		}
+		if page.html != html {
+			fs::write(output_root.join(path), page.to_json().unwrap()).unwrap();
+		}
+	}
+	fs::write(index_path, serde_json::to_string(&index).unwrap()).unwrap();
+}
+
+//		index																	
+/// Builds a search index, navigation tree, link graph, and list of page access
+/// restrictions from the parsed pages, and writes them to files.
+/// 
+/// The files written are `search.json`, `nav.json`, `graph.json`, and
+/// `access.json`. The last of these records the `access` list from the front
+/// matter of each page that has one, so that the server does not need to load
+/// every page to know which ones are restricted. Pages whose front matter is
+/// invalid are recorded with an empty list, so that nobody can access them.
+/// 
+/// # Parameters
+/// 
+/// * `pages`   - The parsed pages, keyed by path.
+/// * `out_dir` - The path to the directory to write the files to.
+/// 
+fn index(pages: &BTreeMap<String, CompiledPage>, out_dir: &Path) {
+	println!("Indexing content");
+	let mut index  = SearchIndex::new();
+	let mut nav    = NavTree::new();
+	let mut graph  = LinkGraph::new();
+	let mut access = BTreeMap::new();
+	for (path, page) in pages {
+		index.add(path, &page.title, &page.toc, &page.html);
+		nav.insert(path, &page.title, page.metadata.order);
+		graph.add_page(path, &page.title, &page.links);
+		if let Some(ref err) = page.invalid {
+			println!("cargo:warning=Page {path} will not be served, as its front matter is invalid: {err}");
+			_ = access.insert(path, &[][..]);
+		} else if !page.metadata.access.is_empty() {
+			_ = access.insert(path, &page.metadata.access);
+		}
+	}
+	nav.sort();
+	fs::write(out_dir.join("search.json"), serde_json::to_string(&index).unwrap()).unwrap();
+	fs::write(out_dir.join("nav.json"),    serde_json::to_string(&nav).unwrap()).unwrap();
+	fs::write(out_dir.join("graph.json"),  serde_json::to_string(&graph).unwrap()).unwrap();
+	fs::write(out_dir.join("access.json"), serde_json::to_string(&access).unwrap()).unwrap();
+}
+
+//		check																	
+/// Checks the links in the parsed pages, and writes the link checker state to
+/// a file.
+/// 
+/// Broken links are reported as Cargo warnings. If the
+/// `RUSTMARK_FAIL_ON_BROKEN_LINKS` environment variable is set to `1` or
+/// `true`, the build fails instead. The link checker state is written out so
+/// that the server can check the links in local content against the baked-in
+/// content.
+/// 
+/// # Parameters
+/// 
+/// * `pages`      - The parsed pages, keyed by path.
+/// * `files`      - The paths of all other files.
+/// * `links_path` - The path to the link checker file to write.
+/// 
+fn check(pages: &BTreeMap<String, CompiledPage>, files: &[String], links_path: &Path) {
+	println!("Checking links");
+	let mut checker = LinkChecker::new();
+	for file in files {
+		checker.add_file(file);
+	}
+	for (path, page) in pages {
+		checker.add_page(path, &page.html);
+	}
+	let broken      = pages
+		.iter()
+		.flat_map(|(path, page)| checker.check(path, &page.links))
+		.collect::<Vec<_>>()
+	;
+	for link in &broken {
+		println!("cargo:warning=Broken link in {link}");
+	}
+	fs::write(links_path, serde_json::to_string(&checker).unwrap()).unwrap();
+	let fail        = env::var("RUSTMARK_FAIL_ON_BROKEN_LINKS")
+		.is_ok_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true"))
+	;
+	assert!(!fail || broken.is_empty(), "Found {} broken links", broken.len());
 }
 
 
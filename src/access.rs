@@ -0,0 +1,242 @@
+//! Access control functionality.
+//! 
+//! Access to content can be restricted to users with particular roles, either
+//! by rules in the application configuration that match content paths, or by
+//! an `access` list in the front matter of a page. Where several restrictions
+//! apply to the same path, a user needs to have at least one of the roles
+//! listed by each of them.
+//! 
+//! The paths checked must already be normalised, using [`content_path()`], so
+//! that a path cannot avoid the rules by taking a detour through another
+//! directory.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/access.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use glob::{MatchOptions, Pattern};
+use parking_lot::RwLock;
+use rustmark::CompiledPage;
+use std::{
+	collections::HashMap,
+	path::{Component, Path},
+};
+use tracing::error;
+
+
+
+//		Constants																										
+
+/// The options used when matching paths against access rules. Wildcards do not
+/// match path separators, so `*` only matches within a directory, and `**` is
+/// needed to match across directories.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+	case_sensitive:              true,
+	require_literal_separator:   true,
+	require_literal_leading_dot: false,
+};
+
+
+
+//		Structs																											
+
+//		AccessRules																
+/// The rules restricting access to content.
+#[derive(Debug, Default)]
+pub struct AccessRules {
+	//		Private properties													
+	/// The path patterns configured, and the roles allowed to access the paths
+	/// matching each of them.
+	patterns: Vec<(Pattern, Vec<String>)>,
+	
+	/// The roles allowed to access each page that has an `access` list in its
+	/// front matter, keyed by path. Pages with an empty list cannot be accessed
+	/// by anyone, which is used for pages whose front matter is invalid.
+	pages:    RwLock<HashMap<String, Vec<String>>>,
+}
+
+//󰭅		AccessRules																
+impl AccessRules {
+	//		new																	
+	/// Creates a new set of access rules.
+	/// 
+	/// Patterns that are not valid are logged as errors, and their roles are
+	/// applied to all content instead, so that a mistake does not make
+	/// restricted content available to everyone.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `rules` - The path patterns, relative to the content root, and the
+	///             roles allowed to access the paths matching each of them.
+	/// * `pages` - The roles allowed to access each page that has an `access`
+	///             list in its front matter, keyed by path. Pages with an empty
+	///             list cannot be accessed by anyone.
+	/// 
+	pub fn new(rules: &HashMap<String, Vec<String>>, pages: HashMap<String, Vec<String>>) -> Self {
+		let patterns = rules
+			.iter()
+			.map(|(pattern, roles)| {
+				let compiled = Pattern::new(pattern.trim_start_matches('/')).unwrap_or_else(|err| {
+					error!("Invalid access pattern {pattern}: {err}; applying its roles to all content");
+					Pattern::new("**").unwrap_or_default()
+				});
+				(compiled, roles.clone())
+			})
+			.collect()
+		;
+		Self { patterns, pages: RwLock::new(pages) }
+	}
+	
+	//		allows																
+	/// Checks whether a user with the given roles can access a path.
+	/// 
+	/// Both the configured rules and the front matter access lists recorded
+	/// for pages are checked. Pages that have not yet been loaded may not have
+	/// their front matter recorded, so when a page is being served, it should
+	/// be checked using [`allows_page()`](Self::allows_page()) once loaded.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page or file, relative to the content root.
+	/// * `roles` - The roles the user has.
+	/// 
+	pub fn allows(&self, path: &str, roles: &[String]) -> bool {
+		self.allows_by_rules(path, roles)
+		&&	self.pages.read().get(path).is_none_or(|allowed| permitted(allowed, roles))
+	}
+	
+	//		allows_by_rules														
+	/// Checks whether a user with the given roles can access a path, according
+	/// to the configured rules alone.
+	/// 
+	/// This is a cheap check that can be made before a page is loaded, to avoid
+	/// loading pages that cannot be shown anyway. It does not take front matter
+	/// access lists into account, so [`allows_page()`](Self::allows_page())
+	/// still needs to be checked once the page has been loaded.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page or file, relative to the content root.
+	/// * `roles` - The roles the user has.
+	/// 
+	pub fn allows_by_rules(&self, path: &str, roles: &[String]) -> bool {
+		self.patterns
+			.iter()
+			.filter(|rule| rule.0.matches_with(path, MATCH_OPTIONS))
+			.all(|rule| permitted(&rule.1, roles))
+	}
+	
+	//		allows_page															
+	/// Checks whether a user with the given roles can access a loaded page.
+	/// 
+	/// Both the configured rules and the access list in the front matter of
+	/// the page are checked. Pages whose front matter could not be parsed are
+	/// not allowed for anyone, as they may be missing their restrictions.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `page`  - The page.
+	/// * `roles` - The roles the user has.
+	/// 
+	pub fn allows_page(&self, path: &str, page: &CompiledPage, roles: &[String]) -> bool {
+		page.invalid.is_none()
+		&&	self.allows_by_rules(path, roles)
+		&&	(page.metadata.access.is_empty() || permitted(&page.metadata.access, roles))
+	}
+	
+	//		deny_page															
+	/// Records that a page cannot be accessed by anyone.
+	/// 
+	/// This is used for pages whose front matter could not be parsed, so that
+	/// the restrictions it may have contained are not lost.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn deny_page(&self, path: &str) {
+		drop(self.pages.write().insert(path.to_owned(), vec![]));
+	}
+	
+	//		set_page															
+	/// Records the roles allowed to access a page by its front matter.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`   - The path of the page, relative to the content root.
+	/// * `access` - The roles listed in the front matter of the page. If this
+	///              is empty, the page is not restricted by its front matter.
+	/// 
+	pub fn set_page(&self, path: &str, access: &[String]) {
+		let mut pages = self.pages.write();
+		if access.is_empty() {
+			drop(pages.remove(path));
+		} else {
+			drop(pages.insert(path.to_owned(), access.to_vec()));
+		}
+	}
+}
+
+
+
+//		Functions																										
+
+//		content_path															
+/// Normalises the path of a request for content.
+/// 
+/// The leading slash is removed, to give a path relative to the content root.
+/// Paths that could refer to anything outside of the location they appear to,
+/// such as ones containing `.` or `..` segments, empty segments, encoded dots
+/// or separators, or anything that the platform would treat as a root or a
+/// drive, are rejected. A trailing slash is allowed, to refer to a directory.
+/// 
+/// This needs to be applied before a requested path is checked for access or
+/// used to load anything, as otherwise a path such as `public/../hr/secret.md`
+/// would not match the rules for `hr/**`, but would still load the file.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path requested, as given in the URI.
+/// 
+/// # Returns
+/// 
+/// The path relative to the content root, or [`None`] if it is not allowed.
+/// 
+pub fn content_path(path: &str) -> Option<&str> {
+	let relative = path.strip_prefix('/').unwrap_or(path);
+	let trimmed  = relative.strip_suffix('/').unwrap_or(relative);
+	if trimmed.is_empty() {
+		return relative.is_empty().then_some(relative);
+	}
+	let safe     = trimmed.split('/').all(|segment| {
+		let decoded = segment.to_ascii_lowercase().replace("%2e", ".");
+		!decoded.is_empty()
+		&&	!decoded.chars().all(|c| c == '.')
+		&&	!decoded.contains(['\\', '\0'])
+		&&	!["%00", "%2f", "%5c"].iter().any(|encoded| decoded.contains(encoded))
+	});
+	let plain    = Path::new(trimmed).components().all(|component| matches!(component, Component::Normal(_)));
+	(safe && plain).then_some(relative)
+}
+
+//		permitted																
+/// Checks whether any of the roles a user has are in a list of allowed roles.
+/// 
+/// # Parameters
+/// 
+/// * `allowed` - The roles allowed.
+/// * `roles`   - The roles the user has.
+/// 
+fn permitted(allowed: &[String], roles: &[String]) -> bool {
+	allowed.iter().any(|role| roles.contains(role))
+}
@@ -0,0 +1,302 @@
+//! Audit log functionality.
+//! 
+//! Every request for a page or protected static file is recorded as an audit
+//! event, giving the user who made it, the path, the time, the response status,
+//! and the client IP address. Events are written as JSON lines to a file under
+//! the log directory, which is rotated daily, and the most recent events are
+//! also kept in memory so that they can be queried on the audit page. When the
+//! application starts, the recent events are loaded from the existing files.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/audit.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	auth::User,
+	config::AuditConfig,
+	state::AppState,
+};
+use axum::{
+	body::Body,
+	extract::{ConnectInfo, State},
+	http::Request,
+	middleware::Next,
+	response::Response,
+};
+use chrono::{DateTime, Utc};
+use core::net::{IpAddr, SocketAddr};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::VecDeque,
+	fs,
+	io::Write as _,
+	path::Path,
+	sync::Arc,
+};
+use terracotta::auth::middleware::Context as AuthContext;
+use tracing::{error, warn};
+use tracing_appender::{
+	non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard},
+	rolling::{RollingFileAppender, Rotation},
+};
+use utoipa::ToSchema;
+
+
+
+//		Constants																										
+
+/// The prefix of the audit log file names. The date is appended when the file
+/// is rotated.
+const FILE_PREFIX: &str = "audit.log";
+
+
+
+//		Structs																											
+
+//		AuditEvent																
+/// A request for content, as recorded in the audit log.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct AuditEvent {
+	//		Public properties													
+	/// When the request was made.
+	pub timestamp: DateTime<Utc>,
+	
+	/// The username of the user who made the request, or `None` if they were
+	/// not logged in. For requests made with an API token, this is the name of
+	/// the token.
+	pub user:      Option<String>,
+	
+	/// The path requested.
+	pub path:      String,
+	
+	/// The HTTP status code of the response.
+	pub status:    u16,
+	
+	/// The IP address of the client, if known.
+	#[schema(value_type = Option<String>)]
+	pub ip:        Option<IpAddr>,
+}
+
+//		AuditLog																
+/// The audit log of content requests.
+#[derive(Debug, Default)]
+pub struct AuditLog {
+	//		Private properties													
+	/// The writer for the log file, if enabled, and the guard that flushes it
+	/// when the application stops.
+	file:        Option<(NonBlocking, WorkerGuard)>,
+	
+	/// The most recent events, oldest first.
+	recent:      Mutex<VecDeque<AuditEvent>>,
+	
+	/// The maximum number of recent events to keep.
+	capacity:    usize,
+	
+	/// Whether to take the client IP address from the `X-Forwarded-For`
+	/// header.
+	trust_proxy: bool,
+}
+
+//󰭅		AuditLog																
+impl AuditLog {
+	//		new																	
+	/// Creates a new audit log.
+	/// 
+	/// If the log file cannot be opened, an error is logged, and events are
+	/// only kept in memory.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `config` - The configuration options for the audit log.
+	/// * `logdir` - The directory to store the log files in.
+	/// 
+	pub fn new(config: &AuditConfig, logdir: &str) -> Self {
+		if !config.enabled {
+			return Self::default();
+		}
+		let file = RollingFileAppender::builder()
+			.rotation(Rotation::DAILY)
+			.filename_prefix(FILE_PREFIX)
+			.max_log_files(config.max_files.max(1))
+			.build(logdir)
+			.map_err(|err| error!("Could not open audit log in {logdir}: {err}"))
+			.ok()
+			.map(|appender| NonBlockingBuilder::default().lossy(false).finish(appender))
+		;
+		Self {
+			file,
+			recent:      Mutex::new(load_recent(Path::new(logdir), config.recent)),
+			capacity:    config.recent,
+			trust_proxy: config.trust_proxy,
+		}
+	}
+	
+	//		query																
+	/// Finds recent events, newest first.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `user`  - The username to match exactly, if any.
+	/// * `path`  - The path prefix to match, if any.
+	/// * `limit` - The maximum number of events to return.
+	/// 
+	pub fn query(&self, user: Option<&str>, path: Option<&str>, limit: usize) -> Vec<AuditEvent> {
+		self.recent
+			.lock()
+			.iter()
+			.rev()
+			.filter(|event| user.is_none_or(|username| event.user.as_deref() == Some(username)))
+			.filter(|event| path.is_none_or(|prefix| event.path.starts_with(prefix)))
+			.take(limit)
+			.cloned()
+			.collect()
+	}
+	
+	//		record																
+	/// Records an event.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `event` - The event to record.
+	/// 
+	pub fn record(&self, event: AuditEvent) {
+		if let Some((ref writer, _)) = self.file {
+			//	The line is written in a single call, as each write is passed to the
+			//	writer thread separately, and so the writes for concurrent requests
+			//	could otherwise be interleaved.
+			match serde_json::to_string(&event) {
+				Ok(line) => if let Err(err) = writer.clone().write_all(format!("{line}\n").as_bytes()) {
+					error!("Could not write to audit log: {err}");
+				},
+				Err(err) => error!("Could not serialise audit event: {err}"),
+			}
+		}
+		if self.capacity > 0 {
+			let mut recent = self.recent.lock();
+			if recent.len() >= self.capacity {
+				drop(recent.pop_front());
+			}
+			recent.push_back(event);
+		}
+	}
+}
+
+
+
+//		Functions																										
+
+//		audit_layer																
+/// Records content requests in the audit log.
+/// 
+/// This middleware is applied to the content routes, and so runs after the
+/// request has been authenticated. The event is recorded once the response is
+/// ready, so that its status is known.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `request` - The request.
+/// * `next`    - The next middleware.
+/// 
+pub async fn audit_layer(
+	State(state): State<Arc<AppState>>,
+	request:      Request<Body>,
+	next:         Next,
+) -> Response {
+	let user     = request
+		.extensions()
+		.get::<AuthContext<User>>()
+		.and_then(|auth_cx| auth_cx.current_user.as_ref())
+		.map(|current| current.username.clone())
+	;
+	let ip       = client_ip(&request, state.audit.trust_proxy);
+	let path     = request.uri().path().to_owned();
+	let response = next.run(request).await;
+	state.audit.record(AuditEvent {
+		timestamp: Utc::now(),
+		user,
+		path,
+		status:    response.status().as_u16(),
+		ip,
+	});
+	response
+}
+
+//		client_ip																
+/// Determines the IP address of the client that made a request.
+/// 
+/// When the proxy is trusted, the address is taken from the last entry in the
+/// `X-Forwarded-For` header, which is the one added by the proxy. Any earlier
+/// entries were sent by the client, and so could be forged.
+/// 
+/// # Parameters
+/// 
+/// * `request`     - The request.
+/// * `trust_proxy` - Whether to take the address from the `X-Forwarded-For`
+///                   header, if present.
+/// 
+fn client_ip(request: &Request<Body>, trust_proxy: bool) -> Option<IpAddr> {
+	let forwarded = trust_proxy.then(|| request
+		.headers()
+		.get("x-forwarded-for")
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.rsplit(',').next())
+		.and_then(|value| value.trim().parse().ok())
+	).flatten();
+	forwarded.or_else(|| request.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip()))
+}
+
+//		load_recent																
+/// Loads the most recent events from the existing audit log files.
+/// 
+/// Lines that cannot be parsed are skipped, and a warning is logged.
+/// 
+/// # Parameters
+/// 
+/// * `logdir`   - The directory containing the log files.
+/// * `capacity` - The maximum number of events to load.
+/// 
+fn load_recent(logdir: &Path, capacity: usize) -> VecDeque<AuditEvent> {
+	let mut recent = VecDeque::new();
+	let Ok(entries) = fs::read_dir(logdir) else {
+		return recent;
+	};
+	let mut files  = entries
+		.filter_map(Result::ok)
+		.map(|entry| entry.path())
+		.filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with(FILE_PREFIX)))
+		.collect::<Vec<_>>()
+	;
+	files.sort();
+	for file in files.iter().rev() {
+		if recent.len() >= capacity {
+			break;
+		}
+		let Ok(contents) = fs::read_to_string(file) else {
+			warn!("Could not read audit log {}", file.display());
+			continue;
+		};
+		for line in contents.lines().rev().filter(|line| !line.trim().is_empty()) {
+			if recent.len() >= capacity {
+				break;
+			}
+			match serde_json::from_str(line) {
+				Ok(event) => recent.push_front(event),
+				Err(err)  => warn!("Skipping invalid line in audit log {}: {err}", file.display()),
+			}
+		}
+	}
+	recent
+}
+
+
@@ -4,6 +4,11 @@
 
 //		Packages																										
 
+use crate::{
+	oidc::ID_PREFIX,
+	state::AppState,
+};
+use core::any::Any;
 use serde::{Deserialize, Serialize};
 use terracotta::auth::{
 	middleware::{User as AuthUser, Credentials as AuthCredentials, UserProvider as AuthUserProvider},
@@ -43,8 +48,17 @@ impl AuthCredentials for Credentials {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct User {
 	//		Private properties													
+	/// The unique ID of the user, which is stored in the session. For users
+	/// in the user store, this is the username. For users who logged in using
+	/// OpenID Connect, it is based on the subject identifier assigned by the
+	/// provider.
+	pub id:       String,
+	
 	/// The username.
 	pub username: String,
+	
+	/// The roles the user has, which determine the content they can access.
+	pub roles:    Vec<String>,
 }
 
 //󰭅		AuthUser																
@@ -53,7 +67,7 @@ impl AuthUser for User {
 	
 	//		id																	
 	fn id(&self) -> &Self::Id {
-		&self.username
+		&self.id
 	}
 	
 	//		to_loggable_string													
@@ -72,11 +86,14 @@ impl AuthUserProvider for User {
 		state:       &SP,
 		credentials: &Self::Credentials,
 	) -> Option<Self> {
-		state
-			.users()
-			.get(&credentials.username)
-			.filter(|&pass| pass == &credentials.password)
-			.map(|_| Self { username: credentials.username.clone() })
+		let app = app_state(state)?;
+		app.users
+			.authenticate(&credentials.username, &credentials.password)
+			.then(|| Self {
+				id:       credentials.username.clone(),
+				username: credentials.username.clone(),
+				roles:    app.roles(&credentials.username),
+			})
 	}
 	
 	//		find_by_id															
@@ -84,11 +101,38 @@ impl AuthUserProvider for User {
 		state: &SP,
 		id:    &<Self::User as AuthUser>::Id,
 	) -> Option<Self> {
-		state
-			.users()
-			.get(id)
-			.map(|_| Self { username: id.to_owned() })
+		let app = app_state(state)?;
+		if id.starts_with(ID_PREFIX) {
+			return app.oidc.as_ref()?.user(id);
+		}
+		app.users
+			.contains(id)
+			.then(|| Self {
+				id:       id.to_owned(),
+				username: id.to_owned(),
+				roles:    app.roles(id),
+			})
 	}
 }
 
 
+
+//		Functions																										
+
+//		app_state																
+/// Gets the application state from the state provider.
+/// 
+/// Terracotta only passes the state to the user provider as a generic state
+/// provider, which does not give access to the user store, so the concrete
+/// application state needs to be recovered.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The state provider.
+/// 
+fn app_state<SP: AuthStateProvider>(state: &SP) -> Option<&AppState> {
+	let any: &dyn Any = state;
+	any.downcast_ref::<AppState>()
+}
+
+
@@ -0,0 +1,185 @@
+//! Caching of Markdown pages parsed at runtime.
+//! 
+//! Local Markdown content is parsed when requested, which is comparatively
+//! slow. Parsed pages are therefore kept in memory, and reused for as long as
+//! the files backing them remain unchanged.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/cache.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use core::{
+	num::NonZeroUsize,
+	sync::atomic::{AtomicU64, Ordering},
+};
+use lru::LruCache;
+use parking_lot::Mutex;
+use rustmark::CompiledPage;
+use std::{
+	fs::Metadata,
+	sync::Arc,
+	time::SystemTime,
+};
+
+
+
+//		Structs																											
+
+//		CachedPage																
+/// A parsed page held in the cache.
+#[derive(Debug)]
+struct CachedPage {
+	//		Private properties													
+	/// The modification time of the file the page was parsed from.
+	modified: Option<SystemTime>,
+	
+	/// The size of the file the page was parsed from.
+	size:     u64,
+	
+	/// The parsed page.
+	page:     Arc<CompiledPage>,
+}
+
+//		CacheStats																
+/// A snapshot of the cache statistics.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+	//		Public properties													
+	/// The maximum number of pages that can be cached.
+	pub capacity: usize,
+	
+	/// The number of pages currently cached.
+	pub entries:  usize,
+	
+	/// The number of requests that were served from the cache.
+	pub hits:     u64,
+	
+	/// The number of requests that required the page to be parsed.
+	pub misses:   u64,
+}
+
+//		PageCache																
+/// A least-recently-used cache of parsed pages.
+/// 
+/// Pages are keyed by their path, relative to the content root, and are only
+/// returned if the modification time and size of the file backing them are
+/// unchanged since they were parsed. Once the cache is full, the page that was
+/// used the longest time ago is discarded.
+/// 
+#[derive(Debug, Default)]
+pub struct PageCache {
+	//		Private properties													
+	/// The cached pages. If this is `None`, caching is disabled.
+	entries: Option<Mutex<LruCache<String, CachedPage>>>,
+	
+	/// The number of requests that were served from the cache.
+	hits:    AtomicU64,
+	
+	/// The number of requests that required the page to be parsed.
+	misses:  AtomicU64,
+}
+
+//󰭅		PageCache																
+impl PageCache {
+	//		new																	
+	/// Creates a new page cache.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `capacity` - The maximum number of pages to cache. If this is zero,
+	///                caching is disabled.
+	/// 
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: NonZeroUsize::new(capacity).map(|size| Mutex::new(LruCache::new(size))),
+			..Default::default()
+		}
+	}
+	
+	//		get																	
+	/// Retrieves a page from the cache.
+	/// 
+	/// The page is only returned if the file it was parsed from is unchanged.
+	/// A page that is out of date is removed.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`     - The path of the page, relative to the content root.
+	/// * `metadata` - The current metadata of the file backing the page.
+	/// 
+	pub fn get(&self, path: &str, metadata: &Metadata) -> Option<Arc<CompiledPage>> {
+		let found = self.entries.as_ref().and_then(|mutex| {
+			let mut entries = mutex.lock();
+			let current     = entries.get(path).map(|cached|
+				cached.modified == metadata.modified().ok() && cached.size == metadata.len()
+			)?;
+			if current {
+				entries.get(path).map(|cached| Arc::clone(&cached.page))
+			} else {
+				drop(entries.pop(path));
+				None
+			}
+		});
+		let counter = if found.is_some() { &self.hits } else { &self.misses };
+		_ = counter.fetch_add(1, Ordering::Relaxed);
+		found
+	}
+	
+	//		insert																
+	/// Adds a page to the cache.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`     - The path of the page, relative to the content root.
+	/// * `metadata` - The metadata of the file the page was parsed from.
+	/// * `page`     - The parsed page.
+	/// 
+	pub fn insert(&self, path: &str, metadata: &Metadata, page: Arc<CompiledPage>) {
+		if let Some(entries) = self.entries.as_ref() {
+			drop(entries.lock().put(path.to_owned(), CachedPage {
+				modified: metadata.modified().ok(),
+				size:     metadata.len(),
+				page,
+			}));
+		}
+	}
+	
+	//		remove																
+	/// Removes a page from the cache, such as when its file has been deleted.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn remove(&self, path: &str) {
+		if let Some(entries) = self.entries.as_ref() {
+			drop(entries.lock().pop(path));
+		}
+	}
+	
+	//		stats																
+	/// Returns the current cache statistics.
+	pub fn stats(&self) -> CacheStats {
+		let (capacity, entries) = self.entries.as_ref().map_or((0, 0), |mutex| {
+			let entries = mutex.lock();
+			(entries.cap().get(), entries.len())
+		});
+		CacheStats {
+			capacity,
+			entries,
+			hits:   self.hits.load(Ordering::Relaxed),
+			misses: self.misses.load(Ordering::Relaxed),
+		}
+	}
+}
+
+
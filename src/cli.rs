@@ -0,0 +1,315 @@
+//! Command-line interface for the application.
+
+
+
+//		Packages																										
+
+use crate::{
+	config::Config,
+	export::{ExportError, ExportOptions, export},
+	print::{PrintError, PrintOptions, print_document},
+	routes::RouteError,
+	state::AppState,
+	tokens::{TokenError, generate_token},
+	users::{HashAlgorithm, UserStoreError, hash_password},
+};
+use clap::{Arg, ArgAction, Command as ClapCommand, value_parser};
+use rpassword::prompt_password;
+use std::{
+	fs,
+	io::{Error as IoError, IsTerminal as _, Write as _, self},
+	path::PathBuf,
+};
+use terracotta::app::{errors::AppError, init::load_config};
+use thiserror::Error as ThisError;
+
+
+
+//		Enums																											
+
+//		CliError																
+/// Errors that can occur when running the application.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum CliError {
+	/// An error occurred in the application.
+	#[error(transparent)]
+	App(Box<AppError>),
+	
+	/// The password to hash could not be read.
+	#[error("Could not read password: {0}")]
+	CouldNotReadPassword(IoError),
+	
+	/// The output could not be written.
+	#[error("Could not write output: {0}")]
+	CouldNotWriteOutput(IoError),
+	
+	/// The password to hash was empty.
+	#[error("No password given")]
+	EmptyPassword,
+	
+	/// An error occurred when exporting the content.
+	#[error(transparent)]
+	Export(#[from] ExportError),
+	
+	/// An error occurred when creating a printable document.
+	#[error(transparent)]
+	Print(#[from] PrintError),
+	
+	/// The routes could not be set up.
+	#[error(transparent)]
+	Route(#[from] RouteError),
+	
+	/// An error occurred with API tokens.
+	#[error(transparent)]
+	Token(#[from] TokenError),
+	
+	/// An error occurred with users or passwords.
+	#[error(transparent)]
+	UserStore(#[from] UserStoreError),
+}
+
+//󰭅		From<AppError>															
+impl From<AppError> for CliError {
+	//		from																
+	fn from(err: AppError) -> Self {
+		Self::App(Box::new(err))
+	}
+}
+
+//		Command																	
+/// The commands that can be run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Command {
+	/// Exports the content as a static website.
+	Export(ExportOptions),
+	
+	/// Generates a new API token. The token and its hash are written to
+	/// standard output.
+	GenerateToken,
+	
+	/// Hashes a password, for use in the configuration or a users file. The
+	/// password is prompted for, or read from standard input if that is not a
+	/// terminal, and the hash is written to standard output.
+	HashPassword {
+		/// The algorithm to hash the password with.
+		algorithm: HashAlgorithm,
+	},
+	
+	/// Renders a page, or all the pages in a directory, as a single HTML
+	/// document for printing.
+	Print(PrintOptions),
+}
+
+//󰭅		Command																	
+impl Command {
+	//		from_args															
+	/// Determines the command to run from the command-line arguments.
+	/// 
+	/// If the arguments are invalid, or help or version information is
+	/// requested, this is handled by printing the appropriate message and
+	/// exiting.
+	/// 
+	/// # Returns
+	/// 
+	/// The command to run, or `None` if the server should be started.
+	/// 
+	pub fn from_args() -> Option<Self> {
+		let matches = cli().get_matches();
+		match matches.subcommand() {
+			Some(("export", args))        => Some(Self::Export(ExportOptions {
+				output: args.get_one::<PathBuf>("output").cloned().unwrap_or_default(),
+				roles:  args.get_many::<String>("role").unwrap_or_default().cloned().collect(),
+				zip:    args.get_flag("zip"),
+			})),
+			Some(("generate-token", _))   => Some(Self::GenerateToken),
+			Some(("hash-password", args)) => Some(Self::HashPassword {
+				algorithm: match args.get_one::<String>("algorithm").map(String::as_str) {
+					Some("bcrypt") => HashAlgorithm::Bcrypt,
+					_              => HashAlgorithm::Argon2,
+				},
+			}),
+			Some(("print", args))         => Some(Self::Print(PrintOptions {
+				path:   args.get_one::<String>("path").map(|path| path.trim_start_matches('/').to_owned()).unwrap_or_default(),
+				output: args.get_one::<PathBuf>("output").cloned(),
+				roles:  args.get_many::<String>("role").unwrap_or_default().cloned().collect(),
+			})),
+			_                             => None,
+		}
+	}
+	
+	//		run																	
+	/// Runs the command.
+	/// 
+	/// # Errors
+	/// 
+	/// If the command fails, an error is returned.
+	/// 
+	pub async fn run(self) -> Result<(), CliError> {
+		match self {
+			Self::Export(options)            => run_export(&options).await,
+			Self::GenerateToken              => run_generate_token(),
+			Self::HashPassword { algorithm } => run_hash_password(algorithm),
+			Self::Print(options)             => run_print(&options).await,
+		}
+	}
+}
+
+
+
+//		Functions																										
+
+//		cli																		
+/// Describes the command-line interface.
+/// 
+/// If no command is given, the server is started.
+/// 
+fn cli() -> ClapCommand {
+	ClapCommand::new(env!("CARGO_PKG_NAME"))
+		.version(env!("CARGO_PKG_VERSION"))
+		.about(env!("CARGO_PKG_DESCRIPTION"))
+		.subcommand(ClapCommand::new("export")
+			.about("Exports the content as a static website, using the configuration in the current directory")
+			.arg(Arg::new("output")
+				.help("The directory to write the website to, which must not exist or be empty")
+				.required(true)
+				.value_parser(value_parser!(PathBuf))
+			)
+			.arg(Arg::new("role")
+				.long("role")
+				.help("A role to export the content for, which can be given more than once")
+				.action(ArgAction::Append)
+			)
+			.arg(Arg::new("zip")
+				.long("zip")
+				.help("Also create a zip file of the website")
+				.action(ArgAction::SetTrue)
+			)
+		)
+		.subcommand(ClapCommand::new("generate-token")
+			.about("Generates a new API token, and the hash to add to the configuration")
+		)
+		.subcommand(ClapCommand::new("hash-password")
+			.about("Hashes a password, prompted for or read from standard input, for use in the configuration or a users file")
+			.arg(Arg::new("algorithm")
+				.long("algorithm")
+				.help("The algorithm to hash the password with")
+				.value_parser(["argon2", "bcrypt"])
+				.default_value("argon2")
+			)
+		)
+		.subcommand(ClapCommand::new("print")
+			.about("Renders a page, or all the pages in a directory, as a single HTML document for printing")
+			.arg(Arg::new("path")
+				.help("The page or directory to print, relative to the content root, such as guides/setup.md or guides")
+				.default_value("")
+				.hide_default_value(true)
+			)
+			.arg(Arg::new("output")
+				.long("output")
+				.short('o')
+				.help("The file to write the document to, instead of standard output")
+				.value_parser(value_parser!(PathBuf))
+			)
+			.arg(Arg::new("role")
+				.long("role")
+				.help("A role to create the document for, which can be given more than once")
+				.action(ArgAction::Append)
+			)
+		)
+}
+
+//		run_export																
+/// Exports the content as a static website, and writes a summary to standard
+/// output.
+/// 
+/// # Parameters
+/// 
+/// * `options` - The export options.
+/// 
+/// # Errors
+/// 
+/// If the configuration cannot be loaded, the export fails, or the summary
+/// cannot be written, an error is returned.
+/// 
+async fn run_export(options: &ExportOptions) -> Result<(), CliError> {
+	let state   = AppState::new(load_config::<Config>()?);
+	let summary = export(&state, options).await?;
+	let mut out = io::stdout();
+	writeln!(out, "Exported {} pages and {} files to {}", summary.pages, summary.files, options.output.display())
+		.map_err(CliError::CouldNotWriteOutput)?;
+	if let Some(zip) = summary.zip {
+		writeln!(out, "Created {}", zip.display()).map_err(CliError::CouldNotWriteOutput)?;
+	}
+	Ok(())
+}
+
+//		run_generate_token														
+/// Generates a new API token, and writes it and its hash to standard output.
+/// 
+/// # Errors
+/// 
+/// If the token cannot be generated, or the output cannot be written, an error
+/// is returned.
+/// 
+fn run_generate_token() -> Result<(), CliError> {
+	let (token, hash) = generate_token()?;
+	writeln!(io::stdout(), "Token: {token}\nHash:  {hash}").map_err(CliError::CouldNotWriteOutput)
+}
+
+//		run_hash_password														
+/// Reads a password, and writes its hash to standard output.
+/// 
+/// If standard input is a terminal, the password is prompted for, without
+/// being shown as it is typed. Otherwise it is read from standard input, so
+/// that it can be piped in.
+/// 
+/// # Parameters
+/// 
+/// * `algorithm` - The algorithm to hash the password with.
+/// 
+/// # Errors
+/// 
+/// If the password cannot be read or hashed, or the hash cannot be written, an
+/// error is returned.
+/// 
+fn run_hash_password(algorithm: HashAlgorithm) -> Result<(), CliError> {
+	let stdin    = io::stdin();
+	let password = if stdin.is_terminal() {
+		prompt_password("Password: ").map_err(CliError::CouldNotReadPassword)?
+	} else {
+		let mut line = String::new();
+		_ = stdin.read_line(&mut line).map_err(CliError::CouldNotReadPassword)?;
+		line
+	};
+	let trimmed  = password.trim_end_matches(['\r', '\n']);
+	if trimmed.is_empty() {
+		return Err(CliError::EmptyPassword);
+	}
+	writeln!(io::stdout(), "{}", hash_password(trimmed, algorithm)?).map_err(CliError::CouldNotWriteOutput)
+}
+
+//		run_print																
+/// Renders a page, or all the pages in a directory, as a single HTML document
+/// for printing, and writes it to a file or standard output.
+/// 
+/// # Parameters
+/// 
+/// * `options` - The print options.
+/// 
+/// # Errors
+/// 
+/// If the configuration cannot be loaded, the document cannot be created, or
+/// the output cannot be written, an error is returned.
+/// 
+async fn run_print(options: &PrintOptions) -> Result<(), CliError> {
+	let state = AppState::new(load_config::<Config>()?);
+	let html  = print_document(&state, &options.path, &options.roles).await?;
+	match options.output {
+		Some(ref output) => fs::write(output, html),
+		None             => io::stdout().write_all(html.as_bytes()),
+	}.map_err(CliError::CouldNotWriteOutput)
+}
+
+
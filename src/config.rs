@@ -4,7 +4,9 @@
 
 //		Packages																										
 
+use chrono::{DateTime, Utc};
 use core::net::IpAddr;
+use rustmark::{MarkdownOptions, pipeline::PipelineConfig};
 use serde::{Deserialize, Serialize};
 use smart_default::SmartDefault;
 use std::collections::HashMap;
@@ -17,8 +19,81 @@ use terracotta::{
 
 
 
+//		Enums																											
+
+//		UserSource																
+/// The sources that users can be loaded from.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum UserSource {
+	/// The `users` section of the main configuration.
+	#[default]
+	Config,
+	
+	/// An htpasswd file, with one `username:hash` entry per line.
+	Htpasswd,
+	
+	/// A TOML file, with a `users` section in the same format as the main
+	/// configuration.
+	Toml,
+	
+	/// An LDAP directory, which users are authenticated against by binding as
+	/// them.
+	Ldap,
+}
+
+//		TokenScope																
+/// The parts of the application that an API token can be used to access.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum TokenScope {
+	/// Content pages, static files, and search.
+	Content,
+	
+	/// The statistics endpoints, under `/api/stats`.
+	Stats,
+}
+
+
+
 //		Structs																											
 
+//		AuditConfig																
+/// The configuration options for the audit log of content requests.
+/// 
+/// Every request for a page or protected static file is recorded, along with
+/// the user who made it, and written as a JSON line to a log file that is
+/// rotated daily.
+/// 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SmartDefault)]
+pub struct AuditConfig {
+	//		Public properties													
+	/// Whether content requests are recorded in the audit log.
+	#[default = true]
+	pub enabled:     bool,
+	
+	/// The number of daily audit log files to keep. Older files are deleted
+	/// when the log is rotated.
+	#[default = 90]
+	pub max_files:   usize,
+	
+	/// The number of recent events to keep in memory, which can be viewed on
+	/// the audit page.
+	#[default = 10_000]
+	pub recent:      usize,
+	
+	/// The roles allowed to view the audit page.
+	#[default(vec!["admin".to_owned()])]
+	pub roles:       Vec<String>,
+	
+	/// Whether to take the client IP address from the `X-Forwarded-For`
+	/// header. This should only be turned on when the application is behind a
+	/// reverse proxy that sets the header. The last address in the header is
+	/// used, as that is the one added by the proxy.
+	#[default = false]
+	pub trust_proxy: bool,
+}
+
 //		Config																	
 /// The main configuration options for the application.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SmartDefault)]
@@ -26,36 +101,158 @@ pub struct Config {
 	//		Public properties													
 	/// The host to listen on.
 	#[default(IpAddr::from([127, 0, 0, 1]))]
-	pub host:     IpAddr,
+	pub host:       IpAddr,
 	
 	/// The port to listen on.
 	#[default = 8000]
-	pub port:     u16,
+	pub port:       u16,
 	
 	/// The directory to store log files in.
 	#[default = "log"]
-	pub logdir:   String,
+	pub logdir:     String,
 	
 	/// The title of the application.
 	#[default = "Rustmark"]
-	pub title:    String,
+	pub title:      String,
 	
 	/// Loading configuration for HTML templates.
 	#[serde(rename = "html_templates")]
-	pub html:     HtmlTemplates,
+	pub html:       HtmlTemplates,
 	
 	/// Loading configuration for Markdown content.
-	pub markdown: MarkdownContent,
+	pub markdown:   MarkdownContent,
+	
+	/// The configuration options for the processors run over the HTML
+	/// generated from local Markdown content. Baked-in content is processed
+	/// according to `Rustmark.toml` at build time instead.
+	pub pipeline:   PipelineConfig,
 	
 	/// The configuration options for serving static assets.
-	pub assets:   AssetsConfig,
+	pub assets:     AssetsConfig,
 	
 	/// The configuration options for gathering and processing statistics.
-	pub stats:    StatsConfig,
+	pub stats:      StatsConfig,
 	
-	/// A list of users and their passwords.
+	/// The configuration options for the audit log of content requests.
+	pub audit:      AuditConfig,
+	
+	/// A list of users and their password hashes. These are only used if the
+	/// user store is configured to use them.
+	#[default(HashMap::new())]
+	pub users:      HashMap<String, String>,
+	
+	/// The configuration options for where users are loaded from.
+	pub user_store: UserStoreConfig,
+	
+	/// The configuration options for authenticating users against an LDAP
+	/// directory.
+	pub ldap:       LdapConfig,
+	
+	/// The configuration options for logging in using OpenID Connect.
+	pub oidc:       OidcConfig,
+	
+	/// The roles given to users, keyed by username. These apply regardless of
+	/// the user store, and are combined with any roles given by the store.
+	/// Users who log in using OpenID Connect are keyed by their ID instead.
+	#[default(HashMap::new())]
+	pub roles:      HashMap<String, Vec<String>>,
+	
+	/// Access control rules for the content. Each key is a path pattern,
+	/// relative to the content root, such as `hr/**`, and each value is the
+	/// list of roles allowed to access the paths matching it.
+	#[default(HashMap::new())]
+	pub access:     HashMap<String, Vec<String>>,
+	
+	/// Path prefixes, relative to the content root, of content that can be
+	/// viewed without logging in. Each prefix is either a directory, such as
+	/// `public`, or a single file, such as `welcome.md`. Making `index.md`
+	/// public also makes the home page public. Access control rules still
+	/// apply, so restricted content is not shown to users who are not logged
+	/// in. A prefix cannot be the same as one of the application's own
+	/// routes, such as `search`.
+	#[default(Vec::new())]
+	pub public:     Vec<String>,
+	
+	/// API tokens that can be used instead of logging in, keyed by name.
 	#[default(HashMap::new())]
-	pub users:    HashMap<String, String>,
+	pub tokens:     HashMap<String, TokenConfig>,
+}
+
+//		LdapConfig																
+/// The configuration options for authenticating users against an LDAP
+/// directory.
+/// 
+/// Users are authenticated by binding to the directory as them, and their
+/// roles can optionally be found from the groups they are members of.
+/// 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SmartDefault)]
+pub struct LdapConfig {
+	//		Public properties													
+	/// The URL of the directory server. Use `ldaps://` for TLS.
+	#[default = "ldap://127.0.0.1:389"]
+	pub url:             String,
+	
+	/// Whether to upgrade `ldap://` connections to TLS using StartTLS.
+	#[default = false]
+	pub starttls:        bool,
+	
+	/// Whether to verify the server's TLS certificate. This should only be
+	/// turned off for testing.
+	#[default = true]
+	pub verify_tls:      bool,
+	
+	/// The template for the DN to bind as, for a user. `{username}` is replaced
+	/// with the username, escaped.
+	#[default = "uid={username},ou=people,dc=example,dc=com"]
+	pub user_dn:         String,
+	
+	/// The DN of a service account to bind as when looking up users and
+	/// groups. If this is not given, groups are looked up as the user when
+	/// they log in, and users and groups are looked up anonymously otherwise.
+	pub bind_dn:         Option<String>,
+	
+	/// The password of the service account.
+	pub bind_password:   Option<String>,
+	
+	/// The base DN to search for groups under. If this is not given, groups
+	/// are not looked up.
+	pub group_base:      Option<String>,
+	
+	/// The filter used to find the groups a user is a member of. `{dn}` is
+	/// replaced with the user's DN, and `{username}` with the username, both
+	/// escaped.
+	#[default = "(member={dn})"]
+	pub group_filter:    String,
+	
+	/// The attribute of each group that identifies it.
+	#[default = "cn"]
+	pub group_attribute: String,
+	
+	/// The roles to give users, keyed by the names of the groups they are
+	/// members of. Groups that are not listed here are ignored.
+	#[default(HashMap::new())]
+	pub role_map:        HashMap<String, Vec<String>>,
+	
+	/// The maximum number of idle connections to keep open to the server, for
+	/// reuse.
+	#[default = 4]
+	pub pool_size:       usize,
+	
+	/// The timeout for connecting to the server and for each operation, in
+	/// seconds.
+	#[default = 5]
+	pub timeout:         u64,
+	
+	/// How long to remember a user and their roles for, in seconds, before
+	/// looking them up in the directory again. This limits how long a user
+	/// who has been removed from the directory can carry on using a session.
+	#[default = 300]
+	pub cache_ttl:       u64,
+	
+	/// How long to remember that a user could not be found for, in seconds,
+	/// so that requests with unknown usernames do not each reach the server.
+	#[default = 30]
+	pub negative_ttl:    u64,
 }
 
 //		MarkdownContent															
@@ -67,11 +264,129 @@ pub struct MarkdownContent {
 	/// allows local Markdown content to be used to complement the baked-in
 	/// templates.
 	#[default(LoadingBehavior::Deny)]
-	pub behavior:   LoadingBehavior,
+	pub behavior:    LoadingBehavior,
 	
 	/// The path to the local, non-baked-in Markdown content.
 	#[default = "html"]
-	pub local_path: PathBuf,
+	pub local_path:  PathBuf,
+	
+	/// Whether to reload pages in the browser when the local Markdown files
+	/// backing them change. This is intended for use whilst authoring content,
+	/// and has no effect if local Markdown content is not allowed.
+	#[default = false]
+	pub live_reload: bool,
+	
+	/// The maximum number of local Markdown pages to keep parsed in memory, so
+	/// that they do not need to be parsed on every request. A page is parsed
+	/// again if the file backing it changes. Setting this to zero disables the
+	/// cache.
+	#[default = 100]
+	pub cache_size:  usize,
+	
+	/// The options controlling how local Markdown content is converted to
+	/// HTML. Baked-in content is converted according to `Rustmark.toml` at
+	/// build time instead.
+	pub options:     MarkdownOptions,
+}
+
+//		OidcConfig																
+/// The configuration options for logging in using OpenID Connect.
+/// 
+/// Users who log in this way do not need to be in the user store. Their
+/// username and roles are taken from the claims in the ID token issued by the
+/// provider.
+/// 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SmartDefault)]
+pub struct OidcConfig {
+	//		Public properties													
+	/// Whether logging in using OpenID Connect is enabled.
+	#[default = false]
+	pub enabled:        bool,
+	
+	/// The URL of the provider, as given in the `iss` claim of its tokens. The
+	/// provider's configuration is discovered from this.
+	pub issuer:         String,
+	
+	/// The client ID registered with the provider.
+	pub client_id:      String,
+	
+	/// The client secret registered with the provider.
+	pub client_secret:  String,
+	
+	/// The URL the provider redirects back to after logging in. This must be
+	/// the public URL of the `/auth/oidc/callback` route, and must be
+	/// registered with the provider.
+	#[default = "http://127.0.0.1:8000/auth/oidc/callback"]
+	pub redirect_url:   String,
+	
+	/// The scopes to request. The `openid` scope is always requested.
+	#[default(vec!["profile".to_owned(), "email".to_owned()])]
+	pub scopes:         Vec<String>,
+	
+	/// The claim to use as the username.
+	#[default = "preferred_username"]
+	pub username_claim: String,
+	
+	/// The claim containing the groups or roles the user has at the provider.
+	/// This can be a single string or a list of strings.
+	#[default = "groups"]
+	pub roles_claim:    String,
+	
+	/// The roles to give users, keyed by the values of the roles claim. Values
+	/// that are not listed here are ignored.
+	#[default(HashMap::new())]
+	pub role_map:       HashMap<String, Vec<String>>,
+	
+	/// The text of the button shown on the login page.
+	#[default = "Log in with SSO"]
+	pub label:          String,
+}
+
+//		TokenConfig																
+/// The configuration options for an API token.
+/// 
+/// Tokens are sent in an `Authorization: Bearer` header, and allow access to
+/// the protected routes covered by their scopes, without logging in.
+/// 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SmartDefault)]
+#[serde(default)]
+pub struct TokenConfig {
+	//		Public properties													
+	/// The SHA-256 hash of the token, encoded as unpadded URL-safe base64. The
+	/// token itself is never stored.
+	pub hash:    String,
+	
+	/// The parts of the application that the token can be used to access.
+	#[default(vec![TokenScope::Content])]
+	pub scopes:  Vec<TokenScope>,
+	
+	/// The roles given to the token, which determine the content it can
+	/// access.
+	#[default(Vec::new())]
+	pub roles:   Vec<String>,
+	
+	/// When the token expires. If this is not given, the token does not expire.
+	pub expires: Option<DateTime<Utc>>,
+}
+
+//		UserStoreConfig															
+/// The configuration options for where users are loaded from.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SmartDefault)]
+pub struct UserStoreConfig {
+	//		Public properties													
+	/// The source of users. Files are checked for changes whenever a user logs
+	/// in, so that users can be added or removed without restarting the
+	/// application.
+	pub source:         UserSource,
+	
+	/// The path to the file containing the users, when the source is a file.
+	#[default = "users.toml"]
+	pub path:           PathBuf,
+	
+	/// Whether users in the `users` section of the main configuration can
+	/// also log in, when the source is something else.
+	#[default = false]
+	pub include_config: bool,
 }
 
 
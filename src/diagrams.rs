@@ -0,0 +1,411 @@
+//! Diagram rendering functionality.
+//! 
+//! Fenced code blocks with a `mermaid` or `dot` info string are rendered to
+//! inline SVG, so that diagrams can be kept as text alongside the content that
+//! describes them, rather than exported as images. Rendering happens on the
+//! server, at build time for the baked-in content, and at runtime for local
+//! content, so no scripts need to be loaded by the browser. If a diagram cannot
+//! be rendered, its source is shown instead.
+//! 
+//! Rendering is performed by the [`DiagramsProcessor`], which is one of the
+//! built-in processors in the [`Pipeline`](super::pipeline::Pipeline).
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/diagrams.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use super::pipeline::{PipelineError, Processor};
+use core::{
+	fmt::{Display, Formatter, Result as FmtResult, Write as _},
+	panic::AssertUnwindSafe,
+};
+use layout::{
+	backends::svg::SVGWriter,
+	gv::{DotParser, GraphBuilder},
+};
+use nipper::{Document, Selection};
+use rubedo::sugar::s;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::{
+	collections::HashMap,
+	panic::catch_unwind,
+	sync::Mutex,
+};
+
+
+
+//		Enums																											
+
+//		DiagramKind																
+/// The languages that diagrams can be written in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum DiagramKind {
+	/// The Graphviz DOT language.
+	Dot,
+	
+	/// The Mermaid language.
+	Mermaid,
+}
+
+//󰭅		DiagramKind																
+impl DiagramKind {
+	//		from_language														
+	/// Finds the kind of diagram for a code block language, if any.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `language` - The language given in the info string of the code block.
+	/// 
+	#[must_use]
+	pub fn from_language(language: &str) -> Option<Self> {
+		match language {
+			"dot" | "graphviz" => Some(Self::Dot),
+			"mermaid"          => Some(Self::Mermaid),
+			_                  => None,
+		}
+	}
+}
+
+//󰭅		Display																	
+impl Display for DiagramKind {
+	//		fmt																	
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		let name = match *self {
+			Self::Dot     => "dot",
+			Self::Mermaid => "mermaid",
+		};
+		write!(f, "{name}")
+	}
+}
+
+
+
+//		Structs																											
+
+//		DiagramsOptions															
+/// The options for the [`DiagramsProcessor`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct DiagramsOptions {
+	//		Public properties													
+	/// The maximum number of rendered diagrams to keep, so that they do not
+	/// need to be rendered again when a page is parsed again. When the cache
+	/// is full, it is emptied. Setting this to zero disables the cache.
+	pub cache_size: usize,
+	
+	/// Whether to render Graphviz DOT diagrams.
+	pub dot:        bool,
+	
+	/// Whether to render Mermaid diagrams.
+	pub mermaid:    bool,
+}
+
+//󰭅		Default																	
+impl Default for DiagramsOptions {
+	//		default																
+	fn default() -> Self {
+		Self {
+			cache_size: 100,
+			dot:        true,
+			mermaid:    true,
+		}
+	}
+}
+
+//		DiagramsProcessor														
+/// Renders diagram code blocks to inline SVG.
+/// 
+/// Each rendered diagram replaces its code block with a `<figure>` element
+/// with the `diagram` class, and a class for the kind of diagram, such as
+/// `diagram-mermaid`. The IDs inside each SVG are prefixed, so that several
+/// diagrams can appear on the same page. Code blocks that cannot be rendered
+/// are left in place, with the `diagram-error` class added, and the error
+/// given as their title.
+/// 
+/// Rendered diagrams are cached by source, so that editing a page does not
+/// cause the diagrams that have not changed to be rendered again.
+/// 
+#[derive(Debug, Default)]
+pub struct DiagramsProcessor {
+	//		Private properties													
+	/// The options for the processor.
+	options: DiagramsOptions,
+	
+	/// The results of rendering diagrams, keyed by kind and source.
+	cache:   Mutex<HashMap<(DiagramKind, String), Result<String, String>>>,
+}
+
+//󰭅		DiagramsProcessor														
+impl DiagramsProcessor {
+	//		new																	
+	/// Creates a new diagrams processor.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `options` - The options for the processor.
+	/// 
+	#[must_use]
+	pub fn new(options: DiagramsOptions) -> Self {
+		Self {
+			options,
+			cache:   Mutex::new(HashMap::new()),
+		}
+	}
+	
+	//		from_options														
+	/// Creates a new diagrams processor from the options given in the pipeline
+	/// configuration.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `options` - The options, which can be empty to use the defaults.
+	/// 
+	/// # Errors
+	/// 
+	/// If the options are not valid, an error will be returned.
+	/// 
+	pub fn from_options(options: &JsonValue) -> Result<Self, PipelineError> {
+		if options.is_null() {
+			return Ok(Self::default());
+		}
+		serde_json::from_value(options.clone())
+			.map(Self::new)
+			.map_err(|err| PipelineError::InvalidOptions(s!("diagrams"), err.to_string()))
+	}
+	
+	//		enabled																
+	/// Whether a kind of diagram is rendered.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `kind` - The kind of diagram.
+	/// 
+	const fn enabled(&self, kind: DiagramKind) -> bool {
+		match kind {
+			DiagramKind::Dot     => self.options.dot,
+			DiagramKind::Mermaid => self.options.mermaid,
+		}
+	}
+	
+	//		render																
+	/// Renders a diagram, using the cache if possible.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `kind`   - The kind of diagram.
+	/// * `source` - The source of the diagram.
+	/// 
+	/// # Errors
+	/// 
+	/// If the diagram cannot be rendered, the reason will be returned.
+	/// 
+	fn render(&self, kind: DiagramKind, source: &str) -> Result<String, String> {
+		if self.options.cache_size == 0 {
+			return render_diagram(kind, source);
+		}
+		let key    = (kind, source.to_owned());
+		if let Some(result) = self.cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+			return result;
+		}
+		let result = render_diagram(kind, source);
+		if let Ok(mut cache) = self.cache.lock() {
+			if cache.len() >= self.options.cache_size {
+				cache.clear();
+			}
+			drop(cache.insert(key, result.clone()));
+		}
+		result
+	}
+}
+
+//󰭅		Processor																
+impl Processor for DiagramsProcessor {
+	//		name																
+	fn name(&self) -> &'static str {
+		"diagrams"
+	}
+	
+	//		order																
+	fn order(&self) -> i32 {
+		400
+	}
+	
+	//		process																
+	fn process(&self, document: &Document) {
+		let mut count: usize = 0;
+		for mut pre in document.select("pre").iter() {
+			let Some(kind) = diagram_kind(&pre).filter(|&kind| self.enabled(kind)) else {
+				continue;
+			};
+			match self.render(kind, &pre.text()) {
+				Ok(svg)  => {
+					count = count.saturating_add(1);
+					pre.replace_with_html(format!(
+						r#"<figure class="diagram diagram-{kind}">{}</figure>"#,
+						prefix_ids(&svg, &format!("diagram-{count}-")),
+					));
+				},
+				Err(err) => {
+					pre.add_class("diagram-error");
+					pre.set_attr("title", &format!("Could not render {kind} diagram: {err}"));
+				},
+			}
+		}
+	}
+}
+
+
+
+//		Functions																										
+
+//		diagram_kind															
+/// Finds the kind of diagram in a code block, if any.
+/// 
+/// The language is given either by a `language-*` class on the `<code>`
+/// element, or by a `lang` attribute on the `<pre>` element, depending on the
+/// Markdown options.
+/// 
+/// # Parameters
+/// 
+/// * `pre` - The `<pre>` element of the code block.
+/// 
+fn diagram_kind(pre: &Selection<'_>) -> Option<DiagramKind> {
+	let classes = pre.select("code").attr("class").map(|class| class.to_string()).unwrap_or_default();
+	pre.attr("lang")
+		.and_then(|lang| DiagramKind::from_language(&lang))
+		.or_else(|| classes
+			.split_whitespace()
+			.filter_map(|class| class.strip_prefix("language-"))
+			.find_map(DiagramKind::from_language)
+		)
+}
+
+//		inline_styles															
+/// Replaces the classes in an SVG with the styles defined for them.
+/// 
+/// The Graphviz renderer styles text using classes defined in a `<style>`
+/// element, which sanitisation removes, as stylesheets can affect the whole
+/// page. The declarations of each class are therefore added to the elements
+/// that use it as presentation attributes, and the `<style>` element is
+/// removed, so that the text is styled the same either way.
+/// 
+/// # Parameters
+/// 
+/// * `svg` - The SVG.
+/// 
+fn inline_styles(svg: &str) -> String {
+	let Some((before, rest))  = svg.split_once("<style>") else {
+		return svg.to_owned();
+	};
+	let Some((styles, after)) = rest.split_once("</style>") else {
+		return svg.to_owned();
+	};
+	let mut output            = format!("{before}{}", after.trim_start_matches('\n'));
+	for (selector, declarations) in styles.lines().filter_map(|rule| rule.split_once('{')) {
+		let Some(class) = selector.trim().strip_prefix('.') else {
+			continue;
+		};
+		let attributes  = declarations
+			.trim()
+			.trim_end_matches('}')
+			.split(';')
+			.filter_map(|declaration| declaration.split_once(':'))
+			.fold(String::new(), |mut attributes, (property, value)| {
+				_ = write!(attributes, r#" {}="{}""#, property.trim(), value.trim());
+				attributes
+			})
+		;
+		output = output.replace(&format!(r#" class="{class}""#), &attributes);
+	}
+	output
+}
+
+//		prefix_ids																
+/// Adds a prefix to the IDs in an SVG, and to the references to them.
+/// 
+/// This also works for HTML, including any SVGs embedded in it, and so can be
+/// used to stop the IDs in several pages from clashing when they are combined.
+/// 
+/// # Parameters
+/// 
+/// * `svg`    - The SVG.
+/// * `prefix` - The prefix to add.
+/// 
+#[must_use]
+pub fn prefix_ids(svg: &str, prefix: &str) -> String {
+	let ids        = svg
+		.split(r#" id=""#)
+		.skip(1)
+		.filter_map(|rest| rest.split('"').next())
+		.filter(|id| !id.is_empty())
+		.map(ToOwned::to_owned)
+		.collect::<Vec<_>>()
+	;
+	let mut output = svg.to_owned();
+	for id in ids {
+		output = output
+			.replace(&format!(r#" id="{id}""#),     &format!(r#" id="{prefix}{id}""#))
+			.replace(&format!("url(#{id})"),        &format!("url(#{prefix}{id})"))
+			.replace(&format!(r##"href="#{id}""##), &format!(r##"href="#{prefix}{id}""##))
+		;
+	}
+	output
+}
+
+//		render_diagram															
+/// Renders a diagram to SVG.
+/// 
+/// The renderers can panic on some unexpected input, so any panic is caught
+/// and treated as a failure to render.
+/// 
+/// # Parameters
+/// 
+/// * `kind`   - The kind of diagram.
+/// * `source` - The source of the diagram.
+/// 
+/// # Errors
+/// 
+/// If the diagram cannot be rendered, the reason will be returned.
+/// 
+pub fn render_diagram(kind: DiagramKind, source: &str) -> Result<String, String> {
+	catch_unwind(AssertUnwindSafe(|| match kind {
+		DiagramKind::Dot     => render_dot(source),
+		DiagramKind::Mermaid => mermaid_rs_renderer::render(source).map_err(|err| err.to_string()),
+	})).unwrap_or_else(|_| Err(s!("The renderer failed unexpectedly")))
+}
+
+//		render_dot																
+/// Renders a Graphviz DOT diagram to SVG.
+/// 
+/// # Parameters
+/// 
+/// * `source` - The source of the diagram.
+/// 
+/// # Errors
+/// 
+/// If the diagram cannot be parsed, the reason will be returned.
+/// 
+fn render_dot(source: &str) -> Result<String, String> {
+	let graph       = DotParser::new(source).process()?;
+	let mut builder = GraphBuilder::new();
+	builder.visit_graph(&graph);
+	let mut writer  = SVGWriter::new();
+	builder.get().do_it(false, false, false, &mut writer);
+	let svg         = writer.finalize();
+	//	The XML declaration is not valid inside an HTML document.
+	Ok(inline_styles(svg.find("<svg").and_then(|start| svg.get(start..)).unwrap_or(&svg)))
+}
+
+
@@ -0,0 +1,266 @@
+//! Error handling for the application.
+//! 
+//! Requests for pages can fail because the page does not exist, or because it
+//! cannot be loaded or rendered. These failures are described by [`PageError`],
+//! which is logged, and shown to the user using the 404 and 500 error pages.
+//! When a page is not found, the similarly-named pages that the user is allowed
+//! to access are suggested.
+//! 
+//! Other responses with those status codes and no body are given the same error
+//! pages by [`error_layer()`], which also leaves the pages shown for a
+//! [`PageError`] as they are.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/errors.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	print::PrintError,
+	state::AppState,
+};
+use axum::{
+	body::Body,
+	extract::State,
+	http::{HeaderValue, Request, StatusCode},
+	middleware::Next,
+	response::{Html, IntoResponse as _, Response},
+};
+use core::iter;
+use rubedo::{http::UnpackedResponseBody, sugar::s};
+use rustmark::CompiledPageError;
+use serde::Serialize;
+use std::sync::Arc;
+use strsim::normalized_damerau_levenshtein;
+use terracotta::app::{
+	errors::AppError,
+	state::StateProvider as _,
+};
+use tera::Context;
+use thiserror::Error as ThisError;
+use tracing::{error, info};
+
+
+
+//		Constants																										
+
+/// The header used to mark a response whose error page has already been
+/// rendered. This is the same header used by Terracotta, which removes it from
+/// `500 Internal Server Error` responses.
+const ERROR_HANDLED_HEADER: &str = "error-handled";
+
+/// The maximum number of similarly-named pages to suggest when a page is not
+/// found.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// How similar the path of a page needs to be to the path requested for it to
+/// be suggested, from 0.0 to 1.0.
+const SUGGESTION_THRESHOLD: f64 = 0.6;
+
+
+
+//		Enums																											
+
+//		PageError																
+/// Errors that can occur when showing a page.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum PageError {
+	/// The compiled page could not be loaded.
+	#[error("Failed to load compiled page {0}: {1}")]
+	CouldNotLoad(String, CompiledPageError),
+	
+	/// The printable document could not be created.
+	#[error("Failed to print {0}: {1}")]
+	CouldNotPrint(String, PrintError),
+	
+	/// The page could not be rendered using the HTML templates.
+	#[error("Failed to render page {0}: {1}")]
+	CouldNotRender(String, Box<AppError>),
+	
+	/// The page does not exist.
+	#[error("Page not found: {0}")]
+	NotFound(String),
+}
+
+//󰭅		PageError																
+impl PageError {
+	//		path																
+	/// The path of the page the error relates to.
+	#[must_use]
+	pub fn path(&self) -> &str {
+		match *self {
+			Self::CouldNotLoad(ref path, _)   |
+			Self::CouldNotPrint(ref path, _)  |
+			Self::CouldNotRender(ref path, _) |
+			Self::NotFound(ref path)          => path,
+		}
+	}
+	
+	//		status																
+	/// The HTTP status code to respond with.
+	#[must_use]
+	pub const fn status(&self) -> StatusCode {
+		match *self {
+			Self::NotFound(_)         => StatusCode::NOT_FOUND,
+			Self::CouldNotLoad(..)    |
+			Self::CouldNotPrint(..)   |
+			Self::CouldNotRender(..)  => StatusCode::INTERNAL_SERVER_ERROR,
+		}
+	}
+	
+	//		respond																
+	/// Logs the error, and creates a response showing the appropriate error
+	/// page.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `state` - The application state.
+	/// * `roles` - The roles of the current user, which limit the pages
+	///             suggested when a page is not found.
+	/// 
+	pub async fn respond(self, state: &AppState, roles: &[String]) -> Response {
+		let status      = self.status();
+		let mut context = Context::new();
+		let template    = if status == StatusCode::NOT_FOUND {
+			info!("{self}");
+			context.insert("Title",       &format!("Not found - {}", &state.config.title));
+			context.insert("Suggestions", &suggestions(state, self.path(), roles));
+			"404-notfound"
+		} else {
+			error!("{self}");
+			context.insert("Title",       &format!("Server error - {}", &state.config.title));
+			"500-error"
+		};
+		match state.render(template, &context).await {
+			Ok(html) => (
+				status,
+				[(ERROR_HANDLED_HEADER, HeaderValue::from_static("gracefully"))],
+				Html(html),
+			).into_response(),
+			Err(err) => {
+				error!("Failed to render error page for {}: {err}", self.path());
+				status.into_response()
+			},
+		}
+	}
+}
+
+
+
+//		Structs																											
+
+//		Suggestion																
+/// A page suggested when the page requested is not found.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct Suggestion {
+	//		Private properties													
+	/// The path of the page, relative to the content root.
+	path:  String,
+	
+	/// The title of the page.
+	title: String,
+}
+
+
+
+//		Functions																										
+
+//		error_layer																
+/// Shows the error pages for responses that need them.
+/// 
+/// This takes the place of Terracotta's graceful error handling, which renders
+/// the error pages for all `404 Not Found` and `500 Internal Server Error`
+/// responses. Responses that already show an error page, such as those for a
+/// [`PageError`], are left as they are, so that they can give more detail.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `request` - The request.
+/// * `next`    - The next middleware.
+/// 
+/// # Errors
+/// 
+/// If there is an error rendering the error page, an error will be returned.
+/// 
+pub async fn error_layer(
+	State(state): State<Arc<AppState>>,
+	request:      Request<Body>,
+	next:         Next,
+) -> Result<Response, AppError> {
+	let response          = next.run(request).await;
+	let (mut parts, body) = response.into_parts();
+	if parts.headers.contains_key(ERROR_HANDLED_HEADER) {
+		//	The header is removed from 500 responses by the final error layer
+		if parts.status != StatusCode::INTERNAL_SERVER_ERROR {
+			drop(parts.headers.remove(ERROR_HANDLED_HEADER));
+		}
+		return Ok((parts, body).into_response());
+	}
+	let template          = match parts.status {
+		StatusCode::NOT_FOUND             => "404-notfound",
+		StatusCode::INTERNAL_SERVER_ERROR => {
+			error!("Internal server error: {}", UnpackedResponseBody::from(body));
+			drop(parts.headers.insert(ERROR_HANDLED_HEADER, HeaderValue::from_static("gracefully")));
+			"500-error"
+		},
+		_                                 => return Ok((parts, body).into_response()),
+	};
+	drop(parts.headers.remove("content-length"));
+	drop(parts.headers.remove("content-type"));
+	let mut context       = Context::new();
+	context.insert("Title", &state.title());
+	Ok((parts, Html(state.render(template, &context).await?)).into_response())
+}
+
+//		suggestions																
+/// Finds the pages with paths similar to one that was not found.
+/// 
+/// Both the full path and the file name are compared, so that a page that has
+/// moved to another directory can still be found.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `path`  - The path that was not found, relative to the content root.
+/// * `roles` - The roles of the current user. Only pages they are allowed to
+///             access are suggested.
+/// 
+fn suggestions(state: &AppState, path: &str, roles: &[String]) -> Vec<Suggestion> {
+	let normalize   = |page: &str| page.trim_matches('/').trim_end_matches(".md").to_lowercase();
+	let file_name   = |page: &str| page.rsplit('/').next().unwrap_or(page).to_owned();
+	let wanted      = normalize(path);
+	let wanted_name = file_name(&wanted);
+	let mut scored  = iter::once(s!("index.md"))
+		.chain(state.nav.read().pages(""))
+		.filter(|candidate| candidate.as_str() != path && state.access.allows(candidate, roles))
+		.filter_map(|candidate| {
+			let name  = normalize(&candidate);
+			let score = normalized_damerau_levenshtein(&wanted, &name)
+				.max(normalized_damerau_levenshtein(&wanted_name, &file_name(&name)))
+			;
+			(score >= SUGGESTION_THRESHOLD).then_some((score, candidate))
+		})
+		.collect::<Vec<_>>()
+	;
+	scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+	scored
+		.into_iter()
+		.take(MAX_SUGGESTIONS)
+		.map(|(_, page_path)| Suggestion {
+			title: state.page(&page_path).ok().flatten().map_or_else(|| page_path.clone(), |page| page.title.clone()),
+			path:  page_path,
+		})
+		.collect()
+}
+
+
@@ -0,0 +1,479 @@
+//! Static site export functionality.
+//! 
+//! The content can be exported as a static website, for hosting on a plain
+//! file server, or handing over to be read offline. Every page that would be
+//! served, whether baked-in or local, is rendered using the HTML templates and
+//! written to an HTML file, and the static assets and protected files are
+//! copied alongside. Links are rewritten to be relative, and to point to the
+//! HTML files instead of the Markdown pages, so that the website can be opened
+//! from any location, including directly from the filesystem.
+//! 
+//! Features that need the server, such as searching and logging in, are left
+//! out of the exported pages.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/export.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	handlers::page_context,
+	state::{AppState, PageAccess},
+};
+use include_dir::{Dir, DirEntry, File as BakedFile};
+use rustmark::{CompiledPageError, links::is_external};
+use std::{
+	fs::{File, self},
+	io::{Error as IoError, self},
+	path::{Path, PathBuf},
+};
+use terracotta::app::{
+	config::LoadingBehavior,
+	errors::AppError,
+	state::StateProvider as _,
+};
+use thiserror::Error as ThisError;
+use walkdir::WalkDir;
+use zip::{
+	CompressionMethod,
+	ZipWriter,
+	result::ZipError,
+	write::SimpleFileOptions,
+};
+
+
+
+//		Constants																										
+
+/// The attributes containing URLs that are rewritten, including the leading
+/// space and the equals sign.
+const LINK_ATTRIBUTES: [&str; 2] = [" href=", " src="];
+
+
+
+//		Enums																											
+
+//		ExportError																
+/// Errors that can occur when exporting the content.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum ExportError {
+	/// A directory could not be created.
+	#[error("Could not create directory {0}: {1}")]
+	CouldNotCreateDirectory(PathBuf, IoError),
+	
+	/// A page could not be loaded.
+	#[error("Could not load page {0}: {1}")]
+	CouldNotLoadPage(String, CompiledPageError),
+	
+	/// A local file could not be read.
+	#[error("Could not read file {0}: {1}")]
+	CouldNotReadFile(PathBuf, IoError),
+	
+	/// A page could not be rendered using the HTML templates.
+	#[error("Could not render page {0}: {1}")]
+	CouldNotRenderPage(String, Box<AppError>),
+	
+	/// A file could not be written.
+	#[error("Could not write file {0}: {1}")]
+	CouldNotWriteFile(PathBuf, IoError),
+	
+	/// The zip file could not be written.
+	#[error("Could not write zip file {0}: {1}")]
+	CouldNotWriteZip(PathBuf, ZipError),
+	
+	/// The output directory already has something in it.
+	#[error("Output directory {0} is not empty")]
+	OutputNotEmpty(PathBuf),
+}
+
+
+
+//		Structs																											
+
+//		ExportOptions															
+/// The options for exporting the content.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportOptions {
+	//		Public properties													
+	/// The directory to write the website to. This must not exist, or be
+	/// empty.
+	pub output: PathBuf,
+	
+	/// The roles to export the content for. Only pages and files that a user
+	/// with these roles would be allowed to access are exported.
+	pub roles:  Vec<String>,
+	
+	/// Whether to also create a zip file of the website, named after the
+	/// output directory.
+	pub zip:    bool,
+}
+
+//		ExportSummary															
+/// A summary of what was exported.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportSummary {
+	//		Public properties													
+	/// The number of pages exported.
+	pub pages: usize,
+	
+	/// The number of other files copied.
+	pub files: usize,
+	
+	/// The zip file created, if any.
+	pub zip:   Option<PathBuf>,
+}
+
+
+
+//		Functions																										
+
+//		export																	
+/// Exports the content as a static website.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `options` - The export options.
+/// 
+/// # Errors
+/// 
+/// If the output directory is not empty, or any page cannot be loaded,
+/// rendered, or written, or any file cannot be copied, an error is returned.
+/// 
+pub async fn export(state: &AppState, options: &ExportOptions) -> Result<ExportSummary, ExportError> {
+	let output      = &options.output;
+	if fs::read_dir(output).is_ok_and(|mut entries| entries.next().is_some()) {
+		return Err(ExportError::OutputNotEmpty(output.clone()));
+	}
+	fs::create_dir_all(output).map_err(|err| ExportError::CouldNotCreateDirectory(output.clone(), err))?;
+	let mut summary = ExportSummary::default();
+	
+	//		Pages																
+	for path in page_paths(state) {
+		let page_access = state.accessible_page(&path, &options.roles)
+			.map_err(|err| ExportError::CouldNotLoadPage(path.clone(), err))?
+		;
+		let PageAccess::Allowed(page) = page_access else {
+			continue;
+		};
+		let (template, mut context) = page_context(state, &path, &page, &options.roles);
+		context.insert("Export", &true);
+		context.insert("Roles",  &options.roles);
+		let html = state.render(template, &context).await
+			.map_err(|err| ExportError::CouldNotRenderPage(path.clone(), Box::new(err)))?
+		;
+		let depth = path.matches('/').count();
+		write_file(&output.join(html_path(&path)), rewrite_links(&html, depth).as_bytes())?;
+		summary.pages = summary.pages.saturating_add(1);
+	}
+	
+	//		Files																
+	let assets      = &state.config.assets;
+	summary.files   = copy_files(
+		&state.assets_dir,
+		&assets.public_assets.local_path,
+		assets.public_assets.behavior,
+		output,
+		|_| true,
+	)?.saturating_add(copy_files(
+		&state.content_dir,
+		&assets.protected_assets.local_path,
+		assets.protected_assets.behavior,
+		output,
+		|path| !is_markdown(path) && state.access.allows(path, &options.roles),
+	)?);
+	
+	//		Zip file															
+	if options.zip {
+		summary.zip = Some(zip_directory(output)?);
+	}
+	Ok(summary)
+}
+
+//		copy_files																
+/// Copies baked-in and local files to the output directory.
+/// 
+/// Local files are copied according to the loading behaviour, in the same way
+/// that they would be served.
+/// 
+/// # Parameters
+/// 
+/// * `dir`        - The directory of baked-in files.
+/// * `local_path` - The directory of local files.
+/// * `behavior`   - The loading behaviour for the local files.
+/// * `output`     - The output directory.
+/// * `include`    - Determines whether a file should be copied, given its path
+///                  relative to the directory.
+/// 
+/// # Returns
+/// 
+/// The number of files copied.
+/// 
+/// # Errors
+/// 
+/// If any file cannot be read or written, an error is returned.
+/// 
+fn copy_files<F>(
+	dir:        &Dir<'static>,
+	local_path: &Path,
+	behavior:   LoadingBehavior,
+	output:     &Path,
+	include:    F,
+) -> Result<usize, ExportError>
+where
+	F: Fn(&str) -> bool,
+{
+	let mut count = 0_usize;
+	for file in dir_files(dir) {
+		let path = file.path().to_string_lossy().replace('\\', "/");
+		let copy = include(&path) && match behavior {
+			LoadingBehavior::Deny | LoadingBehavior::Supplement => true,
+			LoadingBehavior::Override                           => !local_path.join(&path).exists(),
+		};
+		if copy {
+			write_file(&output.join(&path), file.contents())?;
+			count = count.saturating_add(1);
+		}
+	}
+	if behavior == LoadingBehavior::Deny {
+		return Ok(count);
+	}
+	for entry in WalkDir::new(local_path).follow_links(true).into_iter().filter_map(Result::ok) {
+		if entry.file_type().is_dir() {
+			continue;
+		}
+		let Some(path) = entry.path().strip_prefix(local_path).ok().map(|p| p.to_string_lossy().replace('\\', "/")) else {
+			continue;
+		};
+		if !include(&path) || (behavior == LoadingBehavior::Supplement && dir.get_file(&path).is_some()) {
+			continue;
+		}
+		let contents = fs::read(entry.path()).map_err(|err| ExportError::CouldNotReadFile(entry.path().to_owned(), err))?;
+		write_file(&output.join(&path), &contents)?;
+		count = count.saturating_add(1);
+	}
+	Ok(count)
+}
+
+//		dir_files																
+/// Lists all the files in a baked-in directory, including subdirectories.
+/// 
+/// # Parameters
+/// 
+/// * `dir` - The directory.
+/// 
+fn dir_files<'dir>(dir: &'dir Dir<'static>) -> Vec<&'dir BakedFile<'static>> {
+	dir.entries()
+		.iter()
+		.flat_map(|entry| match *entry {
+			DirEntry::Dir(ref subdir) => dir_files(subdir),
+			DirEntry::File(ref file)  => vec![file],
+		})
+		.collect()
+}
+
+//		html_path																
+/// Converts the path of a Markdown page into the path of its HTML file.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path of the page.
+/// 
+fn html_path(path: &str) -> String {
+	path.strip_suffix(".md").map_or_else(|| path.to_owned(), |stem| format!("{stem}.html"))
+}
+
+//		is_markdown																
+/// Determines whether a path is that of a Markdown page.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path.
+/// 
+fn is_markdown(path: &str) -> bool {
+	Path::new(path).extension().is_some_and(|ext| ext == "md")
+}
+
+//		page_paths																
+/// Lists the paths of all the pages that would be served.
+/// 
+/// This covers both the baked-in and local pages, according to the configured
+/// loading behaviour, with each page listed once.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+fn page_paths(state: &AppState) -> Vec<String> {
+	let mut paths = dir_files(&state.content_dir)
+		.into_iter()
+		.map(|file| file.path().to_string_lossy().replace('\\', "/"))
+		.filter(|path| is_markdown(path))
+		.collect::<Vec<_>>()
+	;
+	let root      = &state.config.markdown.local_path;
+	if state.config.markdown.behavior != LoadingBehavior::Deny {
+		paths.extend(
+			WalkDir::new(root)
+				.follow_links(true)
+				.into_iter()
+				.filter_map(Result::ok)
+				.filter(|entry| !entry.file_type().is_dir())
+				.filter_map(|entry| entry.path().strip_prefix(root).ok().map(|p| p.to_string_lossy().replace('\\', "/")))
+				.filter(|path| is_markdown(path) && state.is_local(path))
+		);
+	}
+	paths.sort();
+	paths.dedup();
+	paths
+}
+
+//		relative_url															
+/// Converts a URL used by the application into one that works in the exported
+/// website.
+/// 
+/// URLs with a scheme, and links to anchors in the same page, are left as they
+/// are. Absolute paths are made relative to the page, links to Markdown pages
+/// are changed to point to the HTML files, and links to directories are changed
+/// to point to their index files. A path is taken to be a directory if it ends
+/// with a slash, or if its last segment has no extension. Query strings are
+/// removed, as they have no meaning for static files.
+/// 
+/// # Parameters
+/// 
+/// * `url`   - The URL.
+/// * `depth` - The number of directories the page is in, below the root.
+/// 
+fn relative_url(url: &str, depth: usize) -> String {
+	if url.is_empty() || url.starts_with('#') || is_external(url) {
+		return url.to_owned();
+	}
+	let (target, fragment) = url.split_once('#').map_or((url, ""), |(target, fragment)| (target, fragment));
+	let without_query      = target.split_once('?').map_or(target, |(path, _)| path);
+	let (absolute, file)   = without_query.strip_prefix('/').map_or((false, without_query), |file| (true, file));
+	let mut path           = file.to_owned();
+	if path.ends_with('/') || (absolute && path.is_empty()) {
+		path.push_str("index.html");
+	} else if !path.is_empty() && Path::new(&path).extension().is_none() {
+		path.push_str("/index.html");
+	}
+	let prefix             = if absolute { "../".repeat(depth) } else { String::new() };
+	let mut output         = format!("{prefix}{}", html_path(&path));
+	if !fragment.is_empty() {
+		output.push('#');
+		output.push_str(fragment);
+	}
+	output
+}
+
+//		rewrite_links															
+/// Rewrites the URLs in some HTML so that they work in the exported website.
+/// 
+/// The URLs in `href` and `src` attributes are rewritten, whether their values
+/// are in double or single quotes. Unquoted values are left as they are, as
+/// neither the Markdown renderer nor the templates produce them. See
+/// [`relative_url()`].
+/// 
+/// # Parameters
+/// 
+/// * `html`  - The HTML.
+/// * `depth` - The number of directories the page is in, below the root.
+/// 
+fn rewrite_links(html: &str, depth: usize) -> String {
+	let mut output = String::with_capacity(html.len());
+	let mut rest   = html;
+	while let Some((start, attribute)) = LINK_ATTRIBUTES
+		.iter()
+		.filter_map(|attribute| rest.find(attribute).map(|start| (start, attribute)))
+		.min_by_key(|&(start, _)| start)
+	{
+		let (before, tail)   = rest.split_at(start.saturating_add(attribute.len()));
+		output.push_str(before);
+		let Some(quote)      = tail.chars().next().filter(|&quote| quote == '"' || quote == '\'') else {
+			rest = tail;
+			continue;
+		};
+		let (opening, value) = tail.split_at(quote.len_utf8());
+		let (url, after)     = value.split_at(value.find(quote).unwrap_or(value.len()));
+		output.push_str(opening);
+		output.push_str(&relative_url(url, depth));
+		rest = after;
+	}
+	output.push_str(rest);
+	output
+}
+
+//		write_file																
+/// Writes a file, creating its directory if necessary.
+/// 
+/// # Parameters
+/// 
+/// * `path`     - The path of the file.
+/// * `contents` - The contents of the file.
+/// 
+/// # Errors
+/// 
+/// If the directory cannot be created, or the file cannot be written, an error
+/// is returned.
+/// 
+fn write_file(path: &Path, contents: &[u8]) -> Result<(), ExportError> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent).map_err(|err| ExportError::CouldNotCreateDirectory(parent.to_owned(), err))?;
+	}
+	fs::write(path, contents).map_err(|err| ExportError::CouldNotWriteFile(path.to_owned(), err))
+}
+
+//		zip_directory															
+/// Creates a zip file of a directory.
+/// 
+/// The zip file is written next to the directory, with the same name, and
+/// contains the directory itself, so that it is unpacked into a single
+/// directory.
+/// 
+/// # Parameters
+/// 
+/// * `dir` - The directory.
+/// 
+/// # Returns
+/// 
+/// The path of the zip file.
+/// 
+/// # Errors
+/// 
+/// If any file cannot be read, or the zip file cannot be written, an error is
+/// returned.
+/// 
+fn zip_directory(dir: &Path) -> Result<PathBuf, ExportError> {
+	let zip_path = dir.with_extension("zip");
+	let zip_err  = |err: ZipError| ExportError::CouldNotWriteZip(zip_path.clone(), err);
+	let name     = dir.file_name().map_or_else(|| "export".into(), |name| name.to_string_lossy());
+	let file     = File::create(&zip_path).map_err(|err| ExportError::CouldNotWriteFile(zip_path.clone(), err))?;
+	let mut zip  = ZipWriter::new(file);
+	let options  = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+	for entry in WalkDir::new(dir).sort_by_file_name().into_iter().filter_map(Result::ok) {
+		let Ok(relative) = entry.path().strip_prefix(dir) else {
+			continue;
+		};
+		let entry_name = Path::new(name.as_ref()).join(relative).to_string_lossy().replace('\\', "/");
+		if entry.file_type().is_dir() {
+			zip.add_directory(entry_name, options).map_err(zip_err)?;
+			continue;
+		}
+		zip.start_file(entry_name, options).map_err(zip_err)?;
+		let mut source = File::open(entry.path()).map_err(|err| ExportError::CouldNotReadFile(entry.path().to_owned(), err))?;
+		_ = io::copy(&mut source, &mut zip).map_err(|err| zip_err(ZipError::Io(err)))?;
+	}
+	drop(zip.finish().map_err(zip_err)?);
+	Ok(zip_path)
+}
+
+
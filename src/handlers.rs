@@ -2,101 +2,721 @@
 
 
 
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/handlers.rs"]
+mod tests;
+
+
+
 //		Packages																										
 
-use crate::state::AppState;
-use axum::{
-	extract::State,
-	http::{StatusCode, Uri},
-	response::{Html, IntoResponse},
+use crate::{
+	access::content_path,
+	audit::AuditEvent,
+	auth::User,
+	errors::PageError,
+	oidc::{PendingLogin, SESSION_KEY as OIDC_SESSION_KEY},
+	print::{PrintError, print_document},
+	requests::{GetAuditParams, GetLoginParams, GetOidcCallbackParams, GetPageParams, GetSearchParams},
+	responses::{AppStatsResponse, PageResponse, SearchResponse, TokenStatsResponse},
+	state::{AppState, PageAccess},
+	utility::{ResponseFormat, negotiate},
 };
-use rustmark::{Heading, self};
-use std::{
-	fs,
-	sync::Arc,
+use axum::{
+	Json,
+	extract::{
+		Query,
+		State,
+		ws::{Message, WebSocket, WebSocketUpgrade},
+	},
+	http::{HeaderMap, StatusCode, Uri, header},
+	response::{Html, IntoResponse, Redirect, Response},
 };
+use rustmark::{CompiledPage, Heading};
+use std::{collections::BTreeMap, sync::Arc};
 use terracotta::{
-	app::config::LoadingBehavior,
+	app::{
+		errors::AppError,
+		state::StateProvider as _,
+	},
 	assets::handlers::get_protected_static_asset,
+	auth::{
+		handlers::get_login as get_base_login,
+		middleware::Context as AuthContext,
+	},
+	stats::handlers::get_stats as get_base_stats,
 };
 use tera::Context;
+use tokio::{
+	select,
+	sync::broadcast::{Receiver, error::RecvError},
+};
+use tower_sessions::Session;
+use tracing::{error, info, warn};
+
+
+
+//		Constants																										
+
+/// The default number of audit events to return.
+const DEFAULT_AUDIT_LIMIT:  usize = 100;
+
+/// The maximum number of audit events that can be requested.
+const MAX_AUDIT_LIMIT:      usize = 1_000;
+
+/// The default number of search results to return.
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// The maximum number of search results that can be requested.
+const MAX_SEARCH_LIMIT:     usize = 100;
+
+/// The templates that pages can choose in their front matter, in addition to
+/// those starting with [`PAGE_TEMPLATE_PREFIX`].
+const PAGE_TEMPLATES:       [&str; 2] = ["index", "page"];
+
+/// The prefix of the names of additional templates that pages can choose in
+/// their front matter.
+const PAGE_TEMPLATE_PREFIX: &str      = "page-";
 
 
 
 //		Functions																										
 
+//		get_audit																
+/// Shows recent requests for content, from the audit log.
+/// 
+/// Only users with one of the roles configured for the audit log can view it.
+/// The events are rendered as an HTML page, unless JSON is requested via the
+/// `Accept` header, in which case a list of [`AuditEvent`]s is returned.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `params`  - The parameters for the request.
+/// * `headers` - The request headers.
+/// 
+/// # Errors
+/// 
+/// If there is an error rendering the audit page, an error will be returned.
+/// 
+#[utoipa::path(
+	get,
+	path = "/admin/audit",
+	tag  = "admin",
+	params(
+		GetAuditParams,
+	),
+	responses(
+		(status = 200, description = "Recent audit events, newest first, as JSON if requested, otherwise as HTML", body = Vec<AuditEvent>),
+		(status = 403, description = "The user is not allowed to view the audit log"),
+	),
+)]
+pub async fn get_audit(
+	State(state):  State<Arc<AppState>>,
+	auth_cx:       AuthContext<User>,
+	Query(params): Query<GetAuditParams>,
+	headers:       HeaderMap,
+) -> Result<Response, AppError> {
+	let roles  = auth_cx.current_user.as_ref().map(|user| user.roles.as_slice()).unwrap_or_default();
+	if !state.config.audit.roles.iter().any(|role| roles.contains(role)) {
+		return Ok(forbidden(&state, "/admin/audit").await);
+	}
+	let user   = params.user.filter(|username| !username.is_empty());
+	let path   = params.path.filter(|prefix| !prefix.is_empty());
+	let limit  = params.limit.unwrap_or(DEFAULT_AUDIT_LIMIT).min(MAX_AUDIT_LIMIT);
+	let events   = state.audit.query(user.as_deref(), path.as_deref(), limit);
+	let response = match negotiate(&headers, &[ResponseFormat::Html, ResponseFormat::Json]) {
+		ResponseFormat::Json                            => Json(events).into_response(),
+		ResponseFormat::Html | ResponseFormat::Markdown => {
+			let mut context = Context::new();
+			context.insert("Title",      &format!("Audit log - {}", &state.config.title));
+			context.insert("ToC",        &Vec::<Heading>::new());
+			context.insert("FilterUser", &user);
+			context.insert("FilterPath", &path);
+			context.insert("Events",     &events);
+			context.insert("User",       &auth_cx.current_user);
+			Html(state.render("audit", &context).await?).into_response()
+		},
+	};
+	Ok(([(header::VARY, "Accept")], response).into_response())
+}
+
 //		get_index																
 /// Shows the index page.
 /// 
 /// # Parameters
 /// 
-/// * `state` - The application state.
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `params`  - The parameters for the request.
+/// * `headers` - The request headers.
+/// 
+pub async fn get_index(
+	State(state):  State<Arc<AppState>>,
+	auth_cx:       AuthContext<User>,
+	Query(params): Query<GetPageParams>,
+	headers:       HeaderMap,
+) -> impl IntoResponse {
+	get_page(State(state), auth_cx, Query(params), headers, Uri::from_static("/index.md")).await
+}
+
+//		get_login																
+/// Shows the login page.
+/// 
+/// This allows logging in from public pages, which do not otherwise require
+/// it. Users who are already logged in are sent straight to the return URI.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `params`  - The parameters for the request.
+/// 
+/// # Errors
+/// 
+/// If there is an error rendering the login page, an error will be returned.
+/// 
+pub async fn get_login(
+	State(state):  State<Arc<AppState>>,
+	auth_cx:       AuthContext<User>,
+	Query(params): Query<GetLoginParams>,
+) -> Result<Response, AppError> {
+	let uri = local_uri(params.uri);
+	if auth_cx.current_user.is_some() {
+		return Ok(Redirect::to(&uri.to_string()).into_response());
+	}
+	Ok(get_base_login(State(state), uri).await?.into_response())
+}
+
+//		get_oidc_callback														
+/// Completes a login using OpenID Connect.
+/// 
+/// The OpenID Connect provider sends the user back here after they have logged
+/// in. If the login is valid, the user is logged in and sent on to the page
+/// they originally requested. Otherwise, the login page is shown again, with a
+/// failure message.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `session` - The session.
+/// * `params`  - The parameters for the request.
 /// 
-pub async fn get_index(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-	get_page(State(state), Uri::from_static("/index.md")).await
+pub async fn get_oidc_callback(
+	State(state):  State<Arc<AppState>>,
+	mut auth_cx:   AuthContext<User>,
+	session:       Session,
+	Query(params): Query<GetOidcCallbackParams>,
+) -> Response {
+	let Some(ref oidc) = state.oidc else {
+		return StatusCode::NOT_FOUND.into_response();
+	};
+	let Ok(Some(pending)) = session.remove::<PendingLogin>(OIDC_SESSION_KEY).await else {
+		warn!("OpenID Connect callback received without a login in progress");
+		return login_failed(&state, "/").await;
+	};
+	if let Some(err) = params.error {
+		warn!("OpenID Connect login failed: {err}: {}", params.error_description.unwrap_or_default());
+		return login_failed(&state, &pending.uri).await;
+	}
+	let result = oidc.login(
+		&pending,
+		params.state.as_deref().unwrap_or_default(),
+		params.code.as_deref().unwrap_or_default(),
+	).await;
+	let mut user = match result {
+		Ok(user) => user,
+		Err(err) => {
+			warn!("OpenID Connect login failed: {err}");
+			return login_failed(&state, &pending.uri).await;
+		},
+	};
+	//	Roles given in the configuration apply by ID, not username, as the
+	//	username is chosen at the provider, and could match that of a local user
+	user.roles.extend(state.config.roles.get(&user.id).cloned().unwrap_or_default());
+	user.roles.sort();
+	user.roles.dedup();
+	oidc.remember(&user);
+	info!("Logging in user: {}", user.username);
+	if let Err(err) = auth_cx.login(&user).await {
+		error!("Failed to log in user {}: {err}", user.username);
+		return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+	}
+	Redirect::to(&pending.uri).into_response()
+}
+
+//		get_oidc_login															
+/// Starts a login using OpenID Connect.
+/// 
+/// The details of the login are stored in the session, and the user is sent
+/// to the OpenID Connect provider to log in. If OpenID Connect is not enabled,
+/// a `404 Not Found` response is returned instead.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `session` - The session.
+/// * `params`  - The parameters for the request.
+/// 
+pub async fn get_oidc_login(
+	State(state):  State<Arc<AppState>>,
+	session:       Session,
+	Query(params): Query<GetLoginParams>,
+) -> Response {
+	let Some(ref oidc) = state.oidc else {
+		return StatusCode::NOT_FOUND.into_response();
+	};
+	let uri            = local_uri(params.uri);
+	let (url, pending) = match oidc.authorization_url(&uri.to_string()).await {
+		Ok(login) => login,
+		Err(err)  => {
+			error!("Failed to start OpenID Connect login: {err}");
+			return StatusCode::BAD_GATEWAY.into_response();
+		},
+	};
+	if let Err(err) = session.insert(OIDC_SESSION_KEY, &pending).await {
+		error!("Failed to store OpenID Connect login in session: {err}");
+		return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+	}
+	Redirect::to(&url).into_response()
 }
 
 //		get_page																
 /// Shows a rendered Markdown page.
 /// 
+/// Pages and files that the current user is not allowed to access are not
+/// shown, and a `403 Forbidden` response is returned instead. Paths that could
+/// lead outside of where they appear to, such as ones containing `..`, are not
+/// found.
+/// 
+/// The page is rendered as HTML, unless something else is requested. The
+/// original Markdown is returned if the `raw` parameter is given, or if
+/// `text/markdown` is preferred according to the `Accept` header. A
+/// [`PageResponse`] is returned if JSON is preferred. See [`negotiate()`].
+/// 
+/// If the `print` format is requested, the page is shown as a self-contained
+/// document for printing instead. The path can also be a directory, in which
+/// case all the pages within it that the user can access are combined.
+/// 
+/// If the page does not exist, or cannot be loaded or rendered, the
+/// [`PageError`] is logged, and the appropriate error page is shown.
+/// 
 /// # Parameters
 /// 
-/// * `state` - The application state.
-/// * `uri`   - The URI of the page to show.
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `params`  - The parameters for the request.
+/// * `headers` - The request headers.
+/// * `uri`     - The URI of the page to show.
 /// 
+#[utoipa::path(
+	get,
+	path = "/{path}",
+	tag  = "content",
+	params(
+		("path" = String, Path, description = "The path of the Markdown page, such as `guides/setup.md`"),
+		GetPageParams,
+	),
+	responses(
+		(status = 200, description = "The page, as Markdown or JSON if requested, otherwise as HTML", content(
+			(String       = "text/html"),
+			(String       = "text/markdown"),
+			(PageResponse = "application/json"),
+		)),
+		(status = 403, description = "The user is not allowed to view the page"),
+		(status = 404, description = "The page does not exist, with similarly-named pages suggested"),
+		(status = 500, description = "The page could not be loaded or rendered"),
+	),
+)]
 pub async fn get_page(
-	State(state): State<Arc<AppState>>,
-	uri:          Uri,
+	State(state):  State<Arc<AppState>>,
+	auth_cx:       AuthContext<User>,
+	Query(params): Query<GetPageParams>,
+	headers:       HeaderMap,
+	uri:           Uri,
 ) -> impl IntoResponse {
-	let path       =  uri.path().trim_start_matches('/');
+	let Some(path)  = content_path(uri.path()) else {
+		return StatusCode::NOT_FOUND.into_response();
+	};
+	let roles       = auth_cx.current_user.as_ref().map(|user| user.roles.as_slice()).unwrap_or_default();
+	//	The configured rules are checked before printing or serving a file, and
+	//	pages are checked in full when they are loaded.
+	if !state.access.allows_by_rules(path, roles) {
+		return forbidden(&state, path).await;
+	}
+	if params.format.as_deref() == Some("print") {
+		return match print_document(&state, path, roles).await {
+			Ok(html)                     => (StatusCode::OK, Html(html)).into_response(),
+			Err(PrintError::NotFound(_)) => PageError::NotFound(path.to_owned()).respond(&state, roles).await,
+			Err(err)                     => PageError::CouldNotPrint(path.to_owned(), err).respond(&state, roles).await,
+		};
+	}
 	#[expect(clippy::case_sensitive_file_extension_comparisons, reason = "The extension should always be lowercase")]
 	if !path.ends_with(".md") {
 		return get_protected_static_asset(State(state), uri).await.into_response();
 	}
-	let local_path =  state.config.markdown.local_path.join(path);
-	let is_local   =  match state.config.markdown.behavior {
-		LoadingBehavior::Deny       => false,
-		LoadingBehavior::Supplement => state.content_dir.get_file(path).is_none(),
-		LoadingBehavior::Override   => local_path.exists(),
+	let page        = match state.accessible_page(path, roles) {
+		Ok(PageAccess::Allowed(page)) => page,
+		Ok(PageAccess::Forbidden)     => return forbidden(&state, path).await,
+		Ok(PageAccess::NotFound)      => return PageError::NotFound(path.to_owned()).respond(&state, roles).await,
+		Err(err)                      => return PageError::CouldNotLoad(path.to_owned(), err).respond(&state, roles).await,
 	};
-	let possible_text = if is_local {
-		local_path.exists().then(|| fs::read_to_string(local_path).ok()).flatten()
+	let format      = if params.raw.is_some() {
+		ResponseFormat::Markdown
 	} else {
-		#[expect(clippy::return_and_then, reason = "False positive - cannot use `?` here")]
-		state.content_dir.get_file(path).and_then(|file| file.contents_utf8().map(ToOwned::to_owned))
+		negotiate(&headers, &[ResponseFormat::Html, ResponseFormat::Markdown, ResponseFormat::Json])
 	};
-	possible_text.map_or_else(|| (StatusCode::NOT_FOUND).into_response(), |text| {
-		let (title, toc, html)     = if is_local {
-			let (title, toc, html) = rustmark::parse(
-				&text,
-				//	Remove the title from the index page, as it will have one added showing
-				//	the application title.
-				path == "content/index.md",
-			);
-			(title, toc, html.to_string())
-		} else {
-			let mut split = text.splitn(3, '\n');
-			let title     = split.next().unwrap_or_default();
-			let json      = split.next().unwrap_or_default();
-			let html      = split.next().unwrap_or_default();
-			let toc: Vec<Heading> = serde_json::from_str(json).unwrap();
-			(title.to_owned(), toc, html.to_owned())
-		};
-		let mut context = Context::new();
-		let template    = if path == "index.md" { "index" } else { "page" };
-		let page_title  = if path == "index.md" {
-			state.config.title.clone()
-		} else {
-			format!("{title} - {}", &state.config.title)
-		};
-		context.insert("Title",   &page_title);
-		context.insert("ToC",     &toc);
-		context.insert("Content", &html);
-		(
-			StatusCode::OK,
-			Html(state.tera.render(template, &context).unwrap()),
-		).into_response()
+	let response    = match format {
+		ResponseFormat::Markdown => ([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], page.source.clone()).into_response(),
+		ResponseFormat::Json     => Json(PageResponse::from(CompiledPage::clone(&page))).into_response(),
+		ResponseFormat::Html     => {
+			let (template, mut context) = page_context(&state, path, &page, roles);
+			context.insert("User", &auth_cx.current_user);
+			match state.render(template, &context).await {
+				Ok(html) => (StatusCode::OK, Html(html)).into_response(),
+				Err(err) => PageError::CouldNotRender(path.to_owned(), Box::new(err)).respond(&state, roles).await,
+			}
+		},
+	};
+	//	The same URL gives different responses depending on the Accept header,
+	//	so caches need to take it into account.
+	([(header::VARY, "Accept")], response).into_response()
+}
+
+//		get_search																
+/// Searches the Markdown content.
+/// 
+/// The results are rendered as an HTML page, unless JSON is requested via the
+/// `Accept` header, in which case a [`SearchResponse`] is returned.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `params`  - The parameters for the request.
+/// * `headers` - The request headers.
+/// 
+/// # Errors
+/// 
+/// If there is an error rendering the search page, an error will be returned.
+/// 
+#[utoipa::path(
+	get,
+	path = "/search",
+	tag  = "content",
+	params(
+		GetSearchParams,
+	),
+	responses(
+		(status = 200, description = "Search results, as JSON if requested, otherwise as HTML", body = SearchResponse),
+	),
+)]
+pub async fn get_search(
+	State(state):  State<Arc<AppState>>,
+	auth_cx:       AuthContext<User>,
+	Query(params): Query<GetSearchParams>,
+	headers:       HeaderMap,
+) -> Result<Response, AppError> {
+	let query    = params.q.unwrap_or_default();
+	let limit    = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+	let roles    = auth_cx.current_user.as_ref().map(|user| user.roles.as_slice()).unwrap_or_default();
+	let response = SearchResponse {
+		results: state.search(&query, limit, roles).into_iter().map(Into::into).collect(),
+		query,
+	};
+	let rendered = match negotiate(&headers, &[ResponseFormat::Html, ResponseFormat::Json]) {
+		ResponseFormat::Json                            => Json(response).into_response(),
+		ResponseFormat::Html | ResponseFormat::Markdown => {
+			let mut context = Context::new();
+			context.insert("Title",   &format!("Search - {}", &state.config.title));
+			context.insert("ToC",     &Vec::<Heading>::new());
+			context.insert("Query",   &response.query);
+			context.insert("Results", &response.results);
+			context.insert("User",    &auth_cx.current_user);
+			Html(state.render("search", &context).await?).into_response()
+		},
+	};
+	Ok(([(header::VARY, "Accept")], rendered).into_response())
+}
+
+//		get_stats																
+/// Application statistics overview.
+/// 
+/// This returns the standard application statistics, as provided by
+/// Terracotta, with the addition of the page cache statistics:
+/// 
+///   - `cache` - The capacity of the page cache, the number of pages currently
+///               held, and the number of cache hits and misses since the
+///               application last started.
+/// 
+/// The use of each API token is available separately, from the protected
+/// [`get_token_stats()`] endpoint.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+#[utoipa::path(
+	get,
+	path = "/api/stats",
+	tag  = "health",
+	responses(
+		(status = 200, description = "Application statistics overview", body = AppStatsResponse),
+	),
+)]
+pub async fn get_stats(State(state): State<Arc<AppState>>) -> Json<AppStatsResponse> {
+	let Json(base) = get_base_stats(State(Arc::clone(&state))).await;
+	Json(AppStatsResponse {
+		stats: base,
+		cache: state.page_cache.stats().into(),
 	})
 }
 
+//		get_token_stats															
+/// API token usage.
+/// 
+/// This returns the number of requests made using each API token since the
+/// application last started, and when it was last used, keyed by token name.
+/// It requires logging in, or an API token with the `Stats` scope.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+#[utoipa::path(
+	get,
+	path = "/api/stats/tokens",
+	tag  = "admin",
+	responses(
+		(status = 200, description = "API token usage", body = BTreeMap<String, TokenStatsResponse>),
+	),
+)]
+pub async fn get_token_stats(State(state): State<Arc<AppState>>) -> Json<BTreeMap<String, TokenStatsResponse>> {
+	Json(state.tokens.usage().into_iter().map(|(name, usage)| (name, usage.into())).collect())
+}
+
+//		get_reload_feed															
+/// Live reload event feed.
+/// 
+/// This endpoint returns an open WebSocket connection for a feed of changes
+/// to local Markdown files. It will establish a handshake with the
+/// [`WebSocket`] and then pass over to [`ws_reload_feed()`] to handle the
+/// connection. If live reloading is not active, a `404 Not Found` response is
+/// returned instead. Only changes to files the user is allowed to access are
+/// sent to them.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `auth_cx` - The authentication context.
+/// * `ws_req`  - The websocket request.
+/// 
+#[utoipa::path(
+	get,
+	path = "/api/reload",
+	tag  = "content",
+	responses(
+		(status = 101, description = "Live reload event feed"),
+		(status = 404, description = "Live reloading is not active"),
+	),
+)]
+pub async fn get_reload_feed(
+	State(state): State<Arc<AppState>>,
+	auth_cx:      AuthContext<User>,
+	ws_req:       WebSocketUpgrade,
+) -> Response {
+	if !state.live_reload() {
+		return StatusCode::NOT_FOUND.into_response();
+	}
+	let roles    = auth_cx.current_user.map(|user| user.roles).unwrap_or_default();
+	let receiver = state.reload.subscribe();
+	ws_req.on_upgrade(move |socket| ws_reload_feed(socket, state, receiver, roles))
+}
+
+//		ws_reload_feed															
+/// WebSocket feed of changes to local Markdown files.
+/// 
+/// Each change received from the broadcast channel that the user is allowed to
+/// see is sent as a text message containing the path of the changed file,
+/// relative to the content root. It is up to the browser to decide whether the
+/// change affects the page being viewed.
+/// 
+/// # Parameters
+/// 
+/// * `ws`       - The websocket stream.
+/// * `state`    - The application state.
+/// * `receiver` - The receiving end of the reload channel.
+/// * `roles`    - The roles of the user.
+/// 
+async fn ws_reload_feed(mut ws: WebSocket, state: Arc<AppState>, mut receiver: Receiver<String>, roles: Vec<String>) {
+	loop { select! {
+		//		Incoming message												
+		//	Nothing is expected from the browser apart from the connection being
+		//	closed, but pings need to be answered.
+		msg = ws.recv() => match msg {
+			Some(Ok(Message::Ping(ping))) => {
+				if let Err(err) = ws.send(Message::Pong(ping)).await {
+					warn!("Failed to send pong over WebSocket: {err}");
+					break;
+				}
+			},
+			Some(Ok(Message::Close(_))) | None => break,
+			Some(Ok(_))                        => {},
+			Some(Err(err))                     => {
+				warn!("WebSocket error: {err}");
+				break;
+			},
+		},
+		
+		//		Send changes													
+		changed = next_change(&mut receiver, &state, &roles) => match changed {
+			Some(path) => {
+				if let Err(err) = ws.send(Message::Text(path.into())).await {
+					warn!("Failed to send data over WebSocket: {err}");
+					break;
+				}
+			},
+			None       => break,
+		},
+	}}
+}
+
+//		forbidden																
+/// Shows that access to a page or file is forbidden.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `path`  - The path of the page or file, relative to the content root.
+/// 
+async fn forbidden(state: &AppState, path: &str) -> Response {
+	let mut context = Context::new();
+	context.insert("Title", &format!("Forbidden - {}", &state.config.title));
+	match state.render("403-forbidden", &context).await {
+		Ok(html) => (StatusCode::FORBIDDEN, Html(html)).into_response(),
+		Err(err) => {
+			error!("Failed to render forbidden page for {path}: {err}");
+			StatusCode::FORBIDDEN.into_response()
+		},
+	}
+}
+
+//		local_uri																
+/// Gets a URI to return to after logging in.
+/// 
+/// Only local URIs are accepted, so that logging in cannot be used to redirect
+/// to other sites. Anything else is replaced by the home page.
+/// 
+/// # Parameters
+/// 
+/// * `requested` - The URI requested, if any.
+/// 
+fn local_uri(requested: Option<String>) -> Uri {
+	requested
+		.filter(|uri| uri.starts_with('/') && !uri.starts_with("//"))
+		.and_then(|uri| uri.parse::<Uri>().ok())
+		.unwrap_or_default()
+}
+
+//		login_failed															
+/// Shows the login page again, after a failed login.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `uri`   - The URI to return to once logged in.
+/// 
+async fn login_failed(state: &Arc<AppState>, uri: &str) -> Response {
+	let separator = if uri.contains('?') { '&' } else { '?' };
+	let failed    = format!("{uri}{separator}failed").parse::<Uri>().unwrap_or_default();
+	match get_base_login(State(Arc::clone(state)), failed).await {
+		Ok(html) => (StatusCode::UNAUTHORIZED, html).into_response(),
+		Err(err) => {
+			error!("Failed to render login page: {err}");
+			StatusCode::INTERNAL_SERVER_ERROR.into_response()
+		},
+	}
+}
+
+//		next_change																
+/// Waits for the next change to local content that a user is allowed to see.
+/// 
+/// Changes to files the user cannot access are skipped, so that the paths of
+/// restricted pages are not revealed to them. Missed changes are not
+/// important, as only the latest matters.
+/// 
+/// # Parameters
+/// 
+/// * `receiver` - The receiving end of the reload channel.
+/// * `state`    - The application state.
+/// * `roles`    - The roles of the user.
+/// 
+/// # Returns
+/// 
+/// The path of the changed file, relative to the content root, or `None` once
+/// the channel has been closed.
+/// 
+async fn next_change(receiver: &mut Receiver<String>, state: &AppState, roles: &[String]) -> Option<String> {
+	loop {
+		match receiver.recv().await {
+			Ok(path) if state.access.allows(&path, roles) => return Some(path),
+			Ok(_) | Err(RecvError::Lagged(_))              => {},
+			Err(RecvError::Closed)                         => return None,
+		}
+	}
+}
+
+//		page_context															
+/// Prepares the template context for showing a rendered Markdown page.
+/// 
+/// The current user is not added, so that the context can also be used when
+/// there is no user, such as when exporting.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `path`  - The path of the page, relative to the content root.
+/// * `page`  - The page.
+/// * `roles` - The roles of the user the page is being shown to, which limit
+///             the backlinks shown.
+/// 
+/// # Returns
+/// 
+/// The name of the template to render, and the context to render it with.
+/// 
+pub fn page_context<'page>(
+	state: &AppState,
+	path:  &str,
+	page:  &'page CompiledPage,
+	roles: &[String],
+) -> (&'page str, Context) {
+	let mut context = Context::new();
+	//	A template named in the front matter is only used if it is meant for
+	//	pages, so that a page cannot be shown using a template for something
+	//	else, such as the login or audit pages, and if it exists, so that a typo
+	//	does not stop the page from being shown.
+	let template    = page.metadata.template
+		.as_deref()
+		.filter(|name| PAGE_TEMPLATES.contains(name) || name.starts_with(PAGE_TEMPLATE_PREFIX))
+		.filter(|name| state.tera.get_template_names().any(|existing| existing == *name))
+		.unwrap_or(if path == "index.md" { "index" } else { "page" })
+	;
+	let page_title  = if path == "index.md" {
+		state.config.title.clone()
+	} else {
+		format!("{} - {}", page.title, &state.config.title)
+	};
+	context.insert("Title",     &page_title);
+	context.insert("ToC",       &page.toc);
+	context.insert("Content",   &page.html);
+	context.insert("Metadata",  &page.metadata);
+	context.insert("Backlinks", &state.backlinks(path, roles));
+	context.insert("Path",      path);
+	(template, context)
+}
+
 
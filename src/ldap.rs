@@ -0,0 +1,493 @@
+//! LDAP user store functionality.
+//! 
+//! Users can be authenticated against an LDAP directory, by binding to it as
+//! the user. Their roles can optionally be found from the groups they are
+//! members of, which are looked up either as the user or as a service account.
+//! Connections to the directory are pooled, so that they can be reused.
+//! 
+//! Users who log in this way are remembered in memory, along with their roles,
+//! for a configurable time, after which they are looked up in the directory
+//! again when next needed. Changes to a user's groups therefore take effect
+//! the next time they log in, or once they have been forgotten, and users who
+//! are removed from the directory lose access within the same time. Users who
+//! have not logged in since the application started, such as those with
+//! sessions that outlive a restart, are also looked up when first needed, as
+//! the service account if one is configured, or anonymously otherwise. Users
+//! who cannot be found are remembered for a shorter time, so that requests
+//! naming them do not each reach the directory.
+//! 
+//! User stores are called synchronously, once per request for users who are
+//! logged in, so the thread handling a request waits for the directory, for up
+//! to the configured timeout, whenever the user is not already remembered. The
+//! directory is talked to using a runtime of the store's own, which also
+//! drives the pooled connections, from a separate thread, so that waiting is
+//! possible on any kind of runtime. When called from a worker of a
+//! multi-threaded runtime, the worker hands its other tasks over to another
+//! thread before waiting, so that only the request making the lookup is held
+//! up. A runtime with only one thread has nowhere to hand its tasks to, and so
+//! is blocked for the duration.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/ldap.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	config::LdapConfig,
+	users::UserStore,
+};
+use core::{future::Future, time::Duration};
+use ldap3::{
+	Ldap,
+	LdapConnAsync,
+	LdapConnSettings,
+	LdapError,
+	Scope,
+	SearchEntry,
+	dn_escape,
+	ldap_escape,
+};
+use parking_lot::{Mutex, RwLock};
+use std::{
+	collections::HashMap,
+	panic::resume_unwind,
+	thread,
+	time::Instant,
+};
+use tokio::{
+	runtime::{Builder, Handle, Runtime, RuntimeFlavor},
+	task::{block_in_place, spawn},
+};
+use tracing::warn;
+
+
+
+//		Constants																										
+
+/// The LDAP result code for invalid credentials.
+const RC_INVALID_CREDENTIALS: u32 = 49;
+
+/// The LDAP result code for an entry that does not exist.
+const RC_NO_SUCH_OBJECT:      u32 = 32;
+
+
+
+//		Structs																											
+
+//		CachedUser																
+/// A user who has logged in or been looked up, as remembered by the store.
+#[derive(Debug)]
+struct CachedUser {
+	/// When the user is to be forgotten, and looked up again.
+	expires: Instant,
+	
+	/// The roles of the user, or `None` if the user could not be found.
+	roles:   Option<Vec<String>>,
+}
+
+//		LdapUserStore															
+/// Users in an LDAP directory.
+#[derive(Debug)]
+pub struct LdapUserStore {
+	//		Private properties													
+	/// The configuration options for the directory.
+	config:  LdapConfig,
+	
+	/// Idle connections to the directory, available for reuse.
+	pool:    Mutex<Vec<Ldap>>,
+	
+	/// The runtime used to talk to the directory. This is only absent once
+	/// the store has been dropped.
+	runtime: Option<Runtime>,
+	
+	/// The users who have logged in or been looked up, keyed by username.
+	users:   RwLock<HashMap<String, CachedUser>>,
+}
+
+//󰭅		LdapUserStore															
+impl LdapUserStore {
+	//		new																	
+	/// Creates a new LDAP user store.
+	/// 
+	/// No connection is made to the directory until a user logs in, so that
+	/// the application can start even if the directory is unavailable.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `config` - The configuration options for the directory.
+	/// 
+	/// # Panics
+	/// 
+	/// If the runtime used to talk to the directory cannot be created, the
+	/// application cannot run, and so will panic.
+	/// 
+	pub fn new(config: &LdapConfig) -> Self {
+		Self {
+			config:  config.clone(),
+			pool:    Mutex::new(vec![]),
+			runtime: Some(Builder::new_multi_thread()
+				.worker_threads(1)
+				.thread_name("ldap")
+				.enable_all()
+				.build()
+				.expect("Failed to create LDAP runtime")
+			),
+			users:   RwLock::new(HashMap::new()),
+		}
+	}
+	
+	//		bind_service														
+	/// Binds a connection as the service account, if one is configured, or
+	/// anonymously otherwise.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `ldap` - The connection.
+	/// 
+	/// # Errors
+	/// 
+	/// If the bind fails, an error is returned.
+	/// 
+	async fn bind_service(&self, ldap: &mut Ldap) -> Result<(), LdapError> {
+		let bind_dn  = self.config.bind_dn.as_deref().unwrap_or_default();
+		let password = self.config.bind_password.as_deref().unwrap_or_default();
+		drop(ldap.with_timeout(self.timeout()).simple_bind(bind_dn, password).await?.success()?);
+		Ok(())
+	}
+	
+	//		find																
+	/// Finds the roles of a user, looking them up in the directory if they
+	/// are not currently remembered.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// 
+	/// # Returns
+	/// 
+	/// The roles of the user, or `None` if the user does not exist, or cannot
+	/// be looked up.
+	/// 
+	fn find(&self, username: &str) -> Option<Vec<String>> {
+		if username.is_empty() {
+			return None;
+		}
+		let known = self.users.read()
+			.get(username)
+			.filter(|cached| cached.expires > Instant::now())
+			.map(|cached| cached.roles.clone())
+		;
+		if let Some(roles) = known {
+			return roles;
+		}
+		//	Failed lookups are remembered in the same way as users who are not
+		//	found, so that an unreachable directory does not hold up every request
+		let found = self.run(self.lookup(username)).unwrap_or_else(|err| {
+			warn!("Could not look up {username} in LDAP: {err}");
+			None
+		});
+		self.remember(username, found.clone());
+		found
+	}
+	
+	//		connect																
+	/// Gets a connection to the directory, reusing an idle one if available.
+	/// 
+	/// # Errors
+	/// 
+	/// If a new connection cannot be made, an error is returned.
+	/// 
+	async fn connect(&self) -> Result<Ldap, LdapError> {
+		loop {
+			let idle = self.pool.lock().pop();
+			match idle {
+				Some(mut ldap) => if !ldap.is_closed() {
+					return Ok(ldap);
+				},
+				None           => break,
+			}
+		}
+		let settings     = LdapConnSettings::new()
+			.set_conn_timeout(self.timeout())
+			.set_starttls(self.config.starttls)
+			.set_no_tls_verify(!self.config.verify_tls)
+		;
+		let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.config.url).await?;
+		drop(spawn(async move {
+			if let Err(err) = conn.drive().await {
+				warn!("LDAP connection error: {err}");
+			}
+		}));
+		Ok(ldap)
+	}
+	
+	//		groups																
+	/// Finds the groups a user is a member of.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `ldap`     - The connection to use, which is bound as whoever is to
+	///                search for the groups.
+	/// * `username` - The username.
+	/// * `dn`       - The DN of the user.
+	/// 
+	/// # Errors
+	/// 
+	/// If the search fails, an error is returned.
+	/// 
+	#[expect(clippy::literal_string_with_formatting_args, reason = "These are placeholders in the configuration")]
+	async fn groups(&self, ldap: &mut Ldap, username: &str, dn: &str) -> Result<Vec<String>, LdapError> {
+		let Some(ref base) = self.config.group_base else {
+			return Ok(vec![]);
+		};
+		let filter      = self.config.group_filter
+			.replace("{dn}",       &ldap_escape(dn))
+			.replace("{username}", &ldap_escape(username))
+		;
+		let attribute   = self.config.group_attribute.as_str();
+		let (found, _)  = ldap
+			.with_timeout(self.timeout())
+			.search(base, Scope::Subtree, &filter, vec![attribute])
+			.await?
+			.success()?
+		;
+		Ok(found
+			.into_iter()
+			.map(SearchEntry::construct)
+			.flat_map(|mut entry| entry.attrs.remove(attribute).unwrap_or_default())
+			.collect()
+		)
+	}
+	
+	//		login																
+	/// Logs a user in, by binding to the directory as them.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// * `password` - The password.
+	/// 
+	/// # Returns
+	/// 
+	/// The roles of the user, or `None` if the credentials are not valid.
+	/// 
+	/// # Errors
+	/// 
+	/// If the directory cannot be reached, or returns an unexpected error, an
+	/// error is returned.
+	/// 
+	async fn login(&self, username: &str, password: &str) -> Result<Option<Vec<String>>, LdapError> {
+		let dn       = self.user_dn(username);
+		let mut ldap = self.connect().await?;
+		let result   = ldap.with_timeout(self.timeout()).simple_bind(&dn, password).await?;
+		if matches!(result.rc, RC_INVALID_CREDENTIALS | RC_NO_SUCH_OBJECT) {
+			self.release(ldap);
+			return Ok(None);
+		}
+		drop(result.success()?);
+		if self.config.bind_dn.is_some() {
+			self.bind_service(&mut ldap).await?;
+		}
+		let groups   = self.groups(&mut ldap, username, &dn).await?;
+		self.release(ldap);
+		Ok(Some(self.map_roles(&groups)))
+	}
+	
+	//		lookup																
+	/// Looks up a user in the directory, without their password.
+	/// 
+	/// The search is made as the service account, if one is configured, or
+	/// anonymously otherwise.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// 
+	/// # Returns
+	/// 
+	/// The roles of the user, or `None` if the user does not exist.
+	/// 
+	/// # Errors
+	/// 
+	/// If the directory cannot be reached, or returns an unexpected error, an
+	/// error is returned.
+	/// 
+	async fn lookup(&self, username: &str) -> Result<Option<Vec<String>>, LdapError> {
+		let dn         = self.user_dn(username);
+		let mut ldap   = self.connect().await?;
+		self.bind_service(&mut ldap).await?;
+		let result     = ldap
+			.with_timeout(self.timeout())
+			.search(&dn, Scope::Base, "(objectClass=*)", vec!["1.1"])
+			.await?
+		;
+		if result.1.rc == RC_NO_SUCH_OBJECT {
+			self.release(ldap);
+			return Ok(None);
+		}
+		let (found, _) = result.success()?;
+		if found.is_empty() {
+			self.release(ldap);
+			return Ok(None);
+		}
+		let groups     = self.groups(&mut ldap, username, &dn).await?;
+		self.release(ldap);
+		Ok(Some(self.map_roles(&groups)))
+	}
+	
+	//		map_roles															
+	/// Maps the groups a user is a member of to roles.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `groups` - The groups.
+	/// 
+	fn map_roles(&self, groups: &[String]) -> Vec<String> {
+		let mut roles = groups
+			.iter()
+			.filter_map(|group| self.config.role_map.get(group))
+			.flatten()
+			.cloned()
+			.collect::<Vec<_>>()
+		;
+		roles.sort();
+		roles.dedup();
+		roles
+	}
+	
+	//		release																
+	/// Returns a connection to the pool of idle connections, if there is room.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `ldap` - The connection.
+	/// 
+	fn release(&self, mut ldap: Ldap) {
+		let mut pool = self.pool.lock();
+		if pool.len() < self.config.pool_size && !ldap.is_closed() {
+			pool.push(ldap);
+		}
+	}
+	
+	//		remember															
+	/// Remembers a user, along with their roles, or that they could not be
+	/// found.
+	/// 
+	/// Users are remembered for longer if they were found. Any users who have
+	/// already been forgotten are removed at the same time, so that looking up
+	/// many unknown usernames does not use ever more memory.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// * `roles`    - The roles of the user, or `None` if they were not found.
+	/// 
+	fn remember(&self, username: &str, roles: Option<Vec<String>>) {
+		let now       = Instant::now();
+		let ttl       = if roles.is_some() { self.config.cache_ttl } else { self.config.negative_ttl };
+		let expires   = now.checked_add(Duration::from_secs(ttl)).unwrap_or(now);
+		let mut users = self.users.write();
+		users.retain(|_, cached| cached.expires > now);
+		drop(users.insert(username.to_owned(), CachedUser { expires, roles }));
+	}
+	
+	//		run																	
+	/// Runs a future to completion from synchronous code.
+	/// 
+	/// The future is run on the store's own runtime, from a separate thread,
+	/// so that the calling thread can wait for it whether or not it belongs to
+	/// a runtime, and however many threads that runtime has. If the calling
+	/// thread is a worker of a multi-threaded runtime, its other tasks are
+	/// handed over before waiting, so that they are not held up.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `future` - The future to run.
+	/// 
+	fn run<F>(&self, future: F) -> F::Output
+	where
+		F:         Future + Send,
+		F::Output: Send,
+	{
+		let runtime = self.runtime.as_ref().expect("LDAP runtime used after the store was dropped");
+		let wait    = || thread::scope(|scope| scope.spawn(|| runtime.block_on(future)).join());
+		let result  = match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+			Ok(RuntimeFlavor::MultiThread) => block_in_place(wait),
+			_                              => wait(),
+		};
+		result.unwrap_or_else(|err| resume_unwind(err))
+	}
+	
+	//		timeout																
+	/// The timeout for connecting and for each operation.
+	const fn timeout(&self) -> Duration {
+		Duration::from_secs(self.config.timeout)
+	}
+	
+	//		user_dn																
+	/// The DN of a user.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// 
+	#[expect(clippy::literal_string_with_formatting_args, reason = "This is a placeholder in the configuration")]
+	fn user_dn(&self, username: &str) -> String {
+		self.config.user_dn.replace("{username}", &dn_escape(username))
+	}
+}
+
+//󰭅		Drop																	
+impl Drop for LdapUserStore {
+	//		drop																
+	fn drop(&mut self) {
+		//	The runtime may be dropped from within the application's runtime,
+		//	which does not allow blocking whilst its tasks are shut down.
+		if let Some(runtime) = self.runtime.take() {
+			runtime.shutdown_background();
+		}
+	}
+}
+
+//󰭅		UserStore																
+impl UserStore for LdapUserStore {
+	//		authenticate														
+	fn authenticate(&self, username: &str, password: &str) -> bool {
+		//	An empty password would be treated by the directory as an anonymous
+		//	bind, which succeeds without checking anything.
+		if username.is_empty() || password.is_empty() {
+			return false;
+		}
+		match self.run(self.login(username, password)) {
+			Ok(Some(roles)) => {
+				self.remember(username, Some(roles));
+				true
+			},
+			Ok(None)        => false,
+			Err(err)        => {
+				warn!("Could not authenticate {username} against LDAP: {err}");
+				false
+			},
+		}
+	}
+	
+	//		contains															
+	fn contains(&self, username: &str) -> bool {
+		self.find(username).is_some()
+	}
+	
+	//		roles																
+	fn roles(&self, username: &str) -> Vec<String> {
+		self.find(username).unwrap_or_default()
+	}
+}
+
+
@@ -13,8 +13,30 @@
 
 
 
+//		Modules																											
+
+pub mod diagrams;
+pub mod links;
+pub mod math;
+pub mod nav;
+pub mod pipeline;
+pub mod sanitize;
+pub mod search;
+pub mod wiki;
+
+#[cfg(test)]
+#[path = "tests/lib.rs"]
+mod tests;
+
+
+
 //		Packages																										
 
+use self::{
+	links::{Link, find_links},
+	pipeline::Pipeline,
+	sanitize::sanitize_html,
+};
 use comrak::{
 	ComrakOptions,
 	ComrakExtensionOptions,
@@ -27,25 +49,500 @@ use comrak::{
 };
 use nipper::{Document, Selection};
 use rubedo::sugar::s;
-use serde::{Deserialize, Serialize};
-use tendril::StrTendril;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Error as JsonError, Value as JsonValue};
+use std::collections::BTreeMap;
+use thiserror::Error as ThisError;
+use toml::Value as TomlValue;
+
+
+
+//		Constants																										
+
+/// The identifier written into every compiled page.
+pub const COMPILED_PAGE_FORMAT:  &str = "rustmark-page";
+
+/// The version of the compiled page format.
+/// 
+/// This needs to be incremented whenever a change is made to [`CompiledPage`]
+/// that cannot be read by the previous version, i.e. anything other than
+/// adding new fields. It also needs to be incremented when a new field is
+/// added that existing pages need to be compiled again to fill in, such as the
+/// Markdown source, as pages of the current version are not recompiled.
+pub const COMPILED_PAGE_VERSION: u32  = 3;
+
+/// The syntax highlighting themes available. These are the themes built into
+/// the syntax highlighter.
+pub const SYNTAX_THEMES: [&str; 7]    = [
+	"base16-eighties.dark",
+	"base16-mocha.dark",
+	"base16-ocean.dark",
+	"base16-ocean.light",
+	"InspiredGitHub",
+	"Solarized (dark)",
+	"Solarized (light)",
+];
+
+
+
+//		Enums																											
+
+//		CompiledPageError														
+/// Represents all possible errors that can occur when handling compiled pages.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum CompiledPageError {
+	/// The compiled page could not be deserialised.
+	#[error("Failed to deserialize compiled page: {0}")]
+	FailedToDeserialize(JsonError),
+	
+	/// The compiled page could not be serialised.
+	#[error("Failed to serialize compiled page: {0}")]
+	FailedToSerialize(JsonError),
+	
+	/// The data is not a compiled page.
+	#[error("Unknown compiled page format: {0}")]
+	UnknownFormat(String),
+	
+	/// The compiled page was written using an incompatible format version.
+	#[error("Unsupported compiled page version: {0} (expected {COMPILED_PAGE_VERSION})")]
+	UnsupportedVersion(u32),
+}
+
+//		FrontMatterError														
+/// Represents all possible errors that can occur when parsing front matter.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum FrontMatterError {
+	/// The front matter is not valid TOML.
+	#[error("Invalid TOML front matter: {0}")]
+	InvalidToml(String),
+	
+	/// The front matter is not valid YAML.
+	#[error("Invalid YAML front matter: {0}")]
+	InvalidYaml(String),
+	
+	/// The front matter could not be read as page metadata, as a property has
+	/// the wrong type.
+	#[error("Invalid page metadata: {0}")]
+	WrongType(String),
+}
+
+//		ListStyle																
+/// The character used to mark bullet list items in the generated HTML.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum ListStyle {
+	/// A dash, `-`.
+	#[default]
+	Dash,
+	
+	/// A plus sign, `+`.
+	Plus,
+	
+	/// An asterisk, `*`.
+	Star,
+}
+
+//󰭅		From<ListStyle> for ListStyleType										
+impl From<ListStyle> for ListStyleType {
+	//		from																
+	fn from(style: ListStyle) -> Self {
+		match style {
+			ListStyle::Dash => Self::Dash,
+			ListStyle::Plus => Self::Plus,
+			ListStyle::Star => Self::Star,
+		}
+	}
+}
+
+//		MarkdownOptionsError													
+/// Represents all possible errors that can occur when checking Markdown
+/// options.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum MarkdownOptionsError {
+	/// The syntax highlighting theme is not one of the [`SYNTAX_THEMES`].
+	#[error("Unknown syntax highlighting theme: {0}")]
+	UnknownSyntaxTheme(String),
+}
+
+//		StringOrList															
+/// A single string, or a list of them, as accepted for some front matter
+/// properties.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+	/// A single string.
+	One(String),
+	
+	/// A list of strings.
+	Many(Vec<String>),
+}
 
 
 
 //		Structs																											
 
+//		CompiledPage															
+/// A page compiled from Markdown, ready to be rendered.
+/// 
+/// Pages are compiled at build time and included in the binary, and the same
+/// structure is used for pages compiled at runtime. When stored, the page is
+/// wrapped in an envelope containing a format identifier and version number,
+/// so that a page written by an incompatible version of Rustmark is detected
+/// rather than misread.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct CompiledPage {
+	//		Public properties													
+	/// The page title.
+	pub title:    String,
+	
+	/// A table of contents based on the headings found.
+	pub toc:      Vec<Heading>,
+	
+	/// The metadata declared in the front matter, if any.
+	pub metadata: PageMetadata,
+	
+	/// The HTML generated from parsing the Markdown.
+	pub html:     String,
+	
+	/// The links found in the page, in document order.
+	pub links:    Vec<Link>,
+	
+	/// The Markdown the page was compiled from, including any front matter.
+	pub source:   String,
+	
+	/// The reason the front matter could not be parsed, if it could not. Such
+	/// a page has default metadata, and so may be missing restrictions it was
+	/// meant to have, and is not served to anyone.
+	pub invalid:  Option<String>,
+}
+
+//󰭅		CompiledPage															
+impl CompiledPage {
+	//		from_json															
+	/// Reads a compiled page from its stored form.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `json` - The stored form of the page, as produced by
+	///            [`to_json()`](Self::to_json()).
+	/// 
+	/// # Errors
+	/// 
+	/// If the data cannot be deserialised, is not a compiled page, or was
+	/// written using an unsupported format version, an error will be returned.
+	/// 
+	pub fn from_json(json: &str) -> Result<Self, CompiledPageError> {
+		let envelope: CompiledPageEnvelope = serde_json::from_str(json)
+			.map_err(CompiledPageError::FailedToDeserialize)?
+		;
+		if envelope.format != COMPILED_PAGE_FORMAT {
+			return Err(CompiledPageError::UnknownFormat(envelope.format));
+		}
+		if envelope.version != COMPILED_PAGE_VERSION {
+			return Err(CompiledPageError::UnsupportedVersion(envelope.version));
+		}
+		serde_json::from_value(envelope.page).map_err(CompiledPageError::FailedToDeserialize)
+	}
+	
+	//		to_json																
+	/// Converts the compiled page into its stored form.
+	/// 
+	/// # Errors
+	/// 
+	/// If the page cannot be serialised, an error will be returned.
+	/// 
+	pub fn to_json(&self) -> Result<String, CompiledPageError> {
+		let page = serde_json::to_value(self).map_err(CompiledPageError::FailedToSerialize)?;
+		serde_json::to_string(&CompiledPageEnvelope {
+			format:  COMPILED_PAGE_FORMAT.to_owned(),
+			version: COMPILED_PAGE_VERSION,
+			page,
+		}).map_err(CompiledPageError::FailedToSerialize)
+	}
+}
+
+//		CompiledPageEnvelope													
+/// The stored form of a compiled page.
+#[derive(Deserialize, Serialize)]
+struct CompiledPageEnvelope {
+	//		Private properties													
+	/// The format identifier. This should always be [`COMPILED_PAGE_FORMAT`].
+	format:  String,
+	
+	/// The version of the format the page was written with.
+	version: u32,
+	
+	/// The page itself. This is only deserialised once the format and version
+	/// have been checked.
+	page:    JsonValue,
+}
+
 //		Heading																	
 /// A heading extracted from Markdown.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct Heading {
+	//		Public properties													
 	/// The level of the heading. This can be 1-6.
-	level: u8,
+	pub level: u8,
 	
 	/// The HTML id attribute of the heading.
-	id:    String,
+	pub id:    String,
 	
 	/// The text of the heading.
-	text:  String,
+	pub text:  String,
+}
+
+//		MarkdownOptions															
+/// The options controlling how Markdown is converted to HTML.
+/// 
+/// The defaults are the settings Rustmark has always used. Front matter and
+/// wiki links are always enabled, as other features depend on them.
+/// 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[expect(clippy::struct_excessive_bools, reason = "These are independent feature switches")]
+#[non_exhaustive]
+#[serde(default)]
+pub struct MarkdownOptions {
+	//		Public properties													
+	/// Whether to convert bare URLs and email addresses into links.
+	pub autolink:                  bool,
+	
+	/// The language to assume for code blocks that do not specify one.
+	pub default_info_string:       String,
+	
+	/// Whether to support description lists.
+	pub description_lists:         bool,
+	
+	/// Whether to escape raw HTML, rather than removing it, when unsafe HTML
+	/// is not allowed.
+	pub escape:                    bool,
+	
+	/// Whether to support footnotes.
+	pub footnotes:                 bool,
+	
+	/// Whether to keep the full info string of code blocks, rather than only
+	/// the first word.
+	pub full_info_string:          bool,
+	
+	/// Whether to use GitHub-style `<pre lang>` for code blocks.
+	pub github_pre_lang:           bool,
+	
+	/// Whether to render soft line breaks as hard line breaks.
+	pub hardbreaks:                bool,
+	
+	/// The prefix to add to generated heading IDs.
+	pub header_id_prefix:          String,
+	
+	/// Whether to generate IDs for headings. These are needed for the table of
+	/// contents to link to the headings.
+	pub header_ids:                bool,
+	
+	/// The character used to mark bullet list items.
+	pub list_style:                ListStyle,
+	
+	/// Whether to support maths, using `$` for inline maths and `$$` for
+	/// display maths. The TeX inside is converted to MathML by the `math`
+	/// processor. See [`math`].
+	pub math:                      bool,
+	
+	/// Whether to allow any character inside the brackets of task list items.
+	pub relaxed_tasklist_matching: bool,
+	
+	/// Whether to support emoji shortcodes, such as `:rocket:`.
+	pub shortcodes:                bool,
+	
+	/// Whether to convert straight quotes, dashes, and ellipses to their
+	/// typographic equivalents.
+	pub smart:                     bool,
+	
+	/// Whether to sanitise the generated HTML, removing anything that is not on
+	/// an allowlist of safe tags and attributes. This should be enabled for
+	/// content from untrusted sources. See [`sanitize_html()`].
+	pub sanitize:                  bool,
+	
+	/// Whether to add the source position of each element to the HTML.
+	pub sourcepos:                 bool,
+	
+	/// Whether to support strikethrough, using `~~`.
+	pub strikethrough:             bool,
+	
+	/// Whether to support superscript, using `^`.
+	pub superscript:               bool,
+	
+	/// Whether to highlight the syntax of code blocks.
+	pub syntax_highlighting:       bool,
+	
+	/// The syntax highlighting theme, which must be one of the
+	/// [`SYNTAX_THEMES`]. If empty, CSS classes are added instead of inline
+	/// styles, so that the highlighting can be styled by CSS.
+	pub syntax_theme:              String,
+	
+	/// Whether to support tables.
+	pub table:                     bool,
+	
+	/// Whether to filter out HTML tags that are not safe in GitHub Flavored
+	/// Markdown, such as `<script>`.
+	pub tagfilter:                 bool,
+	
+	/// Whether to support task lists.
+	pub tasklist:                  bool,
+	
+	/// Whether to allow raw HTML and potentially dangerous links.
+	pub unsafe_html:               bool,
+	
+	/// The column to wrap output at, when rendering to formats that wrap.
+	pub width:                     usize,
+}
+
+//󰭅		MarkdownOptions															
+impl MarkdownOptions {
+	//		check																
+	/// Checks that the options are valid.
+	/// 
+	/// # Errors
+	/// 
+	/// If the syntax highlighting theme is not known, an error will be
+	/// returned.
+	/// 
+	pub fn check(&self) -> Result<(), MarkdownOptionsError> {
+		if self.syntax_highlighting && !self.syntax_theme.is_empty() && !SYNTAX_THEMES.contains(&self.syntax_theme.as_str()) {
+			return Err(MarkdownOptionsError::UnknownSyntaxTheme(self.syntax_theme.clone()));
+		}
+		Ok(())
+	}
+	
+	//		to_comrak															
+	/// Converts the options into the form used by the Markdown parser.
+	fn to_comrak(&self) -> ComrakOptions<'static> {
+		ComrakOptions {
+			extension:                      ComrakExtensionOptions {
+				strikethrough:              self.strikethrough,
+				tagfilter:                  self.tagfilter,
+				table:                      self.table,
+				autolink:                   self.autolink,
+				tasklist:                   self.tasklist,
+				superscript:                self.superscript,
+				header_ids:                 self.header_ids.then(|| self.header_id_prefix.clone()),
+				footnotes:                  self.footnotes,
+				description_lists:          self.description_lists,
+				front_matter_delimiter:     Some(s!("---")),
+				shortcodes:                 self.shortcodes,
+				wikilinks_title_after_pipe: true,
+				math_dollars:               self.math,
+				..Default::default()
+			},
+			parse:                          ComrakParseOptions {
+				smart:                      self.smart,
+				default_info_string:        Some(self.default_info_string.clone()),
+				relaxed_tasklist_matching:  self.relaxed_tasklist_matching,
+				..Default::default()
+			},
+			render:                         ComrakRenderOptions {
+				hardbreaks:                 self.hardbreaks,
+				github_pre_lang:            self.github_pre_lang,
+				full_info_string:           self.full_info_string,
+				width:                      self.width,
+				unsafe_:                    self.unsafe_html,
+				escape:                     self.escape,
+				list_style:                 self.list_style.into(),
+				sourcepos:                  self.sourcepos,
+				..Default::default()
+			},
+		}
+	}
+}
+
+//󰭅		Default																	
+impl Default for MarkdownOptions {
+	//		default																
+	fn default() -> Self {
+		Self {
+			autolink:                  true,
+			default_info_string:       s!(""),
+			description_lists:         true,
+			escape:                    false,
+			footnotes:                 true,
+			full_info_string:          true,
+			github_pre_lang:           false,
+			hardbreaks:                false,
+			header_id_prefix:          s!(""),
+			header_ids:                true,
+			list_style:                ListStyle::Dash,
+			math:                      false,
+			relaxed_tasklist_matching: true,
+			sanitize:                  false,
+			shortcodes:                true,
+			smart:                     true,
+			sourcepos:                 false,
+			strikethrough:             true,
+			superscript:               true,
+			syntax_highlighting:       true,
+			syntax_theme:              s!("base16-ocean.dark"),
+			table:                     true,
+			tagfilter:                 true,
+			tasklist:                  true,
+			unsafe_html:               true,
+			width:                     80,
+		}
+	}
+}
+
+//		PageMetadata															
+/// Metadata declared in the front matter of a Markdown page.
+/// 
+/// Front matter is an optional block at the very start of a page, delimited by
+/// `---` lines for YAML, or `+++` lines for TOML. The well-known properties are
+/// given their own fields, and anything else is kept in `extra`, so that it is
+/// still available to templates.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct PageMetadata {
+	//		Public properties													
+	/// The page title. If specified, this overrides the title found in the
+	/// content.
+	pub title:       Option<String>,
+	
+	/// A short description of the page.
+	pub description: Option<String>,
+	
+	/// The tags the page has been given. A single tag can be given as a
+	/// string.
+	#[serde(deserialize_with = "string_or_list")]
+	pub tags:        Vec<String>,
+	
+	/// The author of the page.
+	pub author:      Option<String>,
+	
+	/// The date of the page. This is kept as text, as given.
+	pub date:        Option<String>,
+	
+	/// The position of the page amongst its siblings in the navigation
+	/// sidebar. Pages without one are placed after those with one, and sorted
+	/// by title.
+	pub order:       Option<i64>,
+	
+	/// The name of the template to render the page with, instead of the
+	/// default.
+	pub template:    Option<String>,
+	
+	/// The roles allowed to view the page. If empty, the page is not
+	/// restricted, other than by any rules in the application configuration.
+	/// A single role can be given as a string.
+	#[serde(deserialize_with = "string_or_list")]
+	pub access:      Vec<String>,
+	
+	/// Any other properties specified.
+	#[serde(flatten)]
+	pub extra:       BTreeMap<String, JsonValue>,
 }
 
 
@@ -55,68 +552,177 @@ pub struct Heading {
 //		parse																	
 /// Parses Markdown into HTML, extract metadata, and return the result.
 /// 
+/// The default [`MarkdownOptions`] are used, and the HTML is post-processed by
+/// the default [`Pipeline`], containing the built-in processors.
+/// 
 /// # Parameters
 /// 
 /// * `markdown`     - The Markdown to parse.
 /// * `remove_title` - Whether to remove the page title from the HTML.
 /// 
-/// # Returns
+#[must_use]
+pub fn parse(markdown: &str, remove_title: bool) -> CompiledPage {
+	parse_with_options(markdown, remove_title, &MarkdownOptions::default(), &Pipeline::default())
+}
+
+//		parse_with_options														
+/// Parses Markdown into HTML using specific options, extract metadata, and
+/// return the result.
 /// 
-/// * `html`  - The HTML generated from parsing the Markdown.
-/// * `toc`   - A table of contents based on headings found.
-/// * `title` - The page title.
+/// # Parameters
+/// 
+/// * `markdown`     - The Markdown to parse.
+/// * `remove_title` - Whether to remove the page title from the HTML.
+/// * `options`      - The options controlling how the Markdown is converted.
+/// * `pipeline`     - The processors to run over the generated HTML.
 /// 
 #[must_use]
-pub fn parse(markdown: &str, remove_title: bool) -> (String, Vec<Heading>, StrTendril) {
+pub fn parse_with_options(
+	markdown:     &str,
+	remove_title: bool,
+	options:      &MarkdownOptions,
+	pipeline:     &Pipeline,
+) -> CompiledPage {
+	//		Parse front matter													
+	let (parsed, body) = parse_front_matter(markdown);
+	let invalid        = parsed.as_ref().err().map(ToString::to_string);
+	let metadata       = parsed.unwrap_or_default();
 	//		Parse Markdown														
-	let adaptor     = SyntectAdapter::new(Some("base16-ocean.dark"));
+	//	An unknown theme would cause a panic when highlighting, so CSS classes
+	//	are used instead if the options have not been checked.
+	let theme       = Some(options.syntax_theme.as_str())
+		.filter(|theme| SYNTAX_THEMES.contains(theme))
+	;
+	let adaptor     = SyntectAdapter::new(theme);
 	let mut plugins = ComrakPlugins::default();
-	plugins.render.codefence_syntax_highlighter = Some(&adaptor);
-	let html        = markdown_to_html_with_plugins(
-		markdown,
-		&ComrakOptions {
-			extension:                     ComrakExtensionOptions {
-				strikethrough:             true,
-				tagfilter:                 true,
-				table:                     true,
-				autolink:                  true,
-				tasklist:                  true,
-				superscript:               true,
-				header_ids:                Some(s!("")),
-				footnotes:                 true,
-				description_lists:         true,
-				front_matter_delimiter:    Some(s!("---")),
-				shortcodes:                true,
-				..Default::default()
-			},
-			parse:                         ComrakParseOptions {
-				smart:                     true,
-				default_info_string:       Some(s!("")),
-				relaxed_tasklist_matching: true,
-				..Default::default()
-			},
-			render:                        ComrakRenderOptions {
-				hardbreaks:                false,
-				github_pre_lang:           false,
-				full_info_string:          true,
-				width:                     80,
-				unsafe_:                   true,
-				escape:                    false,
-				list_style:                ListStyleType::Dash,
-				sourcepos:                 false,
-				..Default::default()
-			},
-		},
-		&plugins,
-	);
+	if options.syntax_highlighting {
+		plugins.render.codefence_syntax_highlighter = Some(&adaptor);
+	}
+	let html        = markdown_to_html_with_plugins(body, &options.to_comrak(), &plugins);
 	//		Interrogate HTML													
 	let document = Document::from(&html);
-	let title    = find_title(&document, remove_title);
+	let found    = find_title(&document, remove_title);
+	let title    = metadata.title.clone().filter(|title| !title.is_empty()).unwrap_or(found);
 	let toc	     = find_headings(&document);
-	process_details(&document.select("blockquote"));
-	process_callouts(&document.select("blockquote"));
-	process_headings(&document);
-	(title, toc, document.html())
+	pipeline.run(&document);
+	//	Sanitisation happens after the processors, so that it also covers
+	//	anything they add, and so that the links found are the ones that remain.
+	let output   = if options.sanitize {
+		Document::from(&sanitize_html(&document.html()))
+	} else {
+		document
+	};
+	CompiledPage {
+		title,
+		toc,
+		metadata,
+		html:    output.html().to_string(),
+		links:   find_links(&output),
+		source:  markdown.to_owned(),
+		invalid,
+	}
+}
+
+//		parse_front_matter														
+/// Parses the front matter at the start of some Markdown, if present.
+/// 
+/// Front matter delimited by `---` lines is parsed as YAML, and front matter
+/// delimited by `+++` lines is parsed as TOML. If the front matter cannot be
+/// parsed, it is still removed, and the error is returned in place of the
+/// metadata.
+/// 
+/// # Parameters
+/// 
+/// * `markdown` - The Markdown to parse.
+/// 
+/// # Returns
+/// 
+/// * `metadata` - The metadata declared in the front matter, or the reason it
+///                could not be parsed.
+/// * `markdown` - The remaining Markdown, with the front matter removed.
+/// 
+pub fn parse_front_matter(markdown: &str) -> (Result<PageMetadata, FrontMatterError>, &str) {
+	let Some((delimiter, rest)) = ["---", "+++"].into_iter().find_map(|delimiter|
+		markdown
+			.strip_prefix(delimiter)
+			.and_then(|rest| rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n")))
+			.map(|rest| (delimiter, rest))
+	) else {
+		return (Ok(PageMetadata::default()), markdown);
+	};
+	let mut offset = 0_usize;
+	for line in rest.split_inclusive('\n') {
+		if line.trim_end() == delimiter {
+			let front_matter = rest.get(..offset).unwrap_or_default();
+			let body         = rest.get(offset.saturating_add(line.len())..).unwrap_or_default();
+			let value        = if delimiter == "+++" {
+				toml::from_str::<TomlValue>(front_matter)
+					.map(toml_to_json)
+					.map_err(|err| FrontMatterError::InvalidToml(err.message().to_owned()))
+			} else {
+				serde_yaml::from_str::<Option<JsonValue>>(front_matter)
+					.map(Option::unwrap_or_default)
+					.map_err(|err| FrontMatterError::InvalidYaml(err.to_string()))
+			};
+			//	Empty YAML front matter is null, rather than an empty mapping.
+			let metadata     = value.and_then(|json| if json.is_null() {
+				Ok(PageMetadata::default())
+			} else {
+				serde_json::from_value(json).map_err(|err| FrontMatterError::WrongType(err.to_string()))
+			});
+			return (metadata, body);
+		}
+		offset = offset.saturating_add(line.len());
+	}
+	(Ok(PageMetadata::default()), markdown)
+}
+
+//		string_or_list															
+/// Deserialises a list of strings that can also be given as a single string,
+/// or left empty.
+/// 
+/// # Parameters
+/// 
+/// * `deserializer` - The deserialiser to use.
+/// 
+/// # Errors
+/// 
+/// If the value is neither a string nor a list of strings, an error will be
+/// returned.
+/// 
+fn string_or_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	Ok(match Option::<StringOrList>::deserialize(deserializer)? {
+		Some(StringOrList::One(string)) => vec![string],
+		Some(StringOrList::Many(list))  => list,
+		None                            => vec![],
+	})
+}
+
+//		toml_to_json															
+/// Converts a TOML value into a JSON value.
+/// 
+/// TOML has a native date and time type, which has no JSON equivalent, and so
+/// is converted to text.
+/// 
+/// # Parameters
+/// 
+/// * `value` - The TOML value to convert.
+/// 
+fn toml_to_json(value: TomlValue) -> JsonValue {
+	match value {
+		TomlValue::String(string)     => JsonValue::String(string),
+		TomlValue::Integer(integer)   => JsonValue::from(integer),
+		TomlValue::Float(float)       => JsonValue::from(float),
+		TomlValue::Boolean(boolean)   => JsonValue::Bool(boolean),
+		TomlValue::Datetime(datetime) => JsonValue::String(datetime.to_string()),
+		TomlValue::Array(array)       => JsonValue::Array(array.into_iter().map(toml_to_json).collect()),
+		TomlValue::Table(table)       => JsonValue::Object(
+			table.into_iter().map(|(key, entry)| (key, toml_to_json(entry))).collect()
+		),
+	}
 }
 
 //		find_title																
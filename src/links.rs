@@ -0,0 +1,525 @@
+//! Link extraction and checking functionality.
+//! 
+//! This module provides the means to find the links in a rendered page, to
+//! resolve them against the content tree, to check that the pages, files, and
+//! anchors they point to exist, and to find the pages that link to each page.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/links.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use core::{
+	fmt::{Display, self},
+	str,
+};
+use nipper::Document;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	path::Path,
+};
+
+
+
+//		Enums																											
+
+//		LinkKind																
+/// The kind of element a link was found in.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum LinkKind {
+	/// An `<a href>` element.
+	Anchor,
+	
+	/// An `<img src>` element.
+	Image,
+}
+
+//		MissingTarget															
+/// What a broken link points to that does not exist.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum MissingTarget {
+	/// The Markdown page linked to does not exist.
+	Page,
+	
+	/// The file linked to does not exist.
+	File,
+	
+	/// The page linked to exists, but does not contain the anchor.
+	Anchor,
+}
+
+//󰭅		Display																	
+impl Display for MissingTarget {
+	//		fmt																	
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let description = match *self {
+			Self::Page   => "page not found",
+			Self::File   => "file not found",
+			Self::Anchor => "anchor not found",
+		};
+		write!(f, "{description}")
+	}
+}
+
+
+
+//		Structs																											
+
+//		Backlink																
+/// A page that links to another page.
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[non_exhaustive]
+pub struct Backlink {
+	//		Public properties													
+	/// The path of the linking page, relative to the content root.
+	pub path:  String,
+	
+	/// The title of the linking page.
+	pub title: String,
+}
+
+//		BrokenLink																
+/// A link that points to something that does not exist.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct BrokenLink {
+	//		Public properties													
+	/// The path of the page containing the link, relative to the content root.
+	pub page:    String,
+	
+	/// The link target, as written in the page.
+	pub target:  String,
+	
+	/// What the link points to that does not exist.
+	pub missing: MissingTarget,
+}
+
+//󰭅		Display																	
+impl Display for BrokenLink {
+	//		fmt																	
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {} ({})", self.page, self.target, self.missing)
+	}
+}
+
+//		Link																	
+/// A link found in a page.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Link {
+	//		Public properties													
+	/// The kind of element the link was found in.
+	pub kind:   LinkKind,
+	
+	/// The link target, as written in the page.
+	pub target: String,
+}
+
+//		LinkChecker																
+/// Checks links against a set of known pages and files.
+/// 
+/// The checker knows which Markdown pages exist, along with the element ids
+/// found in each of them, and which other files exist. It can be serialised,
+/// so that the baked-in content can be recorded at build time and then
+/// supplemented with local content at runtime.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LinkChecker {
+	//		Private properties													
+	/// The known pages, and the element ids found in each.
+	pages: BTreeMap<String, BTreeSet<String>>,
+	
+	/// The known non-Markdown files.
+	files: BTreeSet<String>,
+}
+
+//󰭅		LinkChecker																
+impl LinkChecker {
+	//		new																	
+	/// Creates a new, empty link checker.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	//		add_file															
+	/// Records that a non-Markdown file exists.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the file, relative to the content root.
+	/// 
+	pub fn add_file(&mut self, path: &str) {
+		_ = self.files.insert(path.to_owned());
+	}
+	
+	//		add_page															
+	/// Records that a page exists, along with the element ids it contains.
+	/// 
+	/// If the page is already known, its ids are replaced, which allows
+	/// baked-in pages to be overridden by local ones.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// * `html` - The rendered HTML of the page.
+	/// 
+	pub fn add_page(&mut self, path: &str, html: &str) {
+		let document = Document::from(html);
+		let ids      = document
+			.select("[id]")
+			.iter()
+			.filter_map(|element| element.attr("id").map(|id| id.to_string()))
+			.collect()
+		;
+		drop(self.pages.insert(path.to_owned(), ids));
+	}
+	
+	//		check																
+	/// Checks the links found in a page.
+	/// 
+	/// Links to other sites, and root-relative links to anything other than
+	/// Markdown pages, are not checked, as they may refer to application
+	/// routes or public assets rather than to content.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `page`  - The path of the page containing the links, relative to the
+	///             content root.
+	/// * `links` - The links found in the page.
+	/// 
+	#[must_use]
+	pub fn check(&self, page: &str, links: &[Link]) -> Vec<BrokenLink> {
+		links
+			.iter()
+			.filter_map(|link| self.check_link(page, &link.target).map(|missing| BrokenLink {
+				page:   page.to_owned(),
+				target: link.target.clone(),
+				missing,
+			}))
+			.collect()
+	}
+	
+	//		check_link															
+	/// Checks a single link.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `page`   - The path of the page containing the link.
+	/// * `target` - The link target.
+	/// 
+	/// # Returns
+	/// 
+	/// What is missing, or `None` if the link is valid or not checked.
+	/// 
+	fn check_link(&self, page: &str, target: &str) -> Option<MissingTarget> {
+		let (path, anchor) = resolve(page, target)?;
+		if !is_markdown(&path) {
+			return (!self.files.contains(&path)).then_some(MissingTarget::File);
+		}
+		let Some(ids) = self.pages.get(&path) else {
+			return Some(MissingTarget::Page);
+		};
+		anchor
+			.filter(|id| !ids.contains(id))
+			.map(|_| MissingTarget::Anchor)
+	}
+	
+	//		remove_page															
+	/// Records that a page no longer exists.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn remove_page(&mut self, path: &str) {
+		drop(self.pages.remove(path));
+	}
+}
+
+
+//		LinkGraph																
+/// The graph of links between pages.
+/// 
+/// This records the pages that each page links to, so that the pages linking
+/// to any given page can be found. It can be serialised, so that the graph for
+/// the baked-in content can be built at build time and then updated with local
+/// content at runtime.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LinkGraph {
+	//		Private properties													
+	/// The known pages, and the pages each of them links to.
+	pages: BTreeMap<String, PageLinks>,
+}
+
+//󰭅		LinkGraph																
+impl LinkGraph {
+	//		new																	
+	/// Creates a new, empty link graph.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	//		add_page															
+	/// Records the pages that a page links to.
+	/// 
+	/// Only links to Markdown pages are recorded, and links from a page to
+	/// itself are ignored. If the page is already known, its links are
+	/// replaced, which allows baked-in pages to be overridden by local ones,
+	/// and local pages to be updated when they change.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `title` - The title of the page.
+	/// * `links` - The links found in the page.
+	/// 
+	pub fn add_page(&mut self, path: &str, title: &str, links: &[Link]) {
+		let targets = links
+			.iter()
+			.filter(|link| link.kind == LinkKind::Anchor)
+			.filter_map(|link| resolve(path, &link.target))
+			.map(|(target, _)| target)
+			.filter(|target| is_markdown(target) && target != path)
+			.collect()
+		;
+		drop(self.pages.insert(path.to_owned(), PageLinks { title: title.to_owned(), targets }));
+	}
+	
+	//		backlinks															
+	/// Finds the pages that link to a page.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	/// # Returns
+	/// 
+	/// The pages linking to the page, ordered by title.
+	/// 
+	#[must_use]
+	pub fn backlinks(&self, path: &str) -> Vec<Backlink> {
+		let mut backlinks = self.pages
+			.iter()
+			.filter(|&(_, links)| links.targets.contains(path))
+			.map(|(source, links)| Backlink {
+				path:  source.clone(),
+				title: links.title.clone(),
+			})
+			.collect::<Vec<_>>()
+		;
+		backlinks.sort_by(|a, b| a.title.cmp(&b.title).then_with(|| a.path.cmp(&b.path)));
+		backlinks
+	}
+	
+	//		remove_page															
+	/// Forgets the links from a page that no longer exists.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn remove_page(&mut self, path: &str) {
+		drop(self.pages.remove(path));
+	}
+}
+
+//		PageLinks																
+/// The links from a single page.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct PageLinks {
+	//		Private properties													
+	/// The title of the page.
+	title:   String,
+	
+	/// The paths of the pages linked to, relative to the content root.
+	targets: BTreeSet<String>,
+}
+
+
+
+//		Functions																										
+
+//		find_links																
+/// Finds all the links in the document.
+/// 
+/// This collects the targets of all `<a href>` and `<img src>` elements, in
+/// document order.
+/// 
+/// # Parameters
+/// 
+/// * `document` - The HTML document tree to search for links.
+/// 
+pub fn find_links(document: &Document) -> Vec<Link> {
+	document
+		.select("a[href], img[src]")
+		.iter()
+		.filter_map(|element| {
+			let (kind, attribute) = if element.is("img") {
+				(LinkKind::Image, "src")
+			} else {
+				(LinkKind::Anchor, "href")
+			};
+			element.attr(attribute).map(|target| Link { kind, target: target.to_string() })
+		})
+		.collect()
+}
+
+//		is_external																
+/// Checks whether a URL points outside of the application.
+/// 
+/// URLs with a scheme, such as `https:` or `mailto:`, and protocol-relative
+/// URLs starting with `//`, are external. Everything else is a path within the
+/// application, or a fragment.
+/// 
+/// # Parameters
+/// 
+/// * `url` - The URL to check.
+/// 
+#[must_use]
+pub fn is_external(url: &str) -> bool {
+	url.starts_with("//") || url.split_once(':').is_some_and(|(scheme, _)|
+		!scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+	)
+}
+
+//		is_markdown																
+/// Checks whether a path refers to a Markdown file.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path to check.
+/// 
+fn is_markdown(path: &str) -> bool {
+	Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+}
+
+//		percent_decode															
+/// Decodes percent-encoded characters in part of a URL.
+/// 
+/// Invalid escape sequences are left as they are.
+/// 
+/// # Parameters
+/// 
+/// * `text` - The text to decode.
+/// 
+#[must_use]
+pub fn percent_decode(text: &str) -> String {
+	let bytes       = text.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut index   = 0_usize;
+	while let Some(&byte) = bytes.get(index) {
+		let escaped = (byte == b'%')
+			.then(|| bytes.get(index.saturating_add(1)..index.saturating_add(3)))
+			.flatten()
+			.and_then(|hex| str::from_utf8(hex).ok())
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+		;
+		if let Some(value) = escaped {
+			decoded.push(value);
+			index = index.saturating_add(3);
+		} else {
+			decoded.push(byte);
+			index = index.saturating_add(1);
+		}
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+//		resolve																	
+/// Resolves a link target against the content tree.
+/// 
+/// Relative targets are resolved against the directory of the page containing
+/// the link, and root-relative targets against the content root. Any query
+/// string is ignored, and percent-encoded characters are decoded. A target
+/// consisting only of an anchor refers to the page containing it.
+/// 
+/// # Parameters
+/// 
+/// * `page`   - The path of the page containing the link, relative to the
+///              content root.
+/// * `target` - The link target.
+/// 
+/// # Returns
+/// 
+/// The path of the target, relative to the content root, and the anchor, if
+/// any. `None` is returned for links to other sites, and for root-relative
+/// links to anything other than Markdown pages, as these are not content.
+/// 
+#[must_use]
+pub fn resolve(page: &str, target: &str) -> Option<(String, Option<String>)> {
+	let path = target.trim().split(['#', '?']).next().unwrap_or_default();
+	if path.strip_prefix('/').is_some_and(|absolute| !is_markdown(absolute)) {
+		return None;
+	}
+	resolve_path(page, target)
+}
+
+//		resolve_path															
+/// Resolves a link target to a path within the site.
+/// 
+/// This works in the same way as [`resolve()`], except that root-relative
+/// targets are resolved whatever they point to, such as static files, so the
+/// path returned is relative to the root of the site. The segments of the path
+/// are decoded, so it needs to be checked before being used to load anything.
+/// 
+/// # Parameters
+/// 
+/// * `page`   - The path of the page containing the link, relative to the
+///              content root.
+/// * `target` - The link target.
+/// 
+/// # Returns
+/// 
+/// The path of the target, relative to the root of the site, and the anchor,
+/// if any. `None` is returned for links to other sites.
+/// 
+#[must_use]
+pub fn resolve_path(page: &str, target: &str) -> Option<(String, Option<String>)> {
+	let trimmed          = target.trim();
+	let (rest, fragment) = trimmed.split_once('#').map_or((trimmed, None), |(rest, fragment)| (rest, Some(fragment)));
+	let path             = rest.split_once('?').map_or(rest, |(path, _)| path);
+	let anchor           = fragment.filter(|id| !id.is_empty()).map(percent_decode);
+	if trimmed.is_empty() || is_external(path) {
+		return None;
+	}
+	if path.is_empty() {
+		return Some((page.to_owned(), anchor));
+	}
+	let (base, relative) = path.strip_prefix('/').map_or_else(
+		|| (page.rsplit_once('/').map_or("", |(dir, _)| dir), path),
+		|absolute| ("", absolute),
+	);
+	let mut segments     = vec![];
+	for segment in base.split('/').chain(relative.split('/')) {
+		match segment {
+			"" | "." => {},
+			".."     => {
+				//	Leaving the content root is recorded, so that the result can
+				//	never match a real path
+				if segments.last().is_none_or(|last: &String| last == "..") {
+					segments.push("..".to_owned());
+				} else {
+					drop(segments.pop());
+				}
+			},
+			_        => segments.push(percent_decode(segment)),
+		}
+	}
+	Some((segments.join("/"), anchor))
+}
+
+
@@ -18,11 +18,25 @@
 
 //		Modules																											
 
+mod access;
+mod audit;
 mod auth;
+mod cache;
+mod cli;
 mod config;
+mod errors;
+mod export;
 mod handlers;
+mod ldap;
+mod oidc;
+mod print;
+mod reload;
+mod requests;
+mod responses;
 mod routes;
 mod state;
+mod tokens;
+mod users;
 mod utility;
 
 
@@ -30,24 +44,28 @@ mod utility;
 //		Packages																										
 
 use crate::{
-	auth::User,
+	cli::{CliError, Command},
 	config::Config,
-	routes::{protected, public},
+	routes::{app, check_public},
 	state::AppState,
-	utility::ApiDoc,
 };
+use axum::{Router, serve};
+use core::net::SocketAddr;
 use std::sync::Arc;
 use terracotta::{
 	app::{
-		create::{app_full as create_app, server as create_server},
 		errors::AppError,
 		init::{load_config, setup_logging},
 		state::StateProvider as _,
 	},
 	stats::worker::start as start_stats_processor,
 };
+use tokio::{
+	net::TcpListener,
+	spawn as spawn_async,
+	task::JoinHandle as TaskHandle,
+};
 use tracing::info;
-use utoipa::OpenApi as _;
 
 #[cfg(not(windows))]
 use tikv_jemallocator::Jemalloc;
@@ -68,16 +86,43 @@ static GLOBAL: Jemalloc = Jemalloc;
 
 //		main																	
 #[tokio::main]
-#[expect(clippy::result_large_err, reason = "Size doesn't matter here as it's only for the main process")]
-async fn main() -> Result<(), AppError> {
+async fn main() -> Result<(), CliError> {
+	if let Some(command) = Command::from_args() {
+		return command.run().await;
+	}
 	let config = load_config::<Config>()?;
+	check_public(&config)?;
 	let _guard = setup_logging(&config.logdir);
 	let state  = Arc::new(AppState::new(config));
 	start_stats_processor(&state).await;
-	let app    = create_app::<_, User, User>(&state, protected(), public(), ApiDoc::openapi());
-	let server = create_server(app, &*state).await?;
+	let _watcher = reload::watch(&state);
+	let server   = server(app(&state), &state).await?;
 	info!("Listening on {}", state.address().expect("Server address not set"));
-	server.await.unwrap()
+	Ok(server.await.unwrap()?)
+}
+
+//		server																	
+/// Creates the application server.
+/// 
+/// This follows Terracotta's server, but also makes the address of each client
+/// available to the handlers, for the audit log.
+/// 
+/// # Parameters
+/// 
+/// * `app`   - The application router.
+/// * `state` - The application state.
+/// 
+/// # Errors
+/// 
+/// If the configured host and port cannot be bound to, an error is returned.
+/// 
+async fn server(app: Router, state: &AppState) -> Result<TaskHandle<Result<(), AppError>>, AppError> {
+	let listener = TcpListener::bind(SocketAddr::from((state.host(), state.port()))).await?;
+	state.set_address(Some(listener.local_addr()?));
+	Ok(spawn_async(async move {
+		serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await
+			.map_err(AppError::CouldNotStartServer)
+	}))
 }
 
 
@@ -0,0 +1,150 @@
+//! Maths rendering functionality.
+//! 
+//! When the `math` Markdown option is enabled, TeX written between `$`
+//! delimiters is marked by the Markdown parser, using `$` for inline maths, and
+//! `$$` for display maths. The TeX is then converted to MathML, which browsers
+//! display natively, so no scripts or fonts need to be loaded. Conversion
+//! happens on the server, at build time for the baked-in content, and at
+//! runtime for local content. If some TeX cannot be converted, it is shown as
+//! written instead.
+//! 
+//! Conversion is performed by the [`MathProcessor`], which is one of the
+//! built-in processors in the [`Pipeline`](super::pipeline::Pipeline).
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/math.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use super::pipeline::{PipelineError, Processor};
+use math_core::{LatexToMathML, MathCoreConfig, MathDisplay};
+use nipper::Document;
+use rubedo::sugar::s;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+
+
+//		Structs																											
+
+//		MathOptions																
+/// The options for the [`MathProcessor`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct MathOptions {
+	//		Public properties													
+	/// Custom TeX macros, keyed by name, with or without the leading
+	/// backslash. Arguments are referred to as `#1`, `#2`, and so on.
+	pub macros: BTreeMap<String, String>,
+}
+
+//		MathProcessor															
+/// Converts TeX maths to MathML.
+/// 
+/// Each piece of maths marked by the Markdown parser is replaced by a `<math>`
+/// element, which has `display="block"` for display maths. Maths that cannot be
+/// converted is left as TeX, with the `math-error` class added, and the error
+/// given as its title.
+/// 
+#[derive(Debug, Default)]
+pub struct MathProcessor {
+	//		Private properties													
+	/// The converter, which holds any custom macros.
+	converter: LatexToMathML,
+}
+
+//󰭅		MathProcessor															
+impl MathProcessor {
+	//		new																	
+	/// Creates a new maths processor.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `options` - The options for the processor.
+	/// 
+	/// # Errors
+	/// 
+	/// If any of the custom macros cannot be parsed, an error will be
+	/// returned.
+	/// 
+	pub fn new(options: MathOptions) -> Result<Self, PipelineError> {
+		let macros = options.macros
+			.into_iter()
+			.map(|(name, definition)| (name.trim_start_matches('\\').to_owned(), definition))
+			.collect::<Vec<_>>()
+		;
+		//	The error gives the position of the macro, and its definition rather
+		//	than its name, so the names are kept to report which one failed.
+		let names  = macros.iter().map(|entry| entry.0.clone()).collect::<Vec<_>>();
+		LatexToMathML::new(MathCoreConfig { macros, ..Default::default() })
+			.map(|converter| Self { converter })
+			.map_err(|(err, index, _)| PipelineError::InvalidOptions(
+				s!("math"),
+				format!("Macro {}: {err}", names.get(index).map_or("", String::as_str)),
+			))
+	}
+	
+	//		from_options														
+	/// Creates a new maths processor from the options given in the pipeline
+	/// configuration.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `options` - The options, which can be empty to use the defaults.
+	/// 
+	/// # Errors
+	/// 
+	/// If the options are not valid, an error will be returned.
+	/// 
+	pub fn from_options(options: &JsonValue) -> Result<Self, PipelineError> {
+		if options.is_null() {
+			return Ok(Self::default());
+		}
+		serde_json::from_value(options.clone())
+			.map_err(|err| PipelineError::InvalidOptions(s!("math"), err.to_string()))
+			.and_then(Self::new)
+	}
+}
+
+//󰭅		Processor																
+impl Processor for MathProcessor {
+	//		name																
+	fn name(&self) -> &'static str {
+		"math"
+	}
+	
+	//		order																
+	fn order(&self) -> i32 {
+		500
+	}
+	
+	//		process																
+	fn process(&self, document: &Document) {
+		for mut span in document.select("span[data-math-style]").iter() {
+			let display = if span.attr("data-math-style").is_some_and(|style| &*style == "display") {
+				MathDisplay::Block
+			} else {
+				MathDisplay::Inline
+			};
+			match self.converter.convert_with_local_state(&span.text(), display) {
+				Ok(result) => span.replace_with_html(result.mathml),
+				Err(err)   => {
+					span.add_class("math-error");
+					span.set_attr("title", &format!("Could not render maths: {err}"));
+				},
+			}
+		}
+	}
+}
+
+
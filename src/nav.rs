@@ -0,0 +1,372 @@
+//! Site navigation functionality.
+//! 
+//! This module provides a tree of the available pages, organised by directory,
+//! which can be built at compile time and baked into the binary, and then
+//! supplemented with local content at runtime.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/nav.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use core::{cmp::Ordering, slice};
+use serde::{Deserialize, Serialize};
+
+
+
+//		Structs																											
+
+//		NavItem																	
+/// An entry in the navigation tree.
+/// 
+/// An entry is either a page, or a directory containing further entries. A
+/// directory can also have a page of its own, if it contains an `index.md`
+/// file, in which case the title and ordering of that page are used for the
+/// directory.
+/// 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct NavItem {
+	//		Public properties													
+	/// The title to show for the entry.
+	pub title:    String,
+	
+	/// The path of the page or directory, relative to the content root.
+	pub path:     String,
+	
+	/// The path of the page to link to, relative to the content root, if any.
+	pub page:     Option<String>,
+	
+	/// The position of the entry amongst its siblings. Entries without one are
+	/// placed after those with one.
+	pub order:    Option<i64>,
+	
+	/// The entries within a directory.
+	pub children: Vec<Self>,
+}
+
+//󰭅		NavItem																	
+impl NavItem {
+	//		compare																
+	/// Compares two entries for ordering purposes.
+	/// 
+	/// Entries are ordered by their `order` value first, and then by title.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `other` - The entry to compare against.
+	/// 
+	fn compare(&self, other: &Self) -> Ordering {
+		match (self.order, other.order) {
+			(Some(a), Some(b)) => a.cmp(&b),
+			(Some(_), None)    => Ordering::Less,
+			(None,    Some(_)) => Ordering::Greater,
+			(None,    None)    => Ordering::Equal,
+		}.then_with(|| self.title.to_lowercase().cmp(&other.title.to_lowercase()))
+	}
+}
+
+//		NavTree																	
+/// A tree of the available pages, organised by directory.
+/// 
+/// The root `index.md` page is not included, as it is the home page and is
+/// linked to separately.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct NavTree {
+	//		Private properties													
+	/// The top-level entries.
+	items: Vec<NavItem>,
+}
+
+//󰭅		NavTree																	
+impl NavTree {
+	//		new																	
+	/// Creates a new, empty navigation tree.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	//		filtered															
+	/// Creates a copy of the tree containing only the pages allowed.
+	/// 
+	/// Directories are kept if they contain any allowed pages, even if their
+	/// own page is not allowed, in which case they are not linked to.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `allowed` - A function that determines whether a page, given its
+	///               path relative to the content root, should be included.
+	/// 
+	#[must_use]
+	pub fn filtered<F: Fn(&str) -> bool>(&self, allowed: F) -> Self {
+		Self { items: filter(&self.items, &allowed) }
+	}
+	
+	//		insert																
+	/// Adds a page to the tree.
+	/// 
+	/// Any directories needed are created. If the page is already present, its
+	/// details are replaced, which allows baked-in pages to be overridden by
+	/// local ones. The tree needs to be [sorted](Self::sort()) once all pages
+	/// have been added.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `title` - The page title.
+	/// * `order` - The position of the page amongst its siblings, if given.
+	/// 
+	pub fn insert(&mut self, path: &str, title: &str, order: Option<i64>) {
+		if path == "index.md" {
+			return;
+		}
+		let segments = path.split('/').collect::<Vec<_>>();
+		insert(&mut self.items, "", &segments, path, title, order);
+	}
+	
+	//		is_empty															
+	/// Checks whether the tree contains any entries.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+	
+	//		items																
+	/// The top-level entries.
+	#[must_use]
+	pub fn items(&self) -> &[NavItem] {
+		&self.items
+	}
+	
+	//		pages																
+	/// Lists the pages in a directory and its subdirectories, in navigation
+	/// order.
+	/// 
+	/// The page of a directory, if it has one, comes before the pages within
+	/// it. The root `index.md` page is not included.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `dir` - The path of the directory, relative to the content root, or
+	///           an empty string for the whole tree.
+	/// 
+	#[must_use]
+	pub fn pages(&self, dir: &str) -> Vec<String> {
+		let mut pages = vec![];
+		if dir.is_empty() {
+			collect(&self.items, &mut pages);
+		} else if let Some(item) = find(&self.items, dir) {
+			collect(slice::from_ref(item), &mut pages);
+		}
+		pages
+	}
+	
+	//		remove																
+	/// Removes a page from the tree.
+	/// 
+	/// A directory with a page of its own keeps its entry if it still has
+	/// other entries within it, and any directories left empty are removed.
+	/// Nothing happens if the page is not present.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn remove(&mut self, path: &str) {
+		if path == "index.md" {
+			return;
+		}
+		let segments = path.split('/').collect::<Vec<_>>();
+		remove(&mut self.items, "", &segments);
+	}
+	
+	//		sort																
+	/// Sorts all entries in the tree.
+	pub fn sort(&mut self) {
+		sort(&mut self.items);
+	}
+}
+
+
+
+//		Functions																										
+
+//		collect																	
+/// Adds the pages in a list of entries and their descendants to a list, in
+/// order.
+/// 
+/// # Parameters
+/// 
+/// * `items` - The list of entries.
+/// * `pages` - The list of page paths to add to.
+/// 
+fn collect(items: &[NavItem], pages: &mut Vec<String>) {
+	for item in items {
+		pages.extend(item.page.clone());
+		collect(&item.children, pages);
+	}
+}
+
+//		entry																	
+/// Finds an entry in a list, adding a directory entry if it is not present.
+/// 
+/// # Parameters
+/// 
+/// * `items` - The list of entries to search.
+/// * `path`  - The path of the entry.
+/// * `name`  - The name to use as the title of a new entry.
+/// 
+fn entry<'a>(items: &'a mut Vec<NavItem>, path: &str, name: &str) -> Option<&'a mut NavItem> {
+	let index = items.iter().position(|item| item.path == path).unwrap_or_else(|| {
+		items.push(NavItem {
+			title:    name.to_owned(),
+			path:     path.to_owned(),
+			page:     None,
+			order:    None,
+			children: vec![],
+		});
+		items.len().saturating_sub(1)
+	});
+	items.get_mut(index)
+}
+
+//		filter																	
+/// Copies a list of entries, keeping only the pages allowed.
+/// 
+/// # Parameters
+/// 
+/// * `items`   - The list of entries to copy.
+/// * `allowed` - A function that determines whether a page should be kept.
+/// 
+fn filter<F: Fn(&str) -> bool>(items: &[NavItem], allowed: &F) -> Vec<NavItem> {
+	items
+		.iter()
+		.filter_map(|item| {
+			let page     = item.page.clone().filter(|path| allowed(path));
+			let children = filter(&item.children, allowed);
+			(page.is_some() || !children.is_empty()).then(|| NavItem {
+				title:    item.title.clone(),
+				path:     item.path.clone(),
+				page,
+				order:    item.order,
+				children,
+			})
+		})
+		.collect()
+}
+
+//		find																	
+/// Finds an entry anywhere in a list of entries and their descendants.
+/// 
+/// # Parameters
+/// 
+/// * `items` - The list of entries to search.
+/// * `path`  - The path of the entry.
+/// 
+fn find<'a>(items: &'a [NavItem], path: &str) -> Option<&'a NavItem> {
+	items.iter().find_map(|item| if item.path == path {
+		Some(item)
+	} else if path.starts_with(&format!("{}/", item.path)) {
+		find(&item.children, path)
+	} else {
+		None
+	})
+}
+
+//		insert																	
+/// Adds a page to a list of entries, descending into directories as needed.
+/// 
+/// # Parameters
+/// 
+/// * `items`    - The list of entries to add to.
+/// * `parent`   - The path of the directory containing the entries.
+/// * `segments` - The remaining segments of the page path.
+/// * `page`     - The full path of the page.
+/// * `title`    - The page title.
+/// * `order`    - The position of the page amongst its siblings, if given.
+/// 
+fn insert(
+	items:    &mut Vec<NavItem>,
+	parent:   &str,
+	segments: &[&str],
+	page:     &str,
+	title:    &str,
+	order:    Option<i64>,
+) {
+	let join = |name: &str| if parent.is_empty() { name.to_owned() } else { format!("{parent}/{name}") };
+	match *segments {
+		[] => {},
+		[name] | [name, "index.md"] => {
+			if let Some(item) = entry(items, &join(name), name) {
+				title.clone_into(&mut item.title);
+				item.page  = Some(page.to_owned());
+				item.order = order;
+			}
+		},
+		[name, ref rest @ ..] => {
+			let path = join(name);
+			if let Some(item) = entry(items, &path, name) {
+				insert(&mut item.children, &path, rest, page, title, order);
+			}
+		},
+	}
+}
+
+//		remove																	
+/// Removes a page from a list of entries, descending into directories as
+/// needed, and removes any entries left empty.
+/// 
+/// # Parameters
+/// 
+/// * `items`    - The list of entries to remove from.
+/// * `parent`   - The path of the directory containing the entries.
+/// * `segments` - The remaining segments of the page path.
+/// 
+fn remove(items: &mut Vec<NavItem>, parent: &str, segments: &[&str]) {
+	let join = |name: &str| if parent.is_empty() { name.to_owned() } else { format!("{parent}/{name}") };
+	match *segments {
+		[] => {},
+		[name] | [name, "index.md"] => {
+			let path = join(name);
+			if let Some(item) = items.iter_mut().find(|item| item.path == path) {
+				name.clone_into(&mut item.title);
+				item.page  = None;
+				item.order = None;
+			}
+		},
+		[name, ref rest @ ..] => {
+			let path = join(name);
+			if let Some(item) = items.iter_mut().find(|item| item.path == path) {
+				remove(&mut item.children, &path, rest);
+			}
+		},
+	}
+	items.retain(|item| item.page.is_some() || !item.children.is_empty());
+}
+
+//		sort																	
+/// Sorts a list of entries and all of their descendants.
+/// 
+/// # Parameters
+/// 
+/// * `items` - The list of entries to sort.
+/// 
+fn sort(items: &mut [NavItem]) {
+	items.sort_by(NavItem::compare);
+	for item in items {
+		sort(&mut item.children);
+	}
+}
+
+
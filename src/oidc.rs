@@ -0,0 +1,495 @@
+//! OpenID Connect functionality.
+//! 
+//! Users can log in using an OpenID Connect provider, as an alternative to the
+//! user store. This uses the authorisation code flow, with PKCE. The provider's
+//! endpoints and signing keys are discovered from its issuer URL, and the ID
+//! token it issues is validated before the user is logged in.
+//! 
+//! Users who log in this way are not in the user store, so they are remembered
+//! in memory for as long as the application runs, which matches the lifetime
+//! of the sessions.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/oidc.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	auth::User,
+	config::OidcConfig,
+};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use core::iter;
+use getrandom::Error as RandomError;
+use jsonwebtoken::{
+	Algorithm,
+	DecodingKey,
+	Validation,
+	decode,
+	decode_header,
+	errors::Error as JwtError,
+	jwk::JwkSet,
+};
+use parking_lot::RwLock;
+use reqwest::{Client, Error as ReqwestError, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use sha2::{Digest as _, Sha256};
+use std::collections::HashMap;
+use thiserror::Error as ThisError;
+use tokio::sync::RwLock as AsyncRwLock;
+
+
+
+//		Constants																										
+
+/// The prefix added to the IDs of users who log in using OpenID Connect, to
+/// distinguish them from users in the user store.
+pub const ID_PREFIX: &str = "oidc:";
+
+/// The session key used to store the details of a login in progress.
+pub const SESSION_KEY: &str = "_oidc_login";
+
+
+
+//		Enums																											
+
+//		OidcError																
+/// Errors that can occur when logging in using OpenID Connect.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum OidcError {
+	/// The provider's configuration could not be discovered.
+	#[error("Could not discover OpenID Connect provider: {0}")]
+	CouldNotDiscover(ReqwestError),
+	
+	/// The authorisation code could not be exchanged for tokens.
+	#[error("Could not exchange authorisation code: {0}")]
+	CouldNotExchangeCode(ReqwestError),
+	
+	/// The provider's signing keys could not be fetched.
+	#[error("Could not fetch OpenID Connect signing keys: {0}")]
+	CouldNotFetchKeys(ReqwestError),
+	
+	/// Random values for the login could not be generated.
+	#[error("Could not generate random values: {0}")]
+	CouldNotGenerateRandom(RandomError),
+	
+	/// The authorisation URL could not be built.
+	#[error("Could not build authorisation URL: {0}")]
+	InvalidAuthorizationUrl(ReqwestError),
+	
+	/// The issuer given by the provider does not match the configured one.
+	#[error("OpenID Connect provider issuer {0} does not match the configured issuer")]
+	InvalidIssuer(String),
+	
+	/// The nonce in the ID token does not match the login in progress.
+	#[error("ID token nonce does not match the login in progress")]
+	InvalidNonce,
+	
+	/// The state returned by the provider does not match the login in
+	/// progress.
+	#[error("State does not match the login in progress")]
+	InvalidState,
+	
+	/// The ID token is not valid.
+	#[error("Invalid ID token: {0}")]
+	InvalidToken(#[from] JwtError),
+	
+	/// A required claim is missing from the ID token.
+	#[error("ID token has no {0} claim")]
+	MissingClaim(String),
+	
+	/// The provider does not have a key that can verify the ID token.
+	#[error("No signing key found for the ID token")]
+	NoMatchingKey,
+	
+	/// The ID token is signed with an algorithm that is not supported.
+	#[error("Unsupported ID token algorithm: {0:?}")]
+	UnsupportedAlgorithm(Algorithm),
+}
+
+
+
+//		Structs																											
+
+//		OidcProvider															
+/// An OpenID Connect provider, which users can log in with.
+#[derive(Debug)]
+pub struct OidcProvider {
+	//		Private properties													
+	/// The configuration options for the provider.
+	config:   OidcConfig,
+	
+	/// The HTTP client used to talk to the provider.
+	http:     Client,
+	
+	/// The signing keys of the provider, fetched when first needed, and again
+	/// when a token is signed with a key that is not known.
+	keys:     AsyncRwLock<JwkSet>,
+	
+	/// The configuration of the provider, discovered when first needed.
+	metadata: AsyncRwLock<Option<ProviderMetadata>>,
+	
+	/// The users who have logged in, keyed by ID.
+	users:    RwLock<HashMap<String, User>>,
+}
+
+//󰭅		OidcProvider															
+impl OidcProvider {
+	//		new																	
+	/// Creates a new OpenID Connect provider.
+	/// 
+	/// Nothing is fetched from the provider until a user logs in, so that the
+	/// application can start even if the provider is unavailable.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `config` - The configuration options for the provider.
+	/// 
+	pub fn new(config: &OidcConfig) -> Self {
+		Self {
+			config:   config.clone(),
+			http:     Client::new(),
+			keys:     AsyncRwLock::new(JwkSet { keys: vec![] }),
+			metadata: AsyncRwLock::new(None),
+			users:    RwLock::new(HashMap::new()),
+		}
+	}
+	
+	//		authorization_url													
+	/// Starts a login.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `uri` - The URI to return to once logged in.
+	/// 
+	/// # Returns
+	/// 
+	/// The URL of the provider to send the user to, and the details of the
+	/// login in progress, which need to be kept in the session until the
+	/// provider sends the user back.
+	/// 
+	/// # Errors
+	/// 
+	/// If the provider cannot be discovered, or the URL cannot be built, an
+	/// error is returned.
+	/// 
+	pub async fn authorization_url(&self, uri: &str) -> Result<(String, PendingLogin), OidcError> {
+		let metadata = self.metadata().await?;
+		let pending  = PendingLogin {
+			state:    random_string()?,
+			nonce:    random_string()?,
+			verifier: random_string()?,
+			uri:      uri.to_owned(),
+		};
+		let scopes   = iter::once("openid")
+			.chain(self.config.scopes.iter().map(String::as_str).filter(|&scope| scope != "openid"))
+			.collect::<Vec<_>>()
+			.join(" ")
+		;
+		let url      = self.http
+			.get(&metadata.authorization_endpoint)
+			.query(&[
+				("response_type",         "code"),
+				("client_id",             &self.config.client_id),
+				("redirect_uri",          &self.config.redirect_url),
+				("scope",                 &scopes),
+				("state",                 &pending.state),
+				("nonce",                 &pending.nonce),
+				("code_challenge",        &URL_SAFE_NO_PAD.encode(Sha256::digest(&pending.verifier))),
+				("code_challenge_method", "S256"),
+			])
+			.build()
+			.map_err(OidcError::InvalidAuthorizationUrl)?
+			.url()
+			.to_string()
+		;
+		Ok((url, pending))
+	}
+	
+	//		login																
+	/// Completes a login, once the provider has sent the user back.
+	/// 
+	/// The authorisation code is exchanged for an ID token, which is validated,
+	/// and the user is then built from its claims. The user's roles are mapped
+	/// from the configured roles claim.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `pending` - The details of the login in progress.
+	/// * `state`   - The state returned by the provider.
+	/// * `code`    - The authorisation code returned by the provider.
+	/// 
+	/// # Errors
+	/// 
+	/// If the state does not match, the code cannot be exchanged, or the ID
+	/// token is not valid, an error is returned.
+	/// 
+	pub async fn login(&self, pending: &PendingLogin, state: &str, code: &str) -> Result<User, OidcError> {
+		if state != pending.state {
+			return Err(OidcError::InvalidState);
+		}
+		let metadata = self.metadata().await?;
+		let tokens   = self.http
+			.post(&metadata.token_endpoint)
+			.basic_auth(&self.config.client_id, Some(&self.config.client_secret))
+			.form(&[
+				("grant_type",    "authorization_code"),
+				("code",          code),
+				("redirect_uri",  &self.config.redirect_url),
+				("code_verifier", &pending.verifier),
+			])
+			.send()
+			.await
+			.and_then(Response::error_for_status)
+			.map_err(OidcError::CouldNotExchangeCode)?
+			.json::<TokenResponse>()
+			.await
+			.map_err(OidcError::CouldNotExchangeCode)?
+		;
+		let claims   = self.validate(&tokens.id_token, &metadata.issuer).await?;
+		if claims.get("nonce").and_then(JsonValue::as_str) != Some(&pending.nonce) {
+			return Err(OidcError::InvalidNonce);
+		}
+		self.user_from_claims(&claims)
+	}
+	
+	//		remember															
+	/// Remembers a user who has logged in, so that they can be found again on
+	/// later requests.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `user` - The user.
+	/// 
+	pub fn remember(&self, user: &User) {
+		drop(self.users.write().insert(user.id.clone(), user.clone()));
+	}
+	
+	//		user																
+	/// Finds a user who has logged in.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `id` - The ID of the user.
+	/// 
+	pub fn user(&self, id: &str) -> Option<User> {
+		self.users.read().get(id).cloned()
+	}
+	
+	//		metadata															
+	/// Gets the configuration of the provider, discovering it if necessary.
+	/// 
+	/// # Errors
+	/// 
+	/// If the configuration cannot be fetched, or is for a different issuer,
+	/// an error is returned.
+	/// 
+	async fn metadata(&self) -> Result<ProviderMetadata, OidcError> {
+		if let Some(ref metadata) = *self.metadata.read().await {
+			return Ok(metadata.clone());
+		}
+		let issuer   = self.config.issuer.trim_end_matches('/');
+		let metadata = self.http
+			.get(format!("{issuer}/.well-known/openid-configuration"))
+			.send()
+			.await
+			.and_then(Response::error_for_status)
+			.map_err(OidcError::CouldNotDiscover)?
+			.json::<ProviderMetadata>()
+			.await
+			.map_err(OidcError::CouldNotDiscover)?
+		;
+		if metadata.issuer.trim_end_matches('/') != issuer {
+			return Err(OidcError::InvalidIssuer(metadata.issuer));
+		}
+		*self.metadata.write().await = Some(metadata.clone());
+		Ok(metadata)
+	}
+	
+	//		signing_key															
+	/// Gets the key to verify an ID token with.
+	/// 
+	/// The keys are fetched again if the key is not known, as the provider may
+	/// have rotated its keys since they were last fetched.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `kid` - The ID of the key, if the token specifies one. If it does not,
+	///           the provider must have exactly one key.
+	/// 
+	/// # Errors
+	/// 
+	/// If the keys cannot be fetched, or no matching key is found, an error is
+	/// returned.
+	/// 
+	async fn signing_key(&self, kid: Option<&str>) -> Result<DecodingKey, OidcError> {
+		let find  = |keys: &JwkSet| kid.map_or_else(
+			|| keys.keys.first().filter(|_| keys.keys.len() == 1).cloned(),
+			|id| keys.find(id).cloned(),
+		);
+		let known = find(&*self.keys.read().await);
+		if let Some(jwk) = known {
+			return Ok(DecodingKey::from_jwk(&jwk)?);
+		}
+		let keys  = self.http
+			.get(&self.metadata().await?.jwks_uri)
+			.send()
+			.await
+			.and_then(Response::error_for_status)
+			.map_err(OidcError::CouldNotFetchKeys)?
+			.json::<JwkSet>()
+			.await
+			.map_err(OidcError::CouldNotFetchKeys)?
+		;
+		let jwk   = find(&keys).ok_or(OidcError::NoMatchingKey)?;
+		*self.keys.write().await = keys;
+		Ok(DecodingKey::from_jwk(&jwk)?)
+	}
+	
+	//		user_from_claims													
+	/// Builds a user from the claims in an ID token.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `claims` - The claims in the ID token.
+	/// 
+	/// # Errors
+	/// 
+	/// If the subject or username claims are missing, an error is returned.
+	/// 
+	fn user_from_claims(&self, claims: &JsonMap<String, JsonValue>) -> Result<User, OidcError> {
+		let claim     = |name: &str| claims.get(name).and_then(JsonValue::as_str).map(ToOwned::to_owned);
+		let subject   = claim("sub").ok_or_else(|| OidcError::MissingClaim("sub".to_owned()))?;
+		let username  = claim(&self.config.username_claim)
+			.ok_or_else(|| OidcError::MissingClaim(self.config.username_claim.clone()))?
+		;
+		let values    = claims.get(&self.config.roles_claim).map_or_else(Vec::new, |value| value.as_array().map_or_else(
+			|| value.as_str().into_iter().collect(),
+			|values| values.iter().filter_map(JsonValue::as_str).collect(),
+		));
+		let mut roles = values
+			.into_iter()
+			.filter_map(|value| self.config.role_map.get(value))
+			.flatten()
+			.cloned()
+			.collect::<Vec<_>>()
+		;
+		roles.sort();
+		roles.dedup();
+		Ok(User { id: format!("{ID_PREFIX}{subject}"), username, roles })
+	}
+	
+	//		validate															
+	/// Validates an ID token.
+	/// 
+	/// The token must be signed by the provider using an asymmetric algorithm,
+	/// must have been issued by the provider to this client, and must not have
+	/// expired.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `id_token` - The ID token.
+	/// * `issuer`   - The issuer of the provider.
+	/// 
+	/// # Returns
+	/// 
+	/// The claims in the ID token.
+	/// 
+	/// # Errors
+	/// 
+	/// If the token is not valid, an error is returned.
+	/// 
+	async fn validate(&self, id_token: &str, issuer: &str) -> Result<JsonMap<String, JsonValue>, OidcError> {
+		let header         = decode_header(id_token)?;
+		//	Symmetric algorithms would allow anyone with the client secret to
+		//	issue tokens, so are not accepted.
+		if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+			return Err(OidcError::UnsupportedAlgorithm(header.alg));
+		}
+		let key            = self.signing_key(header.kid.as_deref()).await?;
+		let mut validation = Validation::new(header.alg);
+		validation.set_issuer(&[issuer]);
+		validation.set_audience(&[&self.config.client_id]);
+		validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+		Ok(decode::<JsonMap<String, JsonValue>>(id_token, &key, &validation)?.claims)
+	}
+}
+
+//		PendingLogin															
+/// The details of a login in progress, which are kept in the session whilst
+/// the user is at the provider.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PendingLogin {
+	//		Public properties													
+	/// The state sent to the provider, which it must send back, to make sure
+	/// that the login was started by this session.
+	pub state:    String,
+	
+	/// The nonce sent to the provider, which it must include in the ID token,
+	/// to make sure that the token was issued for this login.
+	pub nonce:    String,
+	
+	/// The PKCE code verifier, which is needed to exchange the authorisation
+	/// code for tokens.
+	pub verifier: String,
+	
+	/// The URI to return to once logged in.
+	pub uri:      String,
+}
+
+//		ProviderMetadata														
+/// The parts of the configuration of a provider that are needed.
+#[derive(Clone, Debug, Deserialize)]
+struct ProviderMetadata {
+	//		Private properties													
+	/// The issuer of the provider.
+	issuer:                 String,
+	
+	/// The URL to send users to, to log in.
+	authorization_endpoint: String,
+	
+	/// The URL to exchange authorisation codes for tokens at.
+	token_endpoint:         String,
+	
+	/// The URL of the provider's signing keys.
+	jwks_uri:               String,
+}
+
+//		TokenResponse															
+/// The parts of a token response that are needed.
+#[derive(Clone, Debug, Deserialize)]
+struct TokenResponse {
+	//		Private properties													
+	/// The ID token.
+	id_token: String,
+}
+
+
+
+//		Functions																										
+
+//		random_string															
+/// Generates a random string, suitable for use as a state, nonce, or PKCE
+/// code verifier.
+/// 
+/// # Errors
+/// 
+/// If random bytes cannot be generated, an error is returned.
+/// 
+fn random_string() -> Result<String, OidcError> {
+	let mut bytes = [0_u8; 32];
+	getrandom::fill(&mut bytes).map_err(OidcError::CouldNotGenerateRandom)?;
+	Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+
@@ -0,0 +1,393 @@
+//! Post-processing pipeline functionality.
+//! 
+//! Once Markdown has been converted to HTML, the HTML is post-processed by a
+//! series of [`Processor`]s, each of which transforms the document tree in
+//! place. Rustmark's own transforms, for details blocks, callouts, collapsible
+//! headings, diagrams, and maths, are provided as built-in processors, and
+//! further processors can be registered with a [`PipelineBuilder`] to add
+//! custom transforms without changing the parser.
+//! 
+//! Which processors run, in what order, and with what options, can be set by a
+//! [`PipelineConfig`], which can be read from configuration. Processors run in
+//! ascending order, and processors with the same order run in order of name.
+//! 
+
+
+
+//		Packages																										
+
+use super::{
+	diagrams::DiagramsProcessor,
+	math::MathProcessor,
+	process_callouts,
+	process_details,
+	process_headings,
+};
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use nipper::Document;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use thiserror::Error as ThisError;
+
+
+
+//		Enums																											
+
+//		PipelineError															
+/// Represents all possible errors that can occur when building a pipeline.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum PipelineError {
+	/// The options given for a processor are not valid.
+	#[error("Invalid options for processor {0}: {1}")]
+	InvalidOptions(String, String),
+	
+	/// The configuration refers to a processor that has not been registered.
+	#[error("Unknown processor: {0}")]
+	UnknownProcessor(String),
+}
+
+
+
+//		Structs																											
+
+//		CalloutsProcessor														
+/// Converts blockquotes that start with a single `**strong**` word into
+/// callouts.
+/// 
+/// See [`process_callouts()`].
+/// 
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct CalloutsProcessor;
+
+//󰭅		Processor																
+impl Processor for CalloutsProcessor {
+	//		name																
+	fn name(&self) -> &'static str {
+		"callouts"
+	}
+	
+	//		order																
+	fn order(&self) -> i32 {
+		200
+	}
+	
+	//		process																
+	fn process(&self, document: &Document) {
+		process_callouts(&document.select("blockquote"));
+	}
+}
+
+//		DetailsProcessor														
+/// Converts blockquotes that start with `->` lines into details blocks.
+/// 
+/// See [`process_details()`].
+/// 
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct DetailsProcessor;
+
+//󰭅		Processor																
+impl Processor for DetailsProcessor {
+	//		name																
+	fn name(&self) -> &'static str {
+		"details"
+	}
+	
+	//		order																
+	fn order(&self) -> i32 {
+		100
+	}
+	
+	//		process																
+	fn process(&self, document: &Document) {
+		process_details(&document.select("blockquote"));
+	}
+}
+
+//		HeadingsProcessor														
+/// Makes the sections under each heading collapsible.
+/// 
+/// See [`process_headings()`].
+/// 
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct HeadingsProcessor;
+
+//󰭅		Processor																
+impl Processor for HeadingsProcessor {
+	//		name																
+	fn name(&self) -> &'static str {
+		"headings"
+	}
+	
+	//		order																
+	fn order(&self) -> i32 {
+		300
+	}
+	
+	//		process																
+	fn process(&self, document: &Document) {
+		process_headings(document);
+	}
+}
+
+//		Pipeline																
+/// A series of processors to run over the HTML generated from Markdown.
+/// 
+/// The default pipeline contains the built-in processors, in their default
+/// order.
+/// 
+#[derive(Debug)]
+pub struct Pipeline {
+	//		Private properties													
+	/// The processors, in the order they run.
+	processors: Vec<Box<dyn Processor>>,
+}
+
+//󰭅		Pipeline																
+impl Pipeline {
+	//		builder																
+	/// Creates a builder for a pipeline, with the built-in processors
+	/// registered.
+	#[must_use]
+	pub fn builder() -> PipelineBuilder {
+		PipelineBuilder::new()
+	}
+	
+	//		names																
+	/// The names of the processors, in the order they run.
+	#[must_use]
+	pub fn names(&self) -> Vec<&str> {
+		self.processors.iter().map(|processor| processor.name()).collect()
+	}
+	
+	//		run																	
+	/// Runs each processor over a document, in order.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `document` - The HTML document tree to process.
+	/// 
+	pub fn run(&self, document: &Document) {
+		for processor in &self.processors {
+			processor.process(document);
+		}
+	}
+}
+
+//󰭅		Default																	
+impl Default for Pipeline {
+	//		default																
+	fn default() -> Self {
+		Self {
+			processors: vec![
+				Box::new(DetailsProcessor),
+				Box::new(CalloutsProcessor),
+				Box::new(HeadingsProcessor),
+				Box::new(DiagramsProcessor::default()),
+				Box::new(MathProcessor::default()),
+			],
+		}
+	}
+}
+
+//		PipelineBuilder															
+/// Assembles a [`Pipeline`] from registered processors and configuration.
+/// 
+/// Every registered processor is included unless the configuration disables
+/// it, and the configuration can also change the order processors run in, and
+/// pass options to them.
+/// 
+pub struct PipelineBuilder {
+	//		Private properties													
+	/// The functions that create each processor, keyed by name.
+	factories: BTreeMap<String, ProcessorFactory>,
+	
+	/// The configuration to apply.
+	config:    PipelineConfig,
+}
+
+//󰭅		PipelineBuilder															
+impl PipelineBuilder {
+	//		new																	
+	/// Creates a new builder, with the built-in processors registered.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			factories: BTreeMap::new(),
+			config:    PipelineConfig::default(),
+		}
+			.register("callouts", |_| Ok(Box::new(CalloutsProcessor)))
+			.register("details",  |_| Ok(Box::new(DetailsProcessor)))
+			.register("diagrams", |options| Ok(Box::new(DiagramsProcessor::from_options(options)?)))
+			.register("headings", |_| Ok(Box::new(HeadingsProcessor)))
+			.register("math",     |options| Ok(Box::new(MathProcessor::from_options(options)?)))
+	}
+	
+	//		build																
+	/// Builds the pipeline.
+	/// 
+	/// # Errors
+	/// 
+	/// If the configuration refers to a processor that has not been
+	/// registered, or a processor does not accept the options given for it, an
+	/// error will be returned.
+	/// 
+	pub fn build(&self) -> Result<Pipeline, PipelineError> {
+		if let Some(unknown) = self.config.processors.keys().find(|name| !self.factories.contains_key(*name)) {
+			return Err(PipelineError::UnknownProcessor(unknown.clone()));
+		}
+		let mut processors = vec![];
+		for (name, factory) in &self.factories {
+			let config    = self.config.processors.get(name).cloned().unwrap_or_default();
+			if !config.enabled {
+				continue;
+			}
+			let processor = factory(&config.options)?;
+			processors.push((config.order.unwrap_or_else(|| processor.order()), processor));
+		}
+		//	The factories are sorted by name, and the sort is stable, so
+		//	processors with the same order run in order of name.
+		processors.sort_by_key(|entry| entry.0);
+		Ok(Pipeline { processors: processors.into_iter().map(|entry| entry.1).collect() })
+	}
+	
+	//		config																
+	/// Sets the configuration to apply.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `config` - The pipeline configuration.
+	/// 
+	#[must_use]
+	pub fn config(mut self, config: &PipelineConfig) -> Self {
+		config.clone_into(&mut self.config);
+		self
+	}
+	
+	//		register															
+	/// Registers a processor, so that it is included in the pipeline.
+	/// 
+	/// If a processor with the same name has already been registered, it is
+	/// replaced, which allows the built-in processors to be overridden.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `name`    - The name of the processor, as used in the configuration.
+	/// * `factory` - A function or closure that creates the processor from the
+	///               options given in the configuration. Closures can capture
+	///               state, such as shared resources for the processor.
+	/// 
+	#[must_use]
+	pub fn register<F>(mut self, name: &str, factory: F) -> Self
+	where
+		F: Fn(&JsonValue) -> Result<Box<dyn Processor>, PipelineError> + Send + Sync + 'static,
+	{
+		drop(self.factories.insert(name.to_owned(), Box::new(factory)));
+		self
+	}
+}
+
+//󰭅		Debug																	
+impl Debug for PipelineBuilder {
+	//		fmt																	
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		f.debug_struct("PipelineBuilder")
+			.field("factories", &self.factories.keys().collect::<Vec<_>>())
+			.field("config",    &self.config)
+			.finish()
+	}
+}
+
+//󰭅		Default																	
+impl Default for PipelineBuilder {
+	//		default																
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+//		PipelineConfig															
+/// The configuration options for a [`Pipeline`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct PipelineConfig {
+	//		Public properties													
+	/// The configuration for individual processors, keyed by name. Processors
+	/// that are not listed run with their default settings.
+	pub processors: BTreeMap<String, ProcessorConfig>,
+}
+
+//		ProcessorConfig															
+/// The configuration options for a single [`Processor`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct ProcessorConfig {
+	//		Public properties													
+	/// Whether the processor runs.
+	pub enabled: bool,
+	
+	/// The position of the processor in the pipeline, overriding its default.
+	pub order:   Option<i32>,
+	
+	/// Options for the processor, which it interprets itself.
+	pub options: JsonValue,
+}
+
+//󰭅		Default																	
+impl Default for ProcessorConfig {
+	//		default																
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			order:   None,
+			options: JsonValue::Null,
+		}
+	}
+}
+
+
+
+//		Traits																											
+
+//§		Processor																
+/// A transform applied to the HTML generated from Markdown.
+/// 
+/// Processors operate on the parsed document tree, and change it in place.
+/// They run after the title and table of contents have been extracted, and
+/// before the links are collected.
+/// 
+pub trait Processor: Debug + Send + Sync {
+	//		name																
+	/// The name of the processor, as used in the configuration.
+	fn name(&self) -> &str;
+	
+	//		order																
+	/// The default position of the processor in the pipeline. Lower numbers
+	/// run first. The built-in processors use 100, 200, 300, 400, and 500.
+	fn order(&self) -> i32;
+	
+	//		process																
+	/// Transforms the document.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `document` - The HTML document tree to process.
+	/// 
+	fn process(&self, document: &Document);
+}
+
+
+
+//		Types																											
+
+/// A function or closure that creates a [`Processor`] from the options given in
+/// the configuration.
+pub type ProcessorFactory = Box<dyn Fn(&JsonValue) -> Result<Box<dyn Processor>, PipelineError> + Send + Sync>;
+
+
@@ -0,0 +1,359 @@
+//! Print functionality.
+//! 
+//! A page, or all the pages in a directory, can be rendered as a single HTML
+//! document that is suitable for printing, or for converting to PDF. The
+//! document is self-contained, so that it can be saved and opened anywhere:
+//! the stylesheets are included in it, and images are inlined as data URIs.
+//! All collapsible sections are expanded, so that nothing is hidden, and the
+//! table of contents is shown as a numbered list at the start.
+//! 
+//! When the pages in a directory are combined, they are given in the same order
+//! as in the navigation tree, with each one starting on a new printed page. The
+//! IDs in each page are prefixed, so that the links within a page still work
+//! once the pages are combined.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/print.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	access::content_path,
+	state::{AppState, PageAccess},
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use nipper::Document;
+use rubedo::sugar::s;
+use rustmark::{
+	CompiledPageError,
+	Heading,
+	diagrams::prefix_ids,
+	links::resolve_path,
+};
+use serde::Serialize;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use terracotta::app::{
+	config::LoadingBehavior,
+	errors::AppError,
+	state::StateProvider as _,
+};
+use tera::Context;
+use thiserror::Error as ThisError;
+
+
+
+//		Enums																											
+
+//		PrintError																
+/// Errors that can occur when creating a printable document.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum PrintError {
+	/// A page could not be loaded.
+	#[error("Could not load page {0}: {1}")]
+	CouldNotLoadPage(String, CompiledPageError),
+	
+	/// The document could not be rendered using the HTML templates.
+	#[error("Could not render printable document for {0}: {1}")]
+	CouldNotRenderDocument(String, Box<AppError>),
+	
+	/// There are no pages at the path given that can be accessed.
+	#[error("No pages found at {0}")]
+	NotFound(String),
+}
+
+
+
+//		Structs																											
+
+//		PrintOptions															
+/// The options for creating a printable document from the command line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PrintOptions {
+	//		Public properties													
+	/// The path of the page or directory to print, relative to the content
+	/// root. An empty path prints all the pages.
+	pub path:   String,
+	
+	/// The file to write the document to. If not given, the document is written
+	/// to standard output.
+	pub output: Option<PathBuf>,
+	
+	/// The roles to create the document for. Only pages and images that a user
+	/// with these roles would be allowed to access are included.
+	pub roles:  Vec<String>,
+}
+
+//		PrintPage																
+/// A page in a printable document.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct PrintPage {
+	//		Private properties													
+	/// The ID of the page within the document.
+	id:    String,
+	
+	/// The path of the page, relative to the content root.
+	path:  String,
+	
+	/// The title of the page.
+	title: String,
+	
+	/// The HTML content of the page, prepared for printing.
+	html:  String,
+}
+
+//		TocItem																	
+/// An entry in the table of contents of a printable document.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct TocItem {
+	//		Private properties													
+	/// The ID of the element to link to.
+	id:       String,
+	
+	/// The text to show.
+	text:     String,
+	
+	/// The entries below this one.
+	children: Vec<Self>,
+}
+
+
+
+//		Functions																										
+
+//		print_document															
+/// Renders a page, or all the pages in a directory, as a single printable HTML
+/// document.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `path`  - The path of the page or directory, relative to the content
+///             root. A path that does not end in `.md` is treated as a
+///             directory, and an empty path covers all the pages.
+/// * `roles` - The roles of the user the document is for, which limit the
+///             pages and images included.
+/// 
+/// # Errors
+/// 
+/// If there are no pages that can be accessed at the path, or any page cannot
+/// be loaded, or the document cannot be rendered, an error is returned.
+/// 
+pub async fn print_document(state: &AppState, path: &str, roles: &[String]) -> Result<String, PrintError> {
+	#[expect(clippy::case_sensitive_file_extension_comparisons, reason = "The extension should always be lowercase")]
+	let single    = path.ends_with(".md");
+	let dir       = path.trim_matches('/');
+	let paths     = if single {
+		vec![path.to_owned()]
+	} else if dir.is_empty() {
+		let mut paths = vec![s!("index.md")];
+		paths.extend(state.nav.read().pages(dir));
+		paths
+	} else {
+		state.nav.read().pages(dir)
+	};
+	let mut pages = vec![];
+	let mut toc   = vec![];
+	for page_path in paths {
+		let page_access = state.accessible_page(&page_path, roles)
+			.map_err(|err| PrintError::CouldNotLoadPage(page_path.clone(), err))?
+		;
+		let PageAccess::Allowed(page) = page_access else {
+			continue;
+		};
+		let id       = format!("page-{}", pages.len().saturating_add(1));
+		let prefix   = if single { String::new() } else { format!("{id}-") };
+		let headings = toc_tree(&page.toc, &prefix);
+		if single {
+			toc = headings;
+		} else {
+			toc.push(TocItem { id: id.clone(), text: page.title.clone(), children: headings });
+		}
+		pages.push(PrintPage {
+			html:  prepare_html(state, &page_path, &page.html, &prefix, roles),
+			id,
+			path:  page_path,
+			title: page.title.clone(),
+		});
+	}
+	let Some(first) = pages.first() else {
+		return Err(PrintError::NotFound(path.to_owned()));
+	};
+	let title       = if single || first.path == format!("{dir}/index.md") {
+		first.title.clone()
+	} else if dir.is_empty() {
+		state.config.title.clone()
+	} else {
+		dir.rsplit('/').next().unwrap_or(dir).to_owned()
+	};
+	let styles      = ["css/styles.css", "css/custom.css"]
+		.iter()
+		.filter_map(|file| load_file(state, file, roles))
+		.map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+		.collect::<Vec<_>>()
+		.join("\n")
+	;
+	let mut context = Context::new();
+	context.insert("Title",  &title);
+	context.insert("Site",   &state.config.title);
+	context.insert("ToC",    &toc);
+	context.insert("Pages",  &pages);
+	context.insert("Styles", &styles);
+	context.insert("Roles",  roles);
+	state.render("print", &context).await
+		.map_err(|err| PrintError::CouldNotRenderDocument(path.to_owned(), Box::new(err)))
+}
+
+//		add_toc_item															
+/// Adds an entry to the table of contents, below the last entry at each level
+/// down to the depth given.
+/// 
+/// # Parameters
+/// 
+/// * `items` - The entries to add to.
+/// * `depth` - The number of levels to descend before adding the entry.
+/// * `item`  - The entry to add.
+/// 
+fn add_toc_item(items: &mut Vec<TocItem>, depth: usize, item: TocItem) {
+	if depth > 0 && let Some(parent) = items.last_mut() {
+		add_toc_item(&mut parent.children, depth.saturating_sub(1), item);
+		return;
+	}
+	items.push(item);
+}
+
+//		image_type																
+/// Finds the MIME type of an image file from its extension.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path of the file.
+/// 
+fn image_type(path: &str) -> Option<&'static str> {
+	match Path::new(path).extension()?.to_str()?.to_ascii_lowercase().as_str() {
+		"avif"         => Some("image/avif"),
+		"bmp"          => Some("image/bmp"),
+		"gif"          => Some("image/gif"),
+		"ico"          => Some("image/vnd.microsoft.icon"),
+		"jpeg" | "jpg" => Some("image/jpeg"),
+		"png"          => Some("image/png"),
+		"svg"          => Some("image/svg+xml"),
+		"webp"         => Some("image/webp"),
+		_              => None,
+	}
+}
+
+//		load_file																
+/// Loads a file that would be served by the application.
+/// 
+/// Public static assets are looked for first, followed by protected files that
+/// the roles given allow access to. The configured loading behaviour decides
+/// whether the baked-in or the local copy is used, when there are both.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `path`  - The path of the file, relative to the root of the site.
+/// * `roles` - The roles to check access to protected files with.
+/// 
+fn load_file(state: &AppState, path: &str, roles: &[String]) -> Option<Vec<u8>> {
+	let assets    = &state.config.assets;
+	let public    = Some((&state.assets_dir, assets.public_assets.behavior, &assets.public_assets.local_path));
+	let protected = state.access.allows(path, roles)
+		.then_some((&state.content_dir, assets.protected_assets.behavior, &assets.protected_assets.local_path))
+	;
+	[public, protected].into_iter().flatten().find_map(|(dir, behavior, local_path)| {
+		let baked = || dir.get_file(path).map(|file| file.contents().to_vec());
+		let local = || fs::read(local_path.join(path)).ok();
+		match behavior {
+			LoadingBehavior::Deny       => baked(),
+			LoadingBehavior::Override   => local().or_else(baked),
+			LoadingBehavior::Supplement => baked().or_else(local),
+		}
+	})
+}
+
+//		prepare_html															
+/// Prepares the HTML of a page for printing.
+/// 
+/// All collapsible sections are expanded, images are inlined as data URIs, and
+/// the IDs are prefixed if a prefix is given.
+/// 
+/// # Parameters
+/// 
+/// * `state`  - The application state.
+/// * `path`   - The path of the page, relative to the content root.
+/// * `html`   - The HTML of the page.
+/// * `prefix` - The prefix to add to the IDs in the page, if any.
+/// * `roles`  - The roles to check access to images with.
+/// 
+fn prepare_html(state: &AppState, path: &str, html: &str, prefix: &str, roles: &[String]) -> String {
+	let document = Document::from(html);
+	document.select("details").set_attr("open", "");
+	for mut img in document.select("img[src]").iter() {
+		let Some(src) = img.attr("src") else {
+			continue;
+		};
+		//	The path is decoded, so it has to be checked in the same way as a
+		//	request would be, to stop it reaching outside of the content root.
+		let Some(file) = resolve_path(path, &src).and_then(|(file, _)| content_path(&file).map(ToOwned::to_owned)) else {
+			continue;
+		};
+		if let Some((mime, bytes)) = image_type(&file).zip(load_file(state, &file, roles)) {
+			img.set_attr("src", &format!("data:{mime};base64,{}", STANDARD.encode(bytes)));
+		}
+	}
+	let body     = document.select("body").html();
+	let content  = body
+		.strip_prefix("<body>")
+		.and_then(|content| content.strip_suffix("</body>"))
+		.unwrap_or(&body)
+	;
+	if prefix.is_empty() {
+		content.to_owned()
+	} else {
+		prefix_ids(content, prefix)
+	}
+}
+
+//		toc_tree																
+/// Arranges the headings of a page into a tree, for the table of contents.
+/// 
+/// The top-level heading is left out, as it is the title of the page. Each
+/// heading is placed under the closest preceding heading of a higher level.
+/// 
+/// # Parameters
+/// 
+/// * `headings` - The headings of the page, in order.
+/// * `prefix`   - The prefix added to the IDs in the page, if any.
+/// 
+fn toc_tree(headings: &[Heading], prefix: &str) -> Vec<TocItem> {
+	let mut items  = vec![];
+	//	The levels of the headings that the next heading could be placed under
+	let mut levels = vec![];
+	for heading in headings.iter().filter(|heading| heading.level > 1) {
+		while levels.last().is_some_and(|&level| level >= heading.level) {
+			_ = levels.pop();
+		}
+		add_toc_item(&mut items, levels.len(), TocItem {
+			id:       format!("{prefix}{}", heading.id),
+			text:     heading.text.clone(),
+			children: vec![],
+		});
+		levels.push(heading.level);
+	}
+	items
+}
+
+
@@ -0,0 +1,82 @@
+//! Live reloading of local Markdown content.
+//! 
+//! When enabled, the local Markdown content directory is watched for changes,
+//! and the paths of any changed files are broadcast to connected browsers, so
+//! that pages can be reloaded as they are edited.
+//! 
+
+
+
+//		Packages																										
+
+use crate::state::AppState;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+
+
+//		Functions																										
+
+//		watch																	
+/// Starts watching the local Markdown content for changes.
+/// 
+/// The paths of changed files are sent, relative to the content root, to the
+/// application state's reload channel, once the details held about any
+/// changed pages have been [refreshed](AppState::refresh_local_page()).
+/// Nothing is watched unless live reload has been enabled and local Markdown
+/// content is allowed.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+/// # Returns
+/// 
+/// The watcher, if watching was started. This needs to be kept alive for as
+/// long as changes should be detected.
+/// 
+pub fn watch(state: &Arc<AppState>) -> Option<RecommendedWatcher> {
+	if !state.live_reload() {
+		return None;
+	}
+	let root = match state.config.markdown.local_path.canonicalize() {
+		Ok(root) => root,
+		Err(err) => {
+			warn!("Cannot watch {} for changes: {err}", state.config.markdown.local_path.display());
+			return None;
+		},
+	};
+	let app_state   = Arc::clone(state);
+	let prefix      = root.clone();
+	let created     = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+		Ok(event) => {
+			if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+				return;
+			}
+			for path in event.paths.iter().filter_map(|path| path.strip_prefix(&prefix).ok()) {
+				let changed = path.to_string_lossy().replace('\\', "/");
+				debug!("Local content changed: {changed}");
+				app_state.refresh_local_page(&changed);
+				//	An error only means that nobody is listening at the moment
+				drop(app_state.reload.send(changed));
+			}
+		},
+		Err(err) => warn!("Error watching local content: {err}"),
+	});
+	let mut watcher = match created {
+		Ok(watcher) => watcher,
+		Err(err)    => {
+			warn!("Cannot watch {} for changes: {err}", root.display());
+			return None;
+		},
+	};
+	if let Err(err) = watcher.watch(&root, RecursiveMode::Recursive) {
+		warn!("Cannot watch {} for changes: {err}", root.display());
+		return None;
+	}
+	info!("Watching {} for changes", root.display());
+	Some(watcher)
+}
+
+
@@ -0,0 +1,97 @@
+//! Request data for the application.
+
+
+
+//		Packages																										
+
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+
+
+//		Structs																											
+
+//		GetAuditParams															
+/// The parameters for the [`get_audit()`](crate::handlers::get_audit())
+/// handler.
+#[derive(Clone, Debug, Default, Deserialize, Eq, IntoParams, PartialEq)]
+pub struct GetAuditParams {
+	//		Public properties													
+	/// The username to show events for. Defaults to all users.
+	pub user:  Option<String>,
+	
+	/// The path prefix to show events for, such as `/hr/`. Defaults to all
+	/// paths.
+	pub path:  Option<String>,
+	
+	/// The maximum number of events to return. Defaults to 100, and cannot be
+	/// more than 1,000.
+	pub limit: Option<usize>,
+}
+
+//		GetLoginParams															
+/// The parameters for the [`get_login()`](crate::handlers::get_login())
+/// handler.
+#[derive(Clone, Debug, Default, Deserialize, Eq, IntoParams, PartialEq)]
+pub struct GetLoginParams {
+	//		Public properties													
+	/// The URI to return to after logging in. Defaults to the home page.
+	pub uri: Option<String>,
+}
+
+//		GetOidcCallbackParams													
+/// The parameters for the
+/// [`get_oidc_callback()`](crate::handlers::get_oidc_callback()) handler.
+/// 
+/// These are sent by the OpenID Connect provider when it redirects the user
+/// back after logging in.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Eq, IntoParams, PartialEq)]
+pub struct GetOidcCallbackParams {
+	//		Public properties													
+	/// The authorisation code, if the user logged in.
+	pub code:              Option<String>,
+	
+	/// The state sent to the provider when the login was started.
+	pub state:             Option<String>,
+	
+	/// The error code, if the user did not log in.
+	pub error:             Option<String>,
+	
+	/// A description of the error, if the user did not log in.
+	pub error_description: Option<String>,
+}
+
+//		GetPageParams															
+/// The parameters for the [`get_page()`](crate::handlers::get_page())
+/// handler.
+#[derive(Clone, Debug, Default, Deserialize, Eq, IntoParams, PartialEq)]
+pub struct GetPageParams {
+	//		Public properties													
+	/// The format to show the page in. Set to `print` for a single,
+	/// self-contained HTML document suitable for printing, which for a
+	/// directory path covers all the pages within it. Defaults to the normal
+	/// page.
+	pub format: Option<String>,
+	
+	/// If present, with any value or none, the original Markdown of the page is
+	/// returned, as if `text/markdown` had been requested.
+	pub raw:    Option<String>,
+}
+
+//		GetSearchParams															
+/// The parameters for the [`get_search()`](crate::handlers::get_search())
+/// handler.
+#[derive(Clone, Debug, Default, Deserialize, Eq, IntoParams, PartialEq)]
+pub struct GetSearchParams {
+	//		Public properties													
+	/// The search query. Words are matched in full, apart from the last word,
+	/// which is also matched as a prefix.
+	pub q:     Option<String>,
+	
+	/// The maximum number of results to return. Defaults to 20, and cannot be
+	/// more than 100.
+	pub limit: Option<usize>,
+}
+
+
@@ -0,0 +1,210 @@
+//! Response data for the application.
+
+
+
+//		Packages																										
+
+use crate::{
+	cache::CacheStats,
+	tokens::TokenUsage,
+};
+use chrono::{DateTime, Utc};
+use rustmark::{CompiledPage, Heading, PageMetadata, search::SearchResult};
+use serde::Serialize;
+use terracotta::stats::responses::StatsResponse as BaseStatsResponse;
+use utoipa::ToSchema;
+
+
+
+//		Structs																											
+
+//		AppStatsResponse														
+/// The application statistics returned by the `/api/stats` endpoint.
+/// 
+/// This extends the standard statistics with those of the page cache.
+/// 
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct AppStatsResponse {
+	//		Public properties													
+	/// The standard application statistics.
+	#[serde(flatten)]
+	pub stats: BaseStatsResponse,
+	
+	/// The page cache statistics.
+	pub cache: CacheStatsResponse,
+}
+
+//		CacheStatsResponse														
+/// The page cache statistics returned as part of the `/api/stats` endpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct CacheStatsResponse {
+	//		Public properties													
+	/// The maximum number of pages that can be cached.
+	pub capacity: usize,
+	
+	/// The number of pages currently cached.
+	pub entries:  usize,
+	
+	/// The number of requests for local pages that were served from the cache.
+	pub hits:     u64,
+	
+	/// The number of requests for local pages that required parsing.
+	pub misses:   u64,
+}
+
+//󰭅		From<CacheStats>														
+impl From<CacheStats> for CacheStatsResponse {
+	//		from																
+	fn from(stats: CacheStats) -> Self {
+		Self {
+			capacity: stats.capacity,
+			entries:  stats.entries,
+			hits:     stats.hits,
+			misses:   stats.misses,
+		}
+	}
+}
+
+//		HeadingResponse															
+/// A heading in the table of contents of a page.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct HeadingResponse {
+	//		Public properties													
+	/// The level of the heading, from 1 to 6.
+	pub level: u8,
+	
+	/// The HTML id of the heading, for use as an anchor.
+	pub id:    String,
+	
+	/// The text of the heading.
+	pub text:  String,
+}
+
+//󰭅		From<Heading>															
+impl From<Heading> for HeadingResponse {
+	//		from																
+	fn from(heading: Heading) -> Self {
+		Self {
+			level: heading.level,
+			id:    heading.id,
+			text:  heading.text,
+		}
+	}
+}
+
+//		PageResponse															
+/// A page returned as JSON by the page endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct PageResponse {
+	//		Public properties													
+	/// The page title.
+	pub title:    String,
+	
+	/// The table of contents, listing the headings in the page in order.
+	pub toc:      Vec<HeadingResponse>,
+	
+	/// The rendered HTML content of the page.
+	pub html:     String,
+	
+	/// The metadata declared in the front matter, such as the description,
+	/// tags, author, and date, along with any custom fields.
+	#[schema(value_type = Object)]
+	pub metadata: PageMetadata,
+}
+
+//󰭅		From<CompiledPage>														
+impl From<CompiledPage> for PageResponse {
+	//		from																
+	fn from(page: CompiledPage) -> Self {
+		Self {
+			title:    page.title,
+			toc:      page.toc.into_iter().map(Into::into).collect(),
+			html:     page.html,
+			metadata: page.metadata,
+		}
+	}
+}
+
+//		SearchResponse															
+/// The search results returned by the `/search` endpoint.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct SearchResponse {
+	//		Public properties													
+	/// The search query that was performed.
+	pub query:   String,
+	
+	/// The matching pages, in descending order of relevance.
+	pub results: Vec<SearchResultResponse>,
+}
+
+//		SearchResultResponse													
+/// A single search result.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct SearchResultResponse {
+	//		Public properties													
+	/// The path of the page, relative to the content root.
+	pub path:    String,
+	
+	/// The URL of the page, including the anchor of the closest heading.
+	pub url:     String,
+	
+	/// The page title.
+	pub title:   String,
+	
+	/// The HTML id of the heading closest to the first match, if any.
+	pub anchor:  Option<String>,
+	
+	/// The text of the heading closest to the first match, if any.
+	pub heading: Option<String>,
+	
+	/// An extract of the page text surrounding the first match.
+	pub snippet: String,
+	
+	/// The relevance score of the page. Higher is better.
+	pub score:   f64,
+}
+
+//󰭅		From<SearchResult>														
+impl From<SearchResult> for SearchResultResponse {
+	//		from																
+	fn from(result: SearchResult) -> Self {
+		let page = if result.path == "index.md" { "" } else { result.path.as_str() };
+		Self {
+			url:     result.anchor.as_ref().map_or_else(
+				|| format!("/{page}"),
+				|anchor| format!("/{page}#{anchor}"),
+			),
+			path:    result.path,
+			title:   result.title,
+			anchor:  result.anchor,
+			heading: result.heading,
+			snippet: result.snippet,
+			score:   result.score,
+		}
+	}
+}
+
+//		TokenStatsResponse														
+/// The use of an API token, returned by the `/api/stats/tokens` endpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct TokenStatsResponse {
+	//		Public properties													
+	/// The number of requests made using the token.
+	pub requests:  u64,
+	
+	/// When the token was last used, if at all.
+	pub last_used: Option<DateTime<Utc>>,
+}
+
+//󰭅		From<TokenUsage>														
+impl From<TokenUsage> for TokenStatsResponse {
+	//		from																
+	fn from(usage: TokenUsage) -> Self {
+		Self {
+			requests:  usage.requests,
+			last_used: usage.last_used,
+		}
+	}
+}
+
+
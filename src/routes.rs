@@ -2,32 +2,202 @@
 
 
 
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/routes.rs"]
+mod tests;
+
+
+
 //		Packages																										
 
 use crate::{
+	audit::audit_layer,
 	auth::{Credentials, User},
-	handlers::{get_index, get_page},
+	config::Config,
+	errors::error_layer,
+	handlers::{
+		get_audit,
+		get_index,
+		get_login,
+		get_oidc_callback,
+		get_oidc_login,
+		get_page,
+		get_reload_feed,
+		get_search,
+		get_stats,
+		get_token_stats,
+	},
 	state::AppState,
+	tokens::token_layer,
+	utility::ApiDoc,
 };
-use axum::routing::{MethodRouter, get, post};
-use std::sync::Arc;
+use axum::{
+	Router,
+	middleware::from_fn_with_state,
+	routing::{MethodRouter, get},
+};
+use std::{
+	collections::BTreeSet,
+	sync::Arc,
+};
+use tower_http::catch_panic::CatchPanicLayer;
 use terracotta::{
+	app::routing::RouterExt as _,
 	assets::handlers::get_public_static_asset,
-	auth::handlers::{get_logout, post_login},
+	auth::{
+		handlers::{get_logout, post_login},
+		routing::RouterExt as _,
+	},
+	errors::{
+		middleware::no_route,
+		routing::RouterExt as _,
+	},
 	health::handlers::{get_ping, get_version},
-	stats::handlers::{get_stats, get_stats_feed, get_stats_history},
+	stats::{
+		handlers::{get_stats_feed, get_stats_history},
+		routing::RouterExt as _,
+	},
 };
+use thiserror::Error as ThisError;
+use utoipa::OpenApi as _;
+
+
+
+//		Enums																											
+
+//		RouteError																
+/// Errors that can occur when setting up the routes.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum RouteError {
+	/// A public path prefix would be served at the same path as one of the
+	/// application's own routes.
+	#[error("Public path prefix {0} clashes with the application route {1}")]
+	ReservedPublicPrefix(String, String),
+}
 
 
 
 //		Functions																										
 
+//		app																		
+/// Creates the application router.
+/// 
+/// This follows the same arrangement as Terracotta's full application, with
+/// the addition of API token authentication. This has to run after the session
+/// has been checked, so that a token can stand in for a logged-in user, but
+/// before the protected routes are guarded.
+/// 
+/// The error pages are shown by the application's own
+/// [error layer](error_layer()) instead of Terracotta's, so that the pages
+/// shown for errors with Markdown pages can include more detail.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+pub fn app(state: &Arc<AppState>) -> Router {
+	let content = public_content(state);
+	let public  = public()
+		.into_iter()
+		.chain(content.iter().map(|route| (route.0.as_str(), route.1.clone())))
+		.collect()
+	;
+	Router::new()
+		.protected_routes::<_, User>(protected(state), state)
+		.public_routes(public)
+		.add_openapi("/api-docs", ApiDoc::openapi())
+		.fallback(no_route)
+		.add_protected_error_catcher::<_, User>(state)
+		.layer(CatchPanicLayer::new())
+		.layer(from_fn_with_state(Arc::clone(state), error_layer))
+		.layer(from_fn_with_state(Arc::clone(state), token_layer))
+		.add_authentication::<_, User, User>(state)
+		.add_stats_gathering(state)
+		.with_state(Arc::clone(state))
+		.add_http_logging()
+		.add_error_catcher()
+}
+
+//		audited																	
+/// Records requests to a content route in the audit log.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `route` - The route.
+/// 
+fn audited(state: &Arc<AppState>, route: MethodRouter<Arc<AppState>>) -> MethodRouter<Arc<AppState>> {
+	route.layer(from_fn_with_state(Arc::clone(state), audit_layer))
+}
+
+//		check_public															
+/// Checks that the public path prefixes can be routed.
+/// 
+/// A public prefix is served at its own path, and at any path below it, so it
+/// must not be the same as one of the application's own routes, such as
+/// `search` or `api/stats`, which would otherwise be registered twice.
+/// 
+/// # Parameters
+/// 
+/// * `config` - The application configuration.
+/// 
+/// # Errors
+/// 
+/// If a public prefix clashes with an application route, an error will be
+/// returned.
+/// 
+pub fn check_public(config: &Config) -> Result<(), RouteError> {
+	let reserved = public()
+		.into_iter()
+		.chain(protected_features())
+		.map(|route| route.0)
+		.collect::<BTreeSet<_>>()
+	;
+	for prefix in public_prefixes(config) {
+		let paths = [format!("/{prefix}"), format!("/{prefix}/{{*path}}")];
+		if let Some(path) = paths.into_iter().find(|path| reserved.contains(path.as_str())) {
+			return Err(RouteError::ReservedPublicPrefix(prefix.to_owned(), path));
+		}
+	}
+	Ok(())
+}
+
 //		protected																
 /// Returns a list of protected routes.
-pub fn protected() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
+/// 
+/// These are the [application features](protected_features()) and the content.
+/// The home page is left out if the index page has been made public, as it is
+/// then one of the [public content routes](public_content()). Requests for
+/// content are recorded in the audit log.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+pub fn protected(state: &Arc<AppState>) -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
+	let mut routes = protected_features();
+	routes.push(("/{*path}", audited(state, get(get_page))));    //  Also handles get_protected_static_asset(uri)
+	if !public_prefixes(&state.config).contains("index.md") {
+		routes.push(("/", audited(state, get(get_index))));
+	}
+	routes
+}
+
+//		protected_features														
+/// Returns a list of protected routes for application features, rather than
+/// for content.
+/// 
+/// The use of each API token is protected, unlike the rest of the statistics.
+/// 
+fn protected_features() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
 	vec![
-		("/",        get(get_index)),
-		("/{*path}", get(get_page))    //  Also handles get_protected_static_asset(uri)
+		("/admin/audit",      get(get_audit)),
+		("/api/reload",       get(get_reload_feed)),
+		("/api/stats/tokens", get(get_token_stats)),
+		("/search",           get(get_search)),
 	]
 }
 
@@ -35,18 +205,76 @@ pub fn protected() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
 /// Returns a list of public routes.
 pub fn public() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
 	vec![
-		("/api/ping",          get(get_ping)),
-		("/api/version",       get(get_version)),
-		("/api/stats",         get(get_stats)),
-		("/api/stats/history", get(get_stats_history)),
-		("/api/stats/feed",    get(get_stats_feed)),
-		("/login",             post(post_login::<_, Credentials, User, User>)),
-		("/logout",            get(get_logout::<User>)),
-		("/css/{*path}",       get(get_public_static_asset)),
-		("/img/{*path}",       get(get_public_static_asset)),
-		("/js/{*path}",        get(get_public_static_asset)),
-		("/webfonts/{*path}",  get(get_public_static_asset)),
+		("/api/ping",           get(get_ping)),
+		("/api/version",        get(get_version)),
+		("/api/stats",          get(get_stats)),
+		("/api/stats/history",  get(get_stats_history)),
+		("/api/stats/feed",     get(get_stats_feed)),
+		("/login",              get(get_login).post(post_login::<_, Credentials, User, User>)),
+		("/logout",             get(get_logout::<User>)),
+		("/auth/oidc/login",    get(get_oidc_login)),
+		("/auth/oidc/callback", get(get_oidc_callback)),
+		("/css/{*path}",        get(get_public_static_asset)),
+		("/img/{*path}",        get(get_public_static_asset)),
+		("/js/{*path}",         get(get_public_static_asset)),
+		("/webfonts/{*path}",   get(get_public_static_asset)),
 	]
 }
 
+//		is_public																
+/// Determines whether a route is public.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// * `route` - The path of the route, as given by
+///             [`MatchedPath`](axum::extract::MatchedPath).
+/// 
+pub fn is_public(state: &Arc<AppState>, route: &str) -> bool {
+	public().iter().any(|&(path, _)| path == route)
+	||	public_content(state).iter().any(|content| content.0 == route)
+}
+
+//		public_content															
+/// Returns a list of public routes for content.
+/// 
+/// Each configured public path prefix is served without needing to log in,
+/// whether it is a directory or a single file. The content is still subject
+/// to access control, which is applied by the [`get_page()`] handler.
+/// 
+/// # Parameters
+/// 
+/// * `state` - The application state.
+/// 
+pub fn public_content(state: &Arc<AppState>) -> Vec<(String, MethodRouter<Arc<AppState>>)> {
+	let prefixes   = public_prefixes(&state.config);
+	let mut routes = vec![];
+	if prefixes.contains("index.md") {
+		routes.push(("/".to_owned(), audited(state, get(get_index))));
+	}
+	for prefix in prefixes {
+		routes.push((format!("/{prefix}"),           audited(state, get(get_page))));
+		routes.push((format!("/{prefix}/{{*path}}"), audited(state, get(get_page))));
+	}
+	routes
+}
+
+//		public_prefixes															
+/// Returns the configured public path prefixes, normalised.
+/// 
+/// Leading and trailing slashes are removed, and empty prefixes are ignored,
+/// as the whole of the content cannot be made public this way.
+/// 
+/// # Parameters
+/// 
+/// * `config` - The application configuration.
+/// 
+fn public_prefixes(config: &Config) -> BTreeSet<&str> {
+	config.public
+		.iter()
+		.map(|prefix| prefix.trim_matches('/'))
+		.filter(|prefix| !prefix.is_empty())
+		.collect()
+}
+
 
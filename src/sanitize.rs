@@ -0,0 +1,221 @@
+//! HTML sanitisation functionality.
+//! 
+//! Content from contributors who are not fully trusted can contain raw HTML,
+//! which would otherwise be passed straight through to readers. Sanitising the
+//! generated HTML removes anything that is not on an allowlist of tags and
+//! attributes, which strips scripts, event handler attributes, and links using
+//! unsafe schemes such as `javascript:`.
+//! 
+//! The allowlist covers everything generated by the Markdown parser and the
+//! built-in processors, including the SVG of rendered diagrams and the MathML
+//! of rendered maths, so sanitisation runs after the processors, and the
+//! classes they add are kept.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/sanitize.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use ammonia::Builder;
+use std::collections::HashSet;
+
+
+
+//		Constants																										
+
+/// The tags allowed in addition to the defaults, which cover the basic HTML
+/// formatting elements.
+const EXTRA_TAGS:       [&str; 3] = ["figure", "input", "section"];
+
+/// The attributes allowed on any tag, in addition to `lang` and `title`.
+const EXTRA_ATTRIBUTES: [&str; 6] = ["aria-hidden", "aria-label", "class", "id", "inert", "open"];
+
+/// The MathML elements allowed, which are the ones used by rendered maths.
+const MATH_TAGS:        [&str; 26] = [
+	"math",
+	"menclose",
+	"merror",
+	"mfrac",
+	"mi",
+	"mmultiscripts",
+	"mn",
+	"mo",
+	"mover",
+	"mpadded",
+	"mphantom",
+	"mprescripts",
+	"mroot",
+	"mrow",
+	"mspace",
+	"msqrt",
+	"mstyle",
+	"msub",
+	"msubsup",
+	"msup",
+	"mtable",
+	"mtd",
+	"mtext",
+	"mtr",
+	"munder",
+	"munderover",
+];
+
+/// The attributes allowed on MathML elements, which control their layout.
+const MATH_ATTRIBUTES:  [&str; 21] = [
+	"accent",
+	"accentunder",
+	"depth",
+	"display",
+	"displaystyle",
+	"form",
+	"height",
+	"largeop",
+	"linethickness",
+	"lspace",
+	"mathvariant",
+	"maxsize",
+	"minsize",
+	"movablelimits",
+	"notation",
+	"rspace",
+	"scriptlevel",
+	"stretchy",
+	"style",
+	"symmetric",
+	"width",
+];
+
+/// The SVG elements allowed, which are the ones used by rendered diagrams.
+const SVG_TAGS:         [&str; 15] = [
+	"circle",
+	"defs",
+	"ellipse",
+	"g",
+	"line",
+	"marker",
+	"path",
+	"polygon",
+	"polyline",
+	"rect",
+	"svg",
+	"text",
+	"textPath",
+	"title",
+	"tspan",
+];
+
+/// The attributes allowed on SVG elements, which control their geometry and
+/// presentation.
+const SVG_ATTRIBUTES:   [&str; 45] = [
+	"cx",
+	"cy",
+	"d",
+	"dominant-baseline",
+	"dx",
+	"dy",
+	"fill",
+	"fill-opacity",
+	"font-family",
+	"font-size",
+	"font-style",
+	"font-weight",
+	"height",
+	"href",
+	"marker-end",
+	"marker-start",
+	"markerHeight",
+	"markerUnits",
+	"markerWidth",
+	"opacity",
+	"orient",
+	"points",
+	"preserveAspectRatio",
+	"r",
+	"refX",
+	"refY",
+	"rx",
+	"ry",
+	"startOffset",
+	"stroke",
+	"stroke-dasharray",
+	"stroke-linecap",
+	"stroke-linejoin",
+	"stroke-opacity",
+	"stroke-width",
+	"text-anchor",
+	"transform",
+	"viewBox",
+	"width",
+	"x",
+	"x1",
+	"x2",
+	"y",
+	"y1",
+	"y2",
+];
+
+/// The CSS properties allowed in `style` attributes, which are the ones used
+/// by syntax highlighting and rendered maths.
+const STYLE_PROPERTIES: [&str; 9] = [
+	"background-color",
+	"color",
+	"font-style",
+	"font-weight",
+	"justify-items",
+	"padding-left",
+	"padding-right",
+	"text-align",
+	"text-decoration",
+];
+
+
+
+//		Functions																										
+
+//		sanitize_html															
+/// Removes anything from some HTML that is not on the allowlist.
+/// 
+/// Elements that are not allowed are removed, but their content is kept,
+/// except for `<script>` and `<style>` elements, which are removed entirely.
+/// Attributes that are not allowed are removed, which includes all event
+/// handlers such as `onclick`, and so are URLs that do not use a safe scheme.
+/// Comments are also removed.
+/// 
+/// # Parameters
+/// 
+/// * `html` - The HTML to sanitise.
+/// 
+#[must_use]
+pub fn sanitize_html(html: &str) -> String {
+	let mut builder = Builder::default();
+	_ = builder
+		.add_tags(EXTRA_TAGS)
+		.add_tags(MATH_TAGS)
+		.add_tags(SVG_TAGS)
+		.add_generic_attributes(EXTRA_ATTRIBUTES)
+		.add_generic_attribute_prefixes(["data-"])
+		.add_tag_attributes("input", ["checked", "disabled", "type"])
+		.add_tag_attribute_values("input", "type", ["checkbox"])
+		.add_tag_attributes("pre", ["style"])
+		.add_tag_attributes("span", ["style"])
+		.filter_style_properties(STYLE_PROPERTIES.into_iter().collect::<HashSet<_>>())
+		.link_rel(None)
+	;
+	for tag in MATH_TAGS {
+		_ = builder.add_tag_attributes(tag, MATH_ATTRIBUTES);
+	}
+	for tag in SVG_TAGS {
+		_ = builder.add_tag_attributes(tag, SVG_ATTRIBUTES);
+	}
+	builder.clean(html).to_string()
+}
+
+
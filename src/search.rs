@@ -0,0 +1,421 @@
+//! Full-text search functionality.
+//!
+//! This module provides a simple inverted index over rendered pages, which can
+//! be built at compile time and baked into the binary, or built at runtime for
+//! local content.
+//!
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/search.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use super::Heading;
+use nipper::Document;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+
+
+//		Constants																										
+
+/// The maximum length of a snippet, in characters.
+const SNIPPET_LENGTH: usize = 160;
+
+/// The number of characters to show before the first match in a snippet.
+const SNIPPET_LEAD:   usize = 40;
+
+
+
+//		Enums																											
+
+//		Field																	
+/// The part of a page a term was found in.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+enum Field {
+	/// The page title.
+	Title,
+	
+	/// A heading within the page.
+	Heading,
+	
+	/// The body text of the page.
+	Body,
+}
+
+//󰭅		Field																	
+impl Field {
+	//		weight																
+	/// The relative importance of a match in this field.
+	const fn weight(self) -> f64 {
+		match self {
+			Self::Title   => 10.0_f64,
+			Self::Heading =>  4.0_f64,
+			Self::Body    =>  1.0_f64,
+		}
+	}
+}
+
+
+
+//		Structs																											
+
+//		SearchIndex																
+/// An inverted index of page content.
+/// 
+/// Each term found in a page is mapped to the pages it occurs in, along with
+/// the field it occurs in and the number of occurrences. The terms are stored
+/// in sorted order so that prefix matches can be found efficiently.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SearchIndex {
+	//		Private properties													
+	/// The pages that have been indexed.
+	documents: Vec<SearchDocument>,
+	
+	/// The terms found, and where they were found.
+	terms:     BTreeMap<String, Vec<Posting>>,
+}
+
+//󰭅		SearchIndex																
+impl SearchIndex {
+	//		new																	
+	/// Creates a new, empty search index.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	//		add																	
+	/// Adds a page to the index.
+	/// 
+	/// The text of the page is extracted from the rendered HTML, and the
+	/// position of each heading within it is recorded so that matches can be
+	/// linked to the nearest section. If the page is already indexed, it is
+	/// replaced, which allows local pages to be updated when they change.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `title` - The page title.
+	/// * `toc`   - The headings found in the page.
+	/// * `html`  - The rendered HTML of the page.
+	/// 
+	pub fn add(&mut self, path: &str, title: &str, toc: &[Heading], html: &str) {
+		self.remove(path);
+		let document     = Document::from(html);
+		let text         = normalize(&document.select("body").text());
+		let mut sections = vec![];
+		let mut cursor   = 0;
+		for heading in toc {
+			let heading_text = normalize(&heading.text);
+			if let Some(offset) = text.get(cursor..).and_then(|rest| rest.find(&heading_text)) {
+				cursor = cursor.saturating_add(offset);
+				sections.push(Section {
+					offset: cursor,
+					id:     heading.id.clone(),
+					text:   heading_text,
+				});
+			}
+		}
+		let id           = self.documents.len();
+		let mut counts   = HashMap::new();
+		for (field, content) in [(Field::Title, title.to_owned()), (Field::Body, text.clone())]
+			.into_iter()
+			.chain(toc.iter().map(|heading| (Field::Heading, heading.text.clone())))
+		{
+			for (_, term) in tokenize(&content) {
+				let count: &mut u32 = counts.entry((term, field)).or_default();
+				*count              = count.saturating_add(1);
+			}
+		}
+		#[expect(clippy::iter_over_hash_type, reason = "Postings are sorted when searched")]
+		for ((term, field), count) in counts {
+			self.terms.entry(term).or_default().push(Posting { document: id, field, count });
+		}
+		self.documents.push(SearchDocument {
+			path:  path.to_owned(),
+			title: title.to_owned(),
+			text,
+			sections,
+		});
+	}
+	
+	//		contains															
+	/// Checks whether a page has been indexed.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	#[must_use]
+	pub fn contains(&self, path: &str) -> bool {
+		self.documents.iter().any(|document| document.path == path)
+	}
+	
+	//		is_empty															
+	/// Checks whether the index contains any pages.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.documents.is_empty()
+	}
+	
+	//		len																	
+	/// The number of pages in the index.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.documents.len()
+	}
+	
+	//		remove																
+	/// Removes a page from the index.
+	/// 
+	/// Nothing happens if the page has not been indexed.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn remove(&mut self, path: &str) {
+		let Some(id) = self.documents.iter().position(|document| document.path == path) else {
+			return;
+		};
+		drop(self.documents.remove(id));
+		//	Documents are identified by position, so those after the removed one
+		//	move down by one.
+		self.terms.retain(|_, postings| {
+			postings.retain(|posting| posting.document != id);
+			for posting in postings.iter_mut().filter(|posting| posting.document > id) {
+				posting.document = posting.document.saturating_sub(1);
+			}
+			!postings.is_empty()
+		});
+	}
+	
+	//		search																
+	/// Searches the index.
+	/// 
+	/// Each term in the query is matched against the indexed terms, with the
+	/// last term also being matched as a prefix, to allow for partial words
+	/// whilst typing. Results are scored by field importance and term rarity,
+	/// and returned in descending order of score.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `query` - The search query.
+	/// * `limit` - The maximum number of results to return.
+	/// 
+	#[must_use]
+	pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+		let terms: Vec<String> = tokenize(query).into_iter().map(|(_, term)| term).collect();
+		let total              = f64::from(u32::try_from(self.documents.len()).unwrap_or(u32::MAX));
+		let mut scores         = HashMap::<usize, f64>::new();
+		for (index, term) in terms.iter().enumerate() {
+			let is_last  = index.saturating_add(1) == terms.len();
+			let postings = self.terms
+				.range(term.clone()..)
+				.take_while(|&(key, _)| key == term || (is_last && key.starts_with(term.as_str())))
+				.flat_map(|(_, postings)| postings)
+				.collect::<Vec<_>>()
+			;
+			let mut found = postings.iter().map(|posting| posting.document).collect::<Vec<_>>();
+			found.sort_unstable();
+			found.dedup();
+			let frequency = f64::from(u32::try_from(found.len()).unwrap_or(u32::MAX));
+			let rarity    = (total / frequency.max(1.0_f64)).ln_1p();
+			for posting in postings {
+				let score = scores.entry(posting.document).or_default();
+				*score   += posting.field.weight() * f64::from(posting.count).sqrt() * rarity;
+			}
+		}
+		let mut ranked = scores.into_iter().collect::<Vec<_>>();
+		ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+		ranked
+			.into_iter()
+			.filter_map(|(id, score)| self.documents.get(id).map(|document| document.to_result(&terms, score)))
+			.take(limit)
+			.collect()
+	}
+}
+
+//		SearchDocument															
+/// A page that has been added to a search index.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct SearchDocument {
+	//		Private properties													
+	/// The path of the page, relative to the content root.
+	path:     String,
+	
+	/// The page title.
+	title:    String,
+	
+	/// The normalised text content of the page.
+	text:     String,
+	
+	/// The sections of the page, as defined by its headings.
+	sections: Vec<Section>,
+}
+
+//󰭅		SearchDocument															
+impl SearchDocument {
+	//		to_result															
+	/// Creates a search result for this page.
+	/// 
+	/// The snippet is taken from around the first occurrence of any of the
+	/// search terms in the page text, and the anchor is that of the heading
+	/// closest before it.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `terms` - The search terms.
+	/// * `score` - The score the page achieved.
+	/// 
+	fn to_result(&self, terms: &[String], score: f64) -> SearchResult {
+		let position = tokenize(&self.text)
+			.into_iter()
+			.find(|token| terms.iter().any(|term| token.1.starts_with(term.as_str())))
+			.map(|(offset, _)| offset)
+		;
+		let section  = position.and_then(|offset|
+			self.sections.iter().rev().find(|section| section.offset <= offset)
+		);
+		SearchResult {
+			path:    self.path.clone(),
+			title:   self.title.clone(),
+			anchor:  section.map(|s| s.id.clone()),
+			heading: section.map(|s| s.text.clone()),
+			snippet: snippet(&self.text, position.unwrap_or_default()),
+			score,
+		}
+	}
+}
+
+//		SearchResult															
+/// A page matching a search query.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct SearchResult {
+	//		Public properties													
+	/// The path of the page, relative to the content root.
+	pub path:    String,
+	
+	/// The page title.
+	pub title:   String,
+	
+	/// The HTML id of the heading closest to the first match, if any.
+	pub anchor:  Option<String>,
+	
+	/// The text of the heading closest to the first match, if any.
+	pub heading: Option<String>,
+	
+	/// An extract of the page text surrounding the first match.
+	pub snippet: String,
+	
+	/// The relevance score of the page. Higher is better.
+	pub score:   f64,
+}
+
+//		Posting																	
+/// An occurrence of a term in a page.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct Posting {
+	//		Private properties													
+	/// The index of the page the term was found in.
+	document: usize,
+	
+	/// The part of the page the term was found in.
+	field:    Field,
+	
+	/// The number of times the term was found.
+	count:    u32,
+}
+
+//		Section																	
+/// A section of a page, as defined by a heading.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Section {
+	//		Private properties													
+	/// The byte offset of the heading within the page text.
+	offset: usize,
+	
+	/// The HTML id of the heading.
+	id:     String,
+	
+	/// The text of the heading.
+	text:   String,
+}
+
+
+
+//		Functions																										
+
+//		normalize																
+/// Collapses all whitespace in a piece of text into single spaces.
+/// 
+/// # Parameters
+/// 
+/// * `text` - The text to normalise.
+/// 
+fn normalize(text: &str) -> String {
+	text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+//		snippet																	
+/// Extracts a snippet of text from around a given position.
+/// 
+/// # Parameters
+/// 
+/// * `text`     - The text to extract the snippet from.
+/// * `position` - The byte offset to centre the snippet on.
+/// 
+fn snippet(text: &str, position: usize) -> String {
+	let chars = text.char_indices().map(|(offset, _)| offset).collect::<Vec<_>>();
+	let index = chars.partition_point(|&offset| offset < position);
+	let start = index.saturating_sub(SNIPPET_LEAD);
+	let end   = start.saturating_add(SNIPPET_LENGTH);
+	let from  = chars.get(start).copied().unwrap_or_default();
+	let to    = chars.get(end).copied().unwrap_or(text.len());
+	format!(
+		"{}{}{}",
+		if start > 0 { "…" } else { "" },
+		text.get(from..to).unwrap_or_default().trim(),
+		if to < text.len() { "…" } else { "" },
+	)
+}
+
+//		tokenize																
+/// Splits text into lowercase search terms.
+/// 
+/// Terms are runs of alphanumeric characters. Each term is returned along with
+/// its byte offset in the original text.
+/// 
+/// # Parameters
+/// 
+/// * `text` - The text to split.
+/// 
+fn tokenize(text: &str) -> Vec<(usize, String)> {
+	let mut tokens = vec![];
+	let mut start  = None;
+	for (offset, c) in text.char_indices().chain([(text.len(), ' ')]) {
+		match (c.is_alphanumeric(), start) {
+			(true,  None)        => start = Some(offset),
+			(false, Some(begin)) => {
+				if let Some(token) = text.get(begin..offset) {
+					tokens.push((begin, token.to_lowercase()));
+				}
+				start = None;
+			},
+			(true, Some(_)) | (false, None) => {},
+		}
+	}
+	tokens
+}
+
+
@@ -4,18 +4,38 @@
 
 //		Packages																										
 
-use crate::config::Config;
+use crate::{
+	access::AccessRules,
+	audit::AuditLog,
+	cache::PageCache,
+	config::Config,
+	oidc::OidcProvider,
+	tokens::TokenStore,
+	users::{ConfigUserStore, UserStore, user_store},
+};
 use core::net::{IpAddr, SocketAddr};
 use include_dir::{Dir, include_dir};
 use parking_lot::RwLock;
+use rustmark::{
+	CompiledPage,
+	CompiledPageError,
+	MarkdownOptions,
+	links::{Backlink, LinkChecker, LinkGraph},
+	nav::NavTree,
+	pipeline::Pipeline,
+	search::{SearchIndex, SearchResult},
+	wiki::{PageIndex, resolve_wiki_links},
+};
 use std::{
 	collections::HashMap,
+	fs,
+	path::Path,
 	sync::Arc,
 };
 use tera::{Context, Tera};
 use terracotta::{
 	app::{
-		config::HtmlTemplates,
+		config::{HtmlTemplates, LoadingBehavior},
 		errors::AppError,
 		init::setup_tera,
 		state::StateProvider as AppStateProvider,
@@ -31,7 +51,42 @@ use terracotta::{
 		state::{State as StatsState, StateProvider as StatsStateProvider},
 	},
 };
-use tokio::sync::RwLock as AsyncRwLock;
+use tokio::sync::{
+	RwLock as AsyncRwLock,
+	broadcast::{Sender, self},
+};
+use tracing::{error, info, warn};
+use walkdir::WalkDir;
+
+
+
+//		Constants																										
+
+/// The number of change notifications that can be queued for each live reload
+/// connection before older ones are dropped.
+const RELOAD_CHANNEL_CAPACITY: usize = 64;
+
+
+
+//		Enums																											
+
+//		PageAccess																
+/// The outcome of loading a page for a user.
+/// 
+/// See [`AppState::accessible_page()`].
+/// 
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum PageAccess {
+	/// The user can access the page.
+	Allowed(Arc<CompiledPage>),
+	
+	/// The page exists, but the user is not allowed to access it.
+	Forbidden,
+	
+	/// The page does not exist.
+	NotFound,
+}
 
 
 
@@ -46,23 +101,78 @@ use tokio::sync::RwLock as AsyncRwLock;
 #[derive(Debug)]
 pub struct AppState {
 	//		Public properties													
+	/// The rules restricting access to content by role. Front matter access
+	/// lists of local pages are updated whenever they are parsed.
+	pub access:       AccessRules,
+	
 	/// The address the server is running on.
-	pub address:     RwLock<Option<SocketAddr>>,
+	pub address:      RwLock<Option<SocketAddr>>,
 	
 	/// The directory containing the static assets.
-	pub assets_dir:  Arc<Dir<'static>>,
+	pub assets_dir:   Arc<Dir<'static>>,
+	
+	/// The audit log of content requests.
+	pub audit:        AuditLog,
 	
 	/// The application configuration.
-	pub config:      Config,
+	pub config:       Config,
 	
 	/// The directory containing the Markdown content.
-	pub content_dir: Arc<Dir<'static>>,
+	pub content_dir:  Arc<Dir<'static>>,
+	
+	/// The graph of links between pages, covering both the baked-in and local
+	/// Markdown content. Local pages are updated in the graph whenever they are
+	/// parsed, so that changes are reflected when live reloading.
+	pub link_graph:   RwLock<LinkGraph>,
+	
+	/// The link checker, covering both the baked-in and local content. This
+	/// knows about all the pages, anchors, and files that links can point to.
+	/// Local pages are updated in the checker whenever they are parsed.
+	pub links:        RwLock<LinkChecker>,
+	
+	/// The search index for the local, non-baked-in Markdown content. This
+	/// only contains pages that are served from the local filesystem according
+	/// to the configured loading behaviour. Local pages are updated in the
+	/// index whenever they are parsed, and removed once their files are gone.
+	pub local_search: RwLock<SearchIndex>,
+	
+	/// The navigation tree, covering both the baked-in and local Markdown
+	/// content. Local pages are updated in the tree whenever they are parsed,
+	/// and removed once their files are gone.
+	pub nav:          RwLock<NavTree>,
+	
+	/// The OpenID Connect provider users can log in with, if enabled.
+	pub oidc:         Option<OidcProvider>,
+	
+	/// The cache of parsed local Markdown pages.
+	pub page_cache:   PageCache,
+	
+	/// The index of pages used to resolve wiki links, covering both the
+	/// baked-in and local Markdown content.
+	pub page_index:   RwLock<PageIndex>,
+	
+	/// The processors run over the HTML generated from local Markdown content.
+	pub pipeline:     Pipeline,
+	
+	/// The channel used to announce changes to local Markdown files, for live
+	/// reloading. Each message is the path of a changed file, relative to the
+	/// content root.
+	pub reload:       Sender<String>,
+	
+	/// The search index for the baked-in Markdown content.
+	pub search:       SearchIndex,
 	
 	/// The application statistics.
-	pub stats:       AsyncRwLock<StatsState>,
+	pub stats:        AsyncRwLock<StatsState>,
 	
 	/// The Tera template engine.
-	pub tera:        Tera,
+	pub tera:         Tera,
+	
+	/// The API tokens that can be used instead of logging in.
+	pub tokens:       TokenStore,
+	
+	/// The store of users that can log in.
+	pub users:        Box<dyn UserStore>,
 }
 
 //󰭅		AppState																
@@ -79,11 +189,425 @@ impl AppState {
 	/// The new application state.
 	/// 
 	pub fn new(config: Config) -> Self {
-		Self {
+		let mut state      = Self {
 			config,
 			..Default::default()
+		};
+		state.access     = AccessRules::new(
+			&state.config.access,
+			serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/access.json")))
+				.expect("Error loading page access lists"),
+		);
+		state.audit      = AuditLog::new(&state.config.audit, &state.config.logdir);
+		if let Err(err) = state.config.markdown.options.check() {
+			error!("Invalid Markdown options, using the default syntax highlighting theme: {err}");
+			state.config.markdown.options.syntax_theme = MarkdownOptions::default().syntax_theme;
+		}
+		state.page_cache = PageCache::new(state.config.markdown.cache_size);
+		state.pipeline   = Pipeline::builder()
+			.config(&state.config.pipeline)
+			.build()
+			.unwrap_or_else(|err| {
+				error!("Invalid pipeline configuration, using the default: {err}");
+				Pipeline::default()
+			})
+		;
+		info!("Markdown processors: {}", state.pipeline.names().join(", "));
+		state.users      = user_store(&state.config);
+		state.oidc       = state.config.oidc.enabled.then(|| OidcProvider::new(&state.config.oidc));
+		state.tokens     = TokenStore::new(&state.config.tokens);
+		state.index_local_content();
+		state
+	}
+	
+	//		accessible_page														
+	/// Loads a Markdown page, if a user with the given roles can access it.
+	/// 
+	/// The configured access rules are checked before the page is loaded, so
+	/// that pages that cannot be shown are not loaded needlessly. The access
+	/// list in the front matter of the page is only known once it has been
+	/// loaded, so is checked afterwards.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `roles` - The roles the user has.
+	/// 
+	/// # Errors
+	/// 
+	/// If a baked-in page cannot be read, an error is returned.
+	/// 
+	pub fn accessible_page(&self, path: &str, roles: &[String]) -> Result<PageAccess, CompiledPageError> {
+		if !self.access.allows_by_rules(path, roles) {
+			return Ok(PageAccess::Forbidden);
+		}
+		Ok(match self.page(path)? {
+			Some(page) if self.access.allows_page(path, &page, roles) => PageAccess::Allowed(page),
+			Some(_)                                                   => PageAccess::Forbidden,
+			None                                                      => PageAccess::NotFound,
+		})
+	}
+	
+	//		backlinks															
+	/// Finds the pages that link to a page.
+	/// 
+	/// Only pages that the user is allowed to access are included.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `roles` - The roles the user has.
+	/// 
+	pub fn backlinks(&self, path: &str, roles: &[String]) -> Vec<Backlink> {
+		let mut backlinks = self.link_graph.read().backlinks(path);
+		backlinks.retain(|backlink| self.access.allows(&backlink.path, roles));
+		backlinks
+	}
+	
+	//		is_local															
+	/// Determines whether a Markdown file should be loaded locally.
+	/// 
+	/// This applies the configured loading behaviour for Markdown content, to
+	/// decide whether the file should be loaded from the local filesystem or
+	/// from the baked-in content.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the file, relative to the content root.
+	/// 
+	pub fn is_local(&self, path: &str) -> bool {
+		match self.config.markdown.behavior {
+			LoadingBehavior::Deny       => false,
+			LoadingBehavior::Supplement => self.content_dir.get_file(path).is_none(),
+			LoadingBehavior::Override   => self.config.markdown.local_path.join(path).exists(),
+		}
+	}
+	
+	//		live_reload															
+	/// Whether live reloading of local Markdown content is active.
+	/// 
+	/// This requires live reloading to be enabled in the configuration, and
+	/// local Markdown content to be allowed.
+	/// 
+	pub const fn live_reload(&self) -> bool {
+		self.config.markdown.live_reload && !matches!(self.config.markdown.behavior, LoadingBehavior::Deny)
+	}
+	
+	//		local_page															
+	/// Loads a local Markdown page.
+	/// 
+	/// The page is served from the cache if the file backing it is unchanged,
+	/// and otherwise parsed, has its wiki links resolved, and is added to the
+	/// cache, with its links recorded in the link graph.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	/// # Returns
+	/// 
+	/// The parsed page, or `None` if the file does not exist or cannot be
+	/// read.
+	/// 
+	pub fn local_page(&self, path: &str) -> Option<Arc<CompiledPage>> {
+		let file     = self.config.markdown.local_path.join(path);
+		let metadata = fs::metadata(&file).ok().filter(fs::Metadata::is_file)?;
+		if let Some(page) = self.page_cache.get(path, &metadata) {
+			return Some(page);
+		}
+		let page     = self.parse_local_page(path)?;
+		Some(self.store_local_page(path, &metadata, page))
+	}
+	
+	//		page																
+	/// Loads a Markdown page.
+	/// 
+	/// The page is loaded from the local filesystem or the baked-in content,
+	/// according to the configured loading behaviour.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	/// # Returns
+	/// 
+	/// The page, or `None` if it does not exist.
+	/// 
+	/// # Errors
+	/// 
+	/// If a baked-in page cannot be read, an error is returned.
+	/// 
+	pub fn page(&self, path: &str) -> Result<Option<Arc<CompiledPage>>, CompiledPageError> {
+		if self.is_local(path) {
+			let page = self.local_page(path);
+			if page.is_none() {
+				self.forget_local_page(path);
+			}
+			return Ok(page);
+		}
+		//	A local page that has been deleted may have been overriding a baked-in
+		//	one, which is served again.
+		self.forget_local_page(path);
+		self.content_dir
+			.get_file(path)
+			.and_then(|file| file.contents_utf8())
+			.map(|text| CompiledPage::from_json(text).map(Arc::new))
+			.transpose()
+	}
+	
+	//		refresh_local_page													
+	/// Brings the details held about a local Markdown page up to date.
+	/// 
+	/// This is called when a local file changes, so that new and changed pages
+	/// are found by searches straight away, rather than once they are next
+	/// requested, and deleted pages are forgotten. Files that are not local
+	/// Markdown pages are ignored.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the file, relative to the content root.
+	/// 
+	pub fn refresh_local_page(&self, path: &str) {
+		if self.config.markdown.behavior == LoadingBehavior::Deny || Path::new(path).extension().is_none_or(|ext| ext != "md") {
+			return;
+		}
+		if !self.is_local(path) || self.local_page(path).is_none() {
+			self.forget_local_page(path);
+		}
+	}
+	
+	//		roles																
+	/// Gets the roles a user has.
+	/// 
+	/// These are the roles given to the user in the configuration, combined
+	/// with any given to them by the user store.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// 
+	pub fn roles(&self, username: &str) -> Vec<String> {
+		let mut roles = self.config.roles.get(username).cloned().unwrap_or_default();
+		roles.extend(self.users.roles(username));
+		roles.sort();
+		roles.dedup();
+		roles
+	}
+	
+	//		search																
+	/// Searches the baked-in and local Markdown content.
+	/// 
+	/// Baked-in pages that are overridden by local pages are excluded, so that
+	/// only the content that would actually be served is matched, and so are
+	/// pages that the user is not allowed to access.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `query` - The search query.
+	/// * `limit` - The maximum number of results to return.
+	/// * `roles` - The roles the user has.
+	/// 
+	pub fn search(&self, query: &str, limit: usize, roles: &[String]) -> Vec<SearchResult> {
+		//	All matches are needed before filtering, as any number of the best
+		//	ones could be inaccessible.
+		let local       = self.local_search.read();
+		let mut results = self.search
+			.search(query, usize::MAX)
+			.into_iter()
+			.filter(|result| !local.contains(&result.path))
+			.chain(local.search(query, usize::MAX))
+			.filter(|result| self.access.allows(&result.path, roles))
+			.collect::<Vec<_>>()
+		;
+		drop(local);
+		results.sort_by(|a, b| b.score.total_cmp(&a.score));
+		results.truncate(limit);
+		results
+	}
+	
+	//		forget_local_page													
+	/// Forgets a local Markdown page whose file no longer exists.
+	/// 
+	/// The page is removed from the local search index, the page cache, the
+	/// navigation tree, the link checker, the link graph, and the page index,
+	/// and its front matter access list is discarded. If it was overriding a baked-in page, the
+	/// details of that page are restored instead. Nothing happens if the page
+	/// is not known to be local.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	fn forget_local_page(&self, path: &str) {
+		if !self.local_search.read().contains(path) {
+			return;
+		}
+		self.local_search.write().remove(path);
+		self.page_cache.remove(path);
+		let baked = self.content_dir
+			.get_file(path)
+			.and_then(|file| file.contents_utf8())
+			.and_then(|text| CompiledPage::from_json(text).ok())
+		;
+		if let Some(page) = baked {
+			self.page_index.write().add(path, &page.title);
+			self.record_page(path, &page);
+		} else {
+			self.page_index.write().remove(path);
+			self.access.set_page(path, &[]);
+			self.link_graph.write().remove_page(path);
+			self.links.write().remove_page(path);
+			self.nav.write().remove(path);
 		}
 	}
+	
+	//		index_local_content													
+	/// Indexes the local Markdown content.
+	/// 
+	/// Local content is parsed at runtime, so it cannot be included in the
+	/// baked-in search index, navigation tree, and link checker. Instead, every
+	/// local Markdown file that would be served instead of or in addition to the
+	/// baked-in content is parsed here, and added to the local search index, the
+	/// navigation tree, and the link checker. This also primes the page cache.
+	/// Pages parsed later on are updated in the same way when they are stored.
+	/// 
+	/// All the local pages are parsed before any of them are stored, so that
+	/// wiki links between local pages can be resolved regardless of the order
+	/// in which the pages are found. Once all the local content is known, the
+	/// links in the local pages are checked, and any broken ones are logged as
+	/// warnings.
+	/// 
+	fn index_local_content(&mut self) {
+		if self.config.markdown.behavior == LoadingBehavior::Deny {
+			return;
+		}
+		let mut parsed = vec![];
+		let mut pages  = vec![];
+		let root       = self.config.markdown.local_path.clone();
+		for entry in WalkDir::new(&root).follow_links(true).sort_by_file_name().into_iter().filter_map(Result::ok) {
+			if entry.file_type().is_dir() || entry.path().extension().is_none_or(|ext| ext != "md") {
+				continue;
+			}
+			let Some(path) = entry.path().strip_prefix(&root).ok().map(|p| p.to_string_lossy().replace('\\', "/")) else {
+				continue;
+			};
+			if !self.is_local(&path) {
+				continue;
+			}
+			let Ok(metadata) = entry.metadata() else {
+				continue;
+			};
+			if let Some(page) = self.parse_local_page(&path) {
+				parsed.push((path, metadata, page));
+			}
+		}
+		for (path, metadata, unresolved) in parsed {
+			let page = self.store_local_page(&path, &metadata, unresolved);
+			pages.push((path, page));
+		}
+		info!("Indexed {} local Markdown files", self.local_search.get_mut().len());
+		
+		//		Check links														
+		//	Local files that are not Markdown are served as protected assets, so
+		//	need to be known about before any links to them can be checked.
+		let assets = &self.config.assets.protected_assets;
+		let links  = self.links.get_mut();
+		if assets.behavior != LoadingBehavior::Deny {
+			for entry in WalkDir::new(&assets.local_path).follow_links(true).into_iter().filter_map(Result::ok) {
+				if entry.file_type().is_dir() {
+					continue;
+				}
+				if let Ok(path) = entry.path().strip_prefix(&assets.local_path) {
+					links.add_file(&path.to_string_lossy().replace('\\', "/"));
+				}
+			}
+		}
+		for (path, page) in pages {
+			for broken in links.check(&path, &page.links) {
+				warn!("Broken link in local page {broken}");
+			}
+		}
+	}
+	
+	//		parse_local_page													
+	/// Parses a local Markdown page, and adds it to the page index.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	/// # Returns
+	/// 
+	/// The parsed page, with its wiki links not yet resolved, or `None` if the
+	/// file cannot be read.
+	/// 
+	fn parse_local_page(&self, path: &str) -> Option<CompiledPage> {
+		let file = self.config.markdown.local_path.join(path);
+		let text = fs::read_to_string(&file)
+			.inspect_err(|err| warn!("Could not read {}: {err}", file.display()))
+			.ok()?
+		;
+		//	Remove the title from the index page, as it will have one added showing
+		//	the application title.
+		let page = rustmark::parse_with_options(&text, path == "index.md", &self.config.markdown.options, &self.pipeline);
+		if let Some(ref err) = page.invalid {
+			warn!("Not serving {}, as its front matter is invalid: {err}", file.display());
+		}
+		self.page_index.write().add(path, &page.title);
+		Some(page)
+	}
+	
+	//		record_page															
+	/// Records the details of a page that are held outside of the page itself.
+	/// 
+	/// The page is recorded in the access rules, the link graph, the link
+	/// checker, and the navigation tree. Pages with invalid front matter are
+	/// denied to everyone.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// * `page` - The page.
+	/// 
+	fn record_page(&self, path: &str, page: &CompiledPage) {
+		if page.invalid.is_some() {
+			self.access.deny_page(path);
+		} else {
+			self.access.set_page(path, &page.metadata.access);
+		}
+		self.link_graph.write().add_page(path, &page.title, &page.links);
+		self.links.write().add_page(path, &page.html);
+		let mut nav = self.nav.write();
+		nav.insert(path, &page.title, page.metadata.order);
+		nav.sort();
+	}
+	
+	//		store_local_page													
+	/// Resolves the wiki links in a parsed local page, and stores it.
+	/// 
+	/// Any wiki links that cannot be resolved are logged as warnings. The page
+	/// is then recorded in the access rules, the link graph, the link checker,
+	/// and the navigation tree, and added to the local search index and the
+	/// cache. Pages with
+	/// invalid front matter are denied to everyone.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`     - The path of the page, relative to the content root.
+	/// * `metadata` - The metadata of the file backing the page.
+	/// * `page`     - The parsed page.
+	/// 
+	fn store_local_page(&self, path: &str, metadata: &fs::Metadata, mut page: CompiledPage) -> Arc<CompiledPage> {
+		let unresolved = resolve_wiki_links(&mut page, &self.page_index.read());
+		for reference in unresolved {
+			warn!("Unresolved wiki link in local page {path}: [[{reference}]]");
+		}
+		let stored     = Arc::new(page);
+		self.record_page(path, &stored);
+		self.local_search.write().add(path, &stored.title, &stored.toc, &stored.html);
+		self.page_cache.insert(path, metadata, Arc::clone(&stored));
+		stored
+	}
 }
 
 //󰭅		AppStateProvider														
@@ -110,7 +634,28 @@ impl AppStateProvider for AppState {
 	
 	//		render																
 	async fn render<T: AsRef<str> + Send>(&self, template: T, context: &Context) -> Result<String, AppError> {
-		render(self, template.as_ref(), context).await
+		//	Values needed by the layout are added to every context here, so that
+		//	individual handlers do not need to be concerned with them.
+		//	The navigation tree only shows the pages that the current user, if any,
+		//	is allowed to access. The roles can also be given directly, for when
+		//	there is no user, such as when exporting.
+		//	Live reloading is only offered to logged-in users, as the feed it
+		//	connects to is protected.
+		let user        = context.get("User").filter(|user| !user.is_null());
+		let roles       = context
+			.get("Roles")
+			.or_else(|| user?.get("roles"))
+			.and_then(|roles| serde_json::from_value::<Vec<String>>(roles.clone()).ok())
+			.unwrap_or_default()
+		;
+		let mut globals = context.clone();
+		globals.insert("LiveReload", &(self.live_reload() && user.is_some()));
+		globals.insert("Oidc",       &self.oidc.as_ref().map(|_| &self.config.oidc.label));
+		globals.insert("Nav",        &self.nav.read().filtered(|path| self.access.allows(path, &roles)));
+		if !globals.contains_key("Path") {
+			globals.insert("Path", "");
+		}
+		render(self, template.as_ref(), &globals).await
 	}
 	
 	//		set_address															
@@ -160,14 +705,42 @@ impl Default for AppState {
 	//		default																
 	fn default() -> Self {
 		Self {
-			address:     RwLock::new(None),
-			assets_dir:  Arc::new(include_dir!("static")),
-			config:      Config::default(),
-			content_dir: Arc::new(include_dir!("$OUT_DIR")),
-			stats:       AsyncRwLock::new(StatsState::default()),
-			tera:        setup_tera(&Arc::new(include_dir!("html")))
+			access:       AccessRules::default(),
+			address:      RwLock::new(None),
+			assets_dir:   Arc::new(include_dir!("static")),
+			audit:        AuditLog::default(),
+			config:       Config::default(),
+			content_dir:  Arc::new(include_dir!("$OUT_DIR/content")),
+			link_graph:   RwLock::new(
+				serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/graph.json")))
+					.expect("Error loading link graph")
+			),
+			links:        RwLock::new(
+				serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/links.json")))
+					.expect("Error loading link checker")
+			),
+			local_search: RwLock::new(SearchIndex::new()),
+			nav:          RwLock::new(
+				serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/nav.json")))
+					.expect("Error loading navigation tree")
+			),
+			oidc:         None,
+			page_cache:   PageCache::default(),
+			page_index:   RwLock::new(
+				serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/pages.json")))
+					.expect("Error loading page index")
+			),
+			pipeline:     Pipeline::default(),
+			reload:       broadcast::channel(RELOAD_CHANNEL_CAPACITY).0,
+			search:       serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/search.json")))
+				.expect("Error loading search index")
+			,
+			stats:        AsyncRwLock::new(StatsState::default()),
+			tera:         setup_tera(&Arc::new(include_dir!("html")))
 				.expect("Error loading templates")
 			,
+			tokens:       TokenStore::default(),
+			users:        Box::new(ConfigUserStore::default()),
 		}
 	}
 }
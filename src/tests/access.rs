@@ -0,0 +1,192 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use rubedo::sugar::s;
+
+
+
+//		Functions																										
+
+//		page_with_access														
+/// Creates a page with the given access list in its front matter.
+fn page_with_access(access: &[&str]) -> CompiledPage {
+	let mut page           = CompiledPage::default();
+	page.metadata.access = access.iter().map(|&role| role.to_owned()).collect();
+	page
+}
+
+//		rules																	
+/// Creates a set of access rules from patterns and roles.
+fn rules(patterns: &[(&str, &[&str])]) -> AccessRules {
+	AccessRules::new(
+		&patterns
+			.iter()
+			.map(|&(pattern, roles)| (pattern.to_owned(), roles.iter().map(|&role| role.to_owned()).collect()))
+			.collect(),
+		HashMap::new(),
+	)
+}
+
+
+
+//		Tests																											
+
+//		AccessRules																
+#[cfg(test)]
+mod access_rules {
+	use super::*;
+	
+	//		allows																
+	#[test]
+	fn allows__unrestricted() {
+		let access = rules(&[("hr/**", &["hr"])]);
+		assert!(access.allows("index.md", &[]));
+		assert!(access.allows("guides/setup.md", &[]));
+	}
+	#[test]
+	fn allows__restricted() {
+		let access = rules(&[("hr/**", &["hr"])]);
+		assert!(!access.allows("hr/pay.md", &[]));
+		assert!(!access.allows("hr/pay.md", &[s!("it")]));
+		assert!(access.allows("hr/pay.md", &[s!("it"), s!("hr")]));
+		assert!(access.allows("hr/policies/leave.md", &[s!("hr")]));
+	}
+	#[test]
+	fn allows__single_wildcard() {
+		let access = rules(&[("hr/*", &["hr"])]);
+		assert!(!access.allows("hr/pay.md", &[]));
+		assert!(access.allows("hr/policies/leave.md", &[]));
+	}
+	#[test]
+	fn allows__leading_slash() {
+		let access = rules(&[("/hr/**", &["hr"])]);
+		assert!(!access.allows("hr/pay.md", &[]));
+	}
+	#[test]
+	fn allows__all_rules_apply() {
+		let access = rules(&[("hr/**", &["hr"]), ("**/secret.md", &["admin"])]);
+		assert!(access.allows("hr/pay.md", &[s!("hr")]));
+		assert!(!access.allows("hr/secret.md", &[s!("hr")]));
+		assert!(!access.allows("hr/secret.md", &[s!("admin")]));
+		assert!(access.allows("hr/secret.md", &[s!("hr"), s!("admin")]));
+	}
+	#[test]
+	fn allows__invalid_pattern() {
+		//	An invalid pattern restricts everything, rather than nothing
+		let access = rules(&[("hr/[", &["hr"])]);
+		assert!(!access.allows("index.md", &[]));
+		assert!(access.allows("index.md", &[s!("hr")]));
+	}
+	#[test]
+	fn allows__front_matter() {
+		let access = AccessRules::new(&HashMap::new(), HashMap::from([(s!("pay.md"), vec![s!("hr")])]));
+		assert!(!access.allows("pay.md", &[]));
+		assert!(access.allows("pay.md", &[s!("hr")]));
+		assert!(access.allows("index.md", &[]));
+	}
+	
+	//		allows_by_rules														
+	#[test]
+	fn allows_by_rules() {
+		let access = AccessRules::new(
+			&HashMap::from([(s!("hr/**"), vec![s!("hr")])]),
+			HashMap::from([(s!("pay.md"), vec![s!("hr")])]),
+		);
+		assert!(!access.allows_by_rules("hr/pay.md", &[]));
+		assert!(access.allows_by_rules("pay.md", &[]));
+	}
+	
+	//		allows_page															
+	#[test]
+	fn allows_page__front_matter() {
+		let access = rules(&[]);
+		assert!(access.allows_page("pay.md", &page_with_access(&[]), &[]));
+		assert!(!access.allows_page("pay.md", &page_with_access(&["hr"]), &[]));
+		assert!(access.allows_page("pay.md", &page_with_access(&["hr"]), &[s!("hr")]));
+	}
+	#[test]
+	fn allows_page__invalid() {
+		let access   = rules(&[]);
+		let mut page = page_with_access(&[]);
+		page.invalid = Some(s!("Invalid page metadata"));
+		assert!(!access.allows_page("pay.md", &page, &[]));
+		assert!(!access.allows_page("pay.md", &page, &[s!("hr")]));
+	}
+	#[test]
+	fn allows_page__rules() {
+		let access = rules(&[("hr/**", &["hr"])]);
+		assert!(!access.allows_page("hr/pay.md", &page_with_access(&[]), &[]));
+		assert!(!access.allows_page("hr/pay.md", &page_with_access(&["admin"]), &[s!("hr")]));
+		assert!(access.allows_page("hr/pay.md", &page_with_access(&["admin"]), &[s!("hr"), s!("admin")]));
+	}
+	
+	//		deny_page															
+	#[test]
+	fn deny_page() {
+		let access = rules(&[]);
+		access.deny_page("pay.md");
+		assert!(!access.allows("pay.md", &[]));
+		assert!(!access.allows("pay.md", &[s!("hr")]));
+		access.set_page("pay.md", &[]);
+		assert!(access.allows("pay.md", &[]));
+	}
+	
+	//		set_page															
+	#[test]
+	fn set_page() {
+		let access = rules(&[]);
+		access.set_page("pay.md", &[s!("hr")]);
+		assert!(!access.allows("pay.md", &[]));
+		access.set_page("pay.md", &[]);
+		assert!(access.allows("pay.md", &[]));
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		content_path														
+	#[test]
+	fn content_path__valid() {
+		assert_eq!(content_path("/"),                    Some(""));
+		assert_eq!(content_path("/index.md"),            Some("index.md"));
+		assert_eq!(content_path("/guides/setup.md"),     Some("guides/setup.md"));
+		assert_eq!(content_path("/guides/"),             Some("guides/"));
+		assert_eq!(content_path("/.well-known/file"),    Some(".well-known/file"));
+		assert_eq!(content_path("/notes/v1.2..3.md"),    Some("notes/v1.2..3.md"));
+		assert_eq!(content_path("/with%20space.md"),     Some("with%20space.md"));
+	}
+	#[test]
+	fn content_path__traversal() {
+		assert_eq!(content_path("/public/../hr/secret.md"), None);
+		assert_eq!(content_path("/../secret.md"),           None);
+		assert_eq!(content_path("/public/.."),              None);
+		assert_eq!(content_path("/public/./page.md"),       None);
+		assert_eq!(content_path("/public/.../page.md"),     None);
+	}
+	#[test]
+	fn content_path__encoded() {
+		assert_eq!(content_path("/public/%2e%2e/hr/secret.md"), None);
+		assert_eq!(content_path("/public/%2E%2E/hr/secret.md"), None);
+		assert_eq!(content_path("/public/.%2e/hr/secret.md"),   None);
+		assert_eq!(content_path("/public/%2e/page.md"),         None);
+		assert_eq!(content_path("/public%2f..%2fhr/secret.md"), None);
+		assert_eq!(content_path("/public/..%5chr/secret.md"),   None);
+		assert_eq!(content_path("/public/page.md%00.txt"),      None);
+	}
+	#[test]
+	fn content_path__empty_segments() {
+		assert_eq!(content_path("//"),               None);
+		assert_eq!(content_path("//etc/passwd"),     None);
+		assert_eq!(content_path("/public//page.md"), None);
+	}
+	#[test]
+	fn content_path__separators() {
+		assert_eq!(content_path("/public/..\\hr\\secret.md"), None);
+		assert_eq!(content_path("/page.md\0"),                None);
+	}
+}
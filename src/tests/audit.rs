@@ -0,0 +1,196 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use chrono::TimeZone as _;
+use core::net::Ipv4Addr;
+use rubedo::sugar::s;
+use std::thread;
+use tempfile::TempDir;
+
+
+
+//		Functions																										
+
+//		config																	
+/// Creates the configuration for an audit log.
+fn config(recent: usize) -> AuditConfig {
+	AuditConfig { recent, ..Default::default() }
+}
+
+//		event																	
+/// Creates an event for a path, made by a user.
+fn event(user: Option<&str>, path: &str) -> AuditEvent {
+	AuditEvent {
+		timestamp: Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+		user:      user.map(ToOwned::to_owned),
+		path:      path.to_owned(),
+		status:    200,
+		ip:        Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+	}
+}
+
+//		lines																	
+/// Reads the lines written to the audit log files in a directory.
+fn lines(dir: &Path) -> Vec<String> {
+	let mut files = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+	files.sort();
+	files.iter().flat_map(|file| fs::read_to_string(file).unwrap().lines().map(ToOwned::to_owned).collect::<Vec<_>>()).collect()
+}
+
+//		request																	
+/// Creates a request from a client address, with an `X-Forwarded-For` header
+/// if given.
+fn request(forwarded: Option<&str>) -> Request<Body> {
+	let mut builder = Request::get("/page.md");
+	if let Some(value) = forwarded {
+		builder = builder.header("x-forwarded-for", value);
+	}
+	let mut request = builder.body(Body::empty()).unwrap();
+	_ = request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([192, 168, 0, 9], 4000))));
+	request
+}
+
+
+
+//		Tests																											
+
+//		AuditEvent																
+#[cfg(test)]
+mod audit_event {
+	use super::*;
+	
+	//		serialize															
+	#[test]
+	fn serialize() {
+		assert_eq!(
+			serde_json::to_string(&event(Some("joe"), "/hr/pay.md")).unwrap(),
+			r#"{"timestamp":"2025-01-02T03:04:05Z","user":"joe","path":"/hr/pay.md","status":200,"ip":"10.0.0.1"}"#,
+		);
+		assert_eq!(
+			serde_json::to_string(&AuditEvent { ip: None, ..event(None, "/") }).unwrap(),
+			r#"{"timestamp":"2025-01-02T03:04:05Z","user":null,"path":"/","status":200,"ip":null}"#,
+		);
+	}
+}
+
+//		AuditLog																
+#[cfg(test)]
+mod audit_log {
+	use super::*;
+	
+	//		new																	
+	#[test]
+	fn new__disabled() {
+		let dir   = TempDir::new().unwrap();
+		let audit = AuditLog::new(&AuditConfig { enabled: false, ..config(10) }, &dir.path().to_string_lossy());
+		audit.record(event(Some("joe"), "/a.md"));
+		drop(audit);
+		assert!(lines(dir.path()).is_empty());
+	}
+	#[test]
+	fn new__loads_recent() {
+		let dir     = TempDir::new().unwrap();
+		let earlier = serde_json::to_string(&event(Some("joe"), "/a.md")).unwrap();
+		let later   = serde_json::to_string(&event(Some("ann"), "/b.md")).unwrap();
+		fs::write(dir.path().join("audit.log.2025-01-01"), format!("{earlier}\nnot json\n")).unwrap();
+		fs::write(dir.path().join("audit.log.2025-01-02"), format!("{later}\n\n")).unwrap();
+		fs::write(dir.path().join("other.log"),            format!("{later}\n")).unwrap();
+		let audit   = AuditLog::new(&config(10), &dir.path().to_string_lossy());
+		let paths   = audit.query(None, None, 10).into_iter().map(|found| found.path).collect::<Vec<_>>();
+		assert_eq!(paths, vec![s!("/b.md"), s!("/a.md")]);
+		let limited = AuditLog::new(&config(1), &dir.path().to_string_lossy());
+		assert_eq!(limited.query(None, None, 10), vec![event(Some("ann"), "/b.md")]);
+	}
+	
+	//		query																
+	#[test]
+	fn query() {
+		let dir   = TempDir::new().unwrap();
+		let audit = AuditLog::new(&config(10), &dir.path().to_string_lossy());
+		audit.record(event(Some("joe"), "/hr/pay.md"));
+		audit.record(event(Some("ann"), "/hr/leave.md"));
+		audit.record(event(None,        "/index.md"));
+		let paths = |user, path, limit| audit.query(user, path, limit).into_iter().map(|found| found.path).collect::<Vec<_>>();
+		assert_eq!(paths(None,        None,        10), vec![s!("/index.md"), s!("/hr/leave.md"), s!("/hr/pay.md")]);
+		assert_eq!(paths(Some("joe"), None,        10), vec![s!("/hr/pay.md")]);
+		assert_eq!(paths(None,        Some("/hr"), 10), vec![s!("/hr/leave.md"), s!("/hr/pay.md")]);
+		assert_eq!(paths(None,        None,        1),  vec![s!("/index.md")]);
+		assert!(paths(Some("bob"),    None,        10).is_empty());
+	}
+	
+	//		record																
+	#[test]
+	fn record() {
+		let dir   = TempDir::new().unwrap();
+		let audit = AuditLog::new(&config(2), &dir.path().to_string_lossy());
+		audit.record(event(Some("joe"), "/a.md"));
+		audit.record(event(Some("joe"), "/b.md"));
+		audit.record(event(Some("joe"), "/c.md"));
+		assert_eq!(audit.query(None, None, 10).len(), 2);
+		drop(audit);
+		assert_eq!(lines(dir.path()), vec![
+			serde_json::to_string(&event(Some("joe"), "/a.md")).unwrap(),
+			serde_json::to_string(&event(Some("joe"), "/b.md")).unwrap(),
+			serde_json::to_string(&event(Some("joe"), "/c.md")).unwrap(),
+		]);
+	}
+	#[test]
+	fn record__concurrent() {
+		//	Each line needs to be written whole, so that lines written at the same
+		//	time are not interleaved
+		let dir   = TempDir::new().unwrap();
+		let audit = AuditLog::new(&config(0), &dir.path().to_string_lossy());
+		thread::scope(|scope| {
+			for thread_number in 0..8_u8 {
+				let log = &audit;
+				drop(scope.spawn(move || {
+					for request_number in 0..100_u8 {
+						log.record(event(Some(&format!("user{thread_number}")), &format!("/{request_number}.md")));
+					}
+				}));
+			}
+		});
+		drop(audit);
+		let written = lines(dir.path());
+		assert_eq!(written.len(), 800);
+		assert!(written.iter().all(|line| serde_json::from_str::<AuditEvent>(line).is_ok()));
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		client_ip															
+	#[test]
+	fn client_ip__connection() {
+		let connection = Some(IpAddr::from([192, 168, 0, 9]));
+		assert_eq!(client_ip(&request(None),               false), connection);
+		assert_eq!(client_ip(&request(Some("10.0.0.1")),   false), connection);
+		assert_eq!(client_ip(&request(None),               true),  connection);
+		assert_eq!(client_ip(&Request::new(Body::empty()), false), None);
+	}
+	#[test]
+	fn client_ip__forwarded() {
+		assert_eq!(client_ip(&request(Some("10.0.0.1")),              true), Some(IpAddr::from([10, 0, 0, 1])));
+		assert_eq!(client_ip(&request(Some(" 2001:db8::1 ")),         true), "2001:db8::1".parse().ok());
+		assert_eq!(client_ip(&request(Some("203.0.113.7, 10.0.0.1")), true), Some(IpAddr::from([10, 0, 0, 1])));
+	}
+	#[test]
+	fn client_ip__forged() {
+		//	The client can send its own header, which the proxy appends to, so
+		//	only the last entry can be trusted
+		assert_eq!(client_ip(&request(Some("1.2.3.4, 10.0.0.1")),         true), Some(IpAddr::from([10, 0, 0, 1])));
+		assert_eq!(client_ip(&request(Some("1.2.3.4, 5.6.7.8,10.0.0.1")), true), Some(IpAddr::from([10, 0, 0, 1])));
+	}
+	#[test]
+	fn client_ip__invalid() {
+		let connection = Some(IpAddr::from([192, 168, 0, 9]));
+		assert_eq!(client_ip(&request(Some("unknown")),           true), connection);
+		assert_eq!(client_ip(&request(Some("10.0.0.1, unknown")), true), connection);
+		assert_eq!(client_ip(&request(Some("")),                  true), connection);
+	}
+}
@@ -0,0 +1,143 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use core::time::Duration;
+use rubedo::sugar::s;
+use rustmark::parse;
+use std::{
+	fs::{self, File},
+	path::Path,
+};
+use tempfile::TempDir;
+
+
+
+//		Functions																										
+
+//		metadata																
+/// Writes a file, and returns its metadata.
+fn metadata(path: &Path, content: &str) -> Metadata {
+	fs::write(path, content).unwrap();
+	fs::metadata(path).unwrap()
+}
+
+//		page																	
+/// Parses a page with a given title.
+fn page(title: &str) -> Arc<CompiledPage> {
+	Arc::new(parse(&format!("# {title}\n"), false))
+}
+
+//		title																	
+/// Retrieves a page from the cache, and returns its title.
+fn title(cache: &PageCache, path: &str, metadata: &Metadata) -> Option<String> {
+	cache.get(path, metadata).map(|found| found.title.clone())
+}
+
+
+
+//		Tests																											
+
+//		PageCache																
+#[cfg(test)]
+mod page_cache {
+	use super::*;
+	
+	//		new																	
+	#[test]
+	fn new__disabled() {
+		let dir   = TempDir::new().unwrap();
+		let file  = metadata(&dir.path().join("a.md"), "# A\n");
+		let cache = PageCache::new(0);
+		cache.insert("a.md", &file, page("A"));
+		assert_eq!(title(&cache, "a.md", &file), None);
+		assert_eq!(cache.stats(), CacheStats { capacity: 0, entries: 0, hits: 0, misses: 1 });
+	}
+	
+	//		get																	
+	#[test]
+	fn get() {
+		let dir   = TempDir::new().unwrap();
+		let file  = metadata(&dir.path().join("a.md"), "# A\n");
+		let cache = PageCache::new(10);
+		assert_eq!(title(&cache, "a.md", &file), None);
+		cache.insert("a.md", &file, page("A"));
+		assert_eq!(title(&cache, "a.md", &file), Some(s!("A")));
+		assert_eq!(title(&cache, "a.md", &file), Some(s!("A")));
+		assert_eq!(cache.stats(), CacheStats { capacity: 10, entries: 1, hits: 2, misses: 1 });
+	}
+	#[test]
+	fn get__modified() {
+		//	A page is discarded once its file has a different modification time,
+		//	even if the size is the same
+		let dir     = TempDir::new().unwrap();
+		let path    = dir.path().join("a.md");
+		let file    = metadata(&path, "# A\n");
+		let cache   = PageCache::new(10);
+		cache.insert("a.md", &file, page("A"));
+		let later   = file.modified().unwrap().checked_add(Duration::from_secs(60)).unwrap();
+		File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+		let changed = fs::metadata(&path).unwrap();
+		assert_eq!(changed.len(), file.len());
+		assert_eq!(title(&cache, "a.md", &changed), None);
+		assert_eq!(cache.stats().entries, 0);
+		//	The page is not returned for the original metadata either, as it has
+		//	been removed
+		assert_eq!(title(&cache, "a.md", &file), None);
+	}
+	#[test]
+	fn get__resized() {
+		let dir     = TempDir::new().unwrap();
+		let path    = dir.path().join("a.md");
+		let file    = metadata(&path, "# A\n");
+		let cache   = PageCache::new(10);
+		cache.insert("a.md", &file, page("A"));
+		let changed = metadata(&path, "# A, changed\n");
+		assert_eq!(title(&cache, "a.md", &changed), None);
+		assert_eq!(cache.stats(), CacheStats { capacity: 10, entries: 0, hits: 0, misses: 1 });
+	}
+	
+	//		insert																
+	#[test]
+	fn insert__evicts() {
+		//	Once full, the page used the longest time ago is discarded, which is
+		//	not necessarily the one added first
+		let dir   = TempDir::new().unwrap();
+		let file  = metadata(&dir.path().join("a.md"), "# A\n");
+		let cache = PageCache::new(2);
+		cache.insert("a.md", &file, page("A"));
+		cache.insert("b.md", &file, page("B"));
+		assert_eq!(title(&cache, "a.md", &file), Some(s!("A")));
+		cache.insert("c.md", &file, page("C"));
+		assert_eq!(title(&cache, "a.md", &file), Some(s!("A")));
+		assert_eq!(title(&cache, "b.md", &file), None);
+		assert_eq!(title(&cache, "c.md", &file), Some(s!("C")));
+		assert_eq!(cache.stats(), CacheStats { capacity: 2, entries: 2, hits: 3, misses: 1 });
+	}
+	#[test]
+	fn insert__replaces() {
+		let dir   = TempDir::new().unwrap();
+		let file  = metadata(&dir.path().join("a.md"), "# A\n");
+		let cache = PageCache::new(2);
+		cache.insert("a.md", &file, page("A"));
+		cache.insert("a.md", &file, page("Changed"));
+		assert_eq!(title(&cache, "a.md", &file), Some(s!("Changed")));
+		assert_eq!(cache.stats().entries, 1);
+	}
+	
+	//		remove																
+	#[test]
+	fn remove() {
+		let dir   = TempDir::new().unwrap();
+		let file  = metadata(&dir.path().join("a.md"), "# A\n");
+		let cache = PageCache::new(2);
+		cache.insert("a.md", &file, page("A"));
+		cache.insert("b.md", &file, page("B"));
+		cache.remove("a.md");
+		cache.remove("c.md");
+		assert_eq!(title(&cache, "a.md", &file), None);
+		assert_eq!(title(&cache, "b.md", &file), Some(s!("B")));
+		PageCache::new(0).remove("a.md");
+	}
+}
@@ -0,0 +1,212 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+use serde_json::json;
+
+
+
+//		Constants																										
+
+/// A valid Graphviz DOT diagram.
+const DOT:     &str = "digraph { start -> finish }";
+
+/// A valid Mermaid diagram.
+const MERMAID: &str = "graph TD\n  Start --> Finish\n";
+
+
+
+//		Functions																										
+
+//		code_block																
+/// Creates the HTML for a code block in a language.
+fn code_block(language: &str, source: &str) -> String {
+	format!(r#"<pre><code class="language-{language}">{source}</code></pre>"#)
+}
+
+//		process																	
+/// Runs a diagrams processor over some HTML, and returns the result.
+fn process(processor: &DiagramsProcessor, html: &str) -> String {
+	let document = Document::from(html);
+	processor.process(&document);
+	document.html().to_string()
+}
+
+
+
+//		Tests																											
+
+//		DiagramKind																
+#[cfg(test)]
+mod diagram_kind {
+	use super::*;
+	
+	//		fmt																	
+	#[test]
+	fn fmt() {
+		assert_eq!(DiagramKind::Dot.to_string(),     "dot");
+		assert_eq!(DiagramKind::Mermaid.to_string(), "mermaid");
+	}
+	
+	//		from_language														
+	#[test]
+	fn from_language() {
+		assert_eq!(DiagramKind::from_language("dot"),      Some(DiagramKind::Dot));
+		assert_eq!(DiagramKind::from_language("graphviz"), Some(DiagramKind::Dot));
+		assert_eq!(DiagramKind::from_language("mermaid"),  Some(DiagramKind::Mermaid));
+		assert_eq!(DiagramKind::from_language("rust"),     None);
+		assert_eq!(DiagramKind::from_language("Mermaid"),  None);
+	}
+}
+
+//		DiagramsProcessor														
+#[cfg(test)]
+mod diagrams_processor {
+	use super::*;
+	
+	//		from_options														
+	#[test]
+	fn from_options() {
+		let defaults = DiagramsProcessor::from_options(&JsonValue::Null).unwrap();
+		assert_eq!(defaults.options, DiagramsOptions::default());
+		let changed  = DiagramsProcessor::from_options(&json!({ "mermaid": false, "cache_size": 0_usize })).unwrap();
+		assert_eq!(changed.options, DiagramsOptions { cache_size: 0, dot: true, mermaid: false });
+		assert!(matches!(
+			DiagramsProcessor::from_options(&json!({ "dot": "yes" })),
+			Err(PipelineError::InvalidOptions(name, _)) if name == "diagrams"
+		));
+	}
+	
+	//		process																
+	#[test]
+	fn process() {
+		let html = super::process(&DiagramsProcessor::default(), &format!(
+			"<p>Before</p>{}{}{}",
+			code_block("dot",     DOT),
+			code_block("mermaid", MERMAID),
+			code_block("rust",    "fn main() {}"),
+		));
+		let document = Document::from(&html);
+		assert_eq!(document.select("figure.diagram.diagram-dot svg").length(),     1);
+		assert_eq!(document.select("figure.diagram.diagram-mermaid svg").length(), 1);
+		assert_eq!(document.select("pre").length(),                                1);
+		assert!(html.contains("fn main() {}"));
+		assert!(html.contains("<p>Before</p>"));
+		assert!(!html.contains("<?xml"));
+	}
+	#[test]
+	fn process__disabled() {
+		let processor = DiagramsProcessor::new(DiagramsOptions { dot: false, ..Default::default() });
+		let document  = Document::from(&super::process(&processor, &format!("{}{}", code_block("dot", DOT), code_block("mermaid", MERMAID))));
+		assert_eq!(document.select("figure.diagram-dot").length(),     0);
+		assert_eq!(document.select("figure.diagram-mermaid").length(), 1);
+		assert_eq!(document.select("pre code.language-dot").text().to_string(), DOT);
+	}
+	#[test]
+	fn process__invalid() {
+		//	The source is left in place, so that it can still be read
+		let html     = super::process(&DiagramsProcessor::default(), &code_block("dot", "digraph { start -&gt; "));
+		let document = Document::from(&html);
+		let pre      = document.select("pre.diagram-error");
+		assert_eq!(pre.length(), 1);
+		assert!(pre.attr("title").unwrap().starts_with("Could not render dot diagram: "));
+		assert_eq!(pre.text().to_string(), "digraph { start -> ");
+		assert_eq!(document.select("figure").length(), 0);
+	}
+	#[test]
+	fn process__ids_prefixed() {
+		//	Each diagram on a page has its own IDs, even when they are the same
+		//	diagram
+		let html     = super::process(&DiagramsProcessor::default(), &format!("{}{}", code_block("mermaid", MERMAID), code_block("mermaid", MERMAID)));
+		let document = Document::from(&html);
+		let ids      = document.select("figure [id]").iter().filter_map(|element| element.attr("id").map(|id| id.to_string())).collect::<Vec<_>>();
+		assert!(!ids.is_empty());
+		assert!(ids.iter().all(|id| id.starts_with("diagram-1-") || id.starts_with("diagram-2-")));
+		assert!(ids.iter().any(|id| id.starts_with("diagram-1-")));
+		assert!(ids.iter().any(|id| id.starts_with("diagram-2-")));
+		let mut unique = ids.clone();
+		unique.sort();
+		unique.dedup();
+		assert_eq!(unique.len(), ids.len());
+	}
+	#[test]
+	fn process__lang_attribute() {
+		let html = super::process(&DiagramsProcessor::default(), &format!(r#"<pre lang="dot"><code>{DOT}</code></pre>"#));
+		assert_eq!(Document::from(&html).select("figure.diagram-dot svg").length(), 1);
+	}
+	
+	//		render																
+	#[test]
+	fn render__cached() {
+		let processor = DiagramsProcessor::new(DiagramsOptions { cache_size: 1, ..Default::default() });
+		let first     = processor.render(DiagramKind::Dot, DOT).unwrap();
+		assert_eq!(processor.render(DiagramKind::Dot, DOT).unwrap(), first);
+		assert_eq!(processor.cache.lock().unwrap().len(), 1);
+		//	Once full, the cache is emptied before the next diagram is added
+		drop(processor.render(DiagramKind::Dot, "digraph { other }"));
+		assert_eq!(processor.cache.lock().unwrap().len(), 1);
+		assert!(processor.render(DiagramKind::Dot, "digraph {").is_err());
+		let uncached  = DiagramsProcessor::new(DiagramsOptions { cache_size: 0, ..Default::default() });
+		assert_eq!(uncached.render(DiagramKind::Dot, DOT).unwrap(), first);
+		assert!(uncached.cache.lock().unwrap().is_empty());
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		diagram_kind														
+	#[test]
+	fn diagram_kind() {
+		let kind = |html: &str| super::diagram_kind(&Document::from(html).select("pre"));
+		assert_eq!(kind(&code_block("dot", DOT)),                                     Some(DiagramKind::Dot));
+		assert_eq!(kind(r#"<pre><code class="other language-mermaid">x</code></pre>"#), Some(DiagramKind::Mermaid));
+		assert_eq!(kind(r#"<pre lang="graphviz"><code>x</code></pre>"#),              Some(DiagramKind::Dot));
+		assert_eq!(kind(&code_block("rust", "x")),                                     None);
+		assert_eq!(kind("<pre><code>x</code></pre>"),                                  None);
+	}
+	
+	//		inline_styles														
+	#[test]
+	fn inline_styles() {
+		let svg = "<svg><style>\n.big { font-size: 20px; fill: red; }\n.small {font-size:8px}\n</style>\n<text class=\"big\">A</text><text class=\"small\">B</text><text class=\"other\">C</text></svg>";
+		assert_eq!(
+			super::inline_styles(svg),
+			r#"<svg><text font-size="20px" fill="red">A</text><text font-size="8px">B</text><text class="other">C</text></svg>"#,
+		);
+		assert_eq!(super::inline_styles("<svg><text>A</text></svg>"), "<svg><text>A</text></svg>");
+		assert_eq!(super::inline_styles("<svg><style>.a {}"),         "<svg><style>.a {}");
+	}
+	
+	//		prefix_ids															
+	#[test]
+	fn prefix_ids() {
+		let svg = r##"<svg><defs><marker id="arrow"/></defs><path id="line" marker-end="url(#arrow)"/><use href="#line"/><g id="">x</g></svg>"##;
+		assert_eq!(
+			super::prefix_ids(svg, "d1-"),
+			r##"<svg><defs><marker id="d1-arrow"/></defs><path id="d1-line" marker-end="url(#d1-arrow)"/><use href="#d1-line"/><g id="">x</g></svg>"##,
+		);
+		assert_eq!(super::prefix_ids("<svg></svg>", "d1-"), "<svg></svg>");
+	}
+	
+	//		render_diagram														
+	#[test]
+	fn render_diagram() {
+		let dot     = super::render_diagram(DiagramKind::Dot, DOT).unwrap();
+		assert!(dot.starts_with("<svg"));
+		assert!(dot.contains("start"));
+		assert!(!dot.contains("<style>"));
+		let mermaid = super::render_diagram(DiagramKind::Mermaid, MERMAID).unwrap();
+		assert!(mermaid.contains("<svg"));
+		assert!(mermaid.contains("Finish"));
+	}
+	#[test]
+	fn render_diagram__invalid() {
+		assert!(super::render_diagram(DiagramKind::Dot,     "digraph {").is_err());
+		assert!(super::render_diagram(DiagramKind::Mermaid, "not a diagram at all {{{").is_err());
+	}
+}
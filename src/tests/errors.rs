@@ -0,0 +1,223 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::config::Config;
+use axum::{
+	Router,
+	body::to_bytes,
+	http::header::{CONTENT_LENGTH, CONTENT_TYPE},
+	middleware::from_fn_with_state,
+	routing::get,
+};
+use std::{collections::HashMap, fs, path::Path};
+use tempfile::TempDir;
+use terracotta::app::config::LoadingBehavior;
+use tower::ServiceExt as _;
+
+
+
+//		Functions																										
+
+//		app																		
+/// Creates an application that passes its responses through the error layer.
+fn app(state: &Arc<AppState>) -> Router {
+	Router::new()
+		.route("/handled",  get(|| async { (
+			StatusCode::NOT_FOUND,
+			[(ERROR_HANDLED_HEADER, HeaderValue::from_static("gracefully"))],
+			"Custom not found",
+		) }))
+		.route("/failed",   get(|| async { (
+			StatusCode::INTERNAL_SERVER_ERROR,
+			[(ERROR_HANDLED_HEADER, HeaderValue::from_static("gracefully"))],
+			"Custom server error",
+		) }))
+		.route("/missing",  get(|| async { (StatusCode::NOT_FOUND,             "Missing") }))
+		.route("/broken",   get(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "Broken") }))
+		.route("/forbidden", get(|| async { (StatusCode::FORBIDDEN,            "Forbidden") }))
+		.layer(from_fn_with_state(Arc::clone(state), error_layer))
+}
+
+//		content																	
+/// Creates local content for the tests, with a guide, some notes, and a
+/// restricted section.
+fn content() -> TempDir {
+	let dir  = TempDir::new().unwrap();
+	let root = dir.path().join("content");
+	fs::create_dir_all(root.join("guide")).unwrap();
+	fs::create_dir_all(root.join("notes")).unwrap();
+	fs::create_dir_all(root.join("hr")).unwrap();
+	fs::write(root.join("guide/setup.md"),   "# Setup\n\nHow to set up.\n").unwrap();
+	fs::write(root.join("guide/install.md"), "# Install\n\nHow to install.\n").unwrap();
+	fs::write(root.join("hr/pay.md"),        "# Pay\n\nPay details.\n").unwrap();
+	for number in 1..=7_u8 {
+		fs::write(root.join(format!("notes/note{number}.md")), format!("# Note {number}\n\nA note.\n")).unwrap();
+	}
+	dir
+}
+
+//		paths																	
+/// Finds the paths of the pages suggested for a path.
+fn paths(state: &AppState, path: &str, roles: &[String]) -> Vec<String> {
+	suggestions(state, path, roles).into_iter().map(|suggestion| suggestion.path).collect()
+}
+
+//		request																	
+/// Makes a request to the application, and returns the status, the error
+/// handled header, and the body.
+async fn request(app: Router, uri: &str) -> (StatusCode, Option<String>, String) {
+	let response = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+	let status   = response.status();
+	let handled  = response.headers().get(ERROR_HANDLED_HEADER).and_then(|value| value.to_str().ok()).map(ToOwned::to_owned);
+	let body     = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	(status, handled, String::from_utf8_lossy(&body).into_owned())
+}
+
+//		state																	
+/// Creates the application state for the tests, using local content, with
+/// `hr` restricted to the `hr` role.
+fn state(dir: &Path) -> Arc<AppState> {
+	let mut config             = Config {
+		access: HashMap::from([(s!("hr/**"), vec![s!("hr")])]),
+		..Default::default()
+	};
+	config.audit.enabled       = false;
+	config.markdown.behavior   = LoadingBehavior::Override;
+	config.markdown.local_path = dir.join("content");
+	Arc::new(AppState::new(config))
+}
+
+
+
+//		Tests																											
+
+//		PageError																
+#[cfg(test)]
+mod page_error {
+	use super::*;
+	
+	//		path																
+	#[test]
+	fn path() {
+		assert_eq!(PageError::NotFound(s!("a.md")).path(), "a.md");
+		assert_eq!(PageError::CouldNotLoad(s!("b.md"), CompiledPageError::UnsupportedVersion(0)).path(), "b.md");
+	}
+	
+	//		respond																
+	#[tokio::test]
+	async fn respond__not_found() {
+		let dir      = content();
+		let state    = state(dir.path());
+		let response = PageError::NotFound(s!("guide/setp.md")).respond(&state, &[]).await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+		assert_eq!(response.headers().get(ERROR_HANDLED_HEADER).unwrap(), "gracefully");
+		let body     = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let html     = String::from_utf8_lossy(&body);
+		assert!(html.contains("Not found"));
+		assert!(html.contains(r#"<a href="/guide&#x2F;setup.md">Setup</a>"#));
+	}
+	#[tokio::test]
+	async fn respond__server_error() {
+		let dir      = content();
+		let state    = state(dir.path());
+		let response = PageError::CouldNotLoad(s!("guide/setup.md"), CompiledPageError::UnsupportedVersion(0)).respond(&state, &[]).await;
+		assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+		assert_eq!(response.headers().get(ERROR_HANDLED_HEADER).unwrap(), "gracefully");
+		let body     = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let html     = String::from_utf8_lossy(&body);
+		assert!(html.contains("Server error"));
+		assert!(!html.contains("Did you mean"));
+	}
+	
+	//		status																
+	#[test]
+	fn status() {
+		assert_eq!(PageError::NotFound(s!("a.md")).status(), StatusCode::NOT_FOUND);
+		assert_eq!(
+			PageError::CouldNotLoad(s!("a.md"), CompiledPageError::UnknownFormat(s!("x"))).status(),
+			StatusCode::INTERNAL_SERVER_ERROR,
+		);
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		error_layer															
+	#[tokio::test]
+	async fn error_layer__handled() {
+		//	Error pages that have already been rendered are passed through, and the
+		//	header is only kept on 500 responses, for the final error layer
+		let dir                     = content();
+		let app                     = app(&state(dir.path()));
+		let (status, handled, body) = request(app.clone(), "/handled").await;
+		assert_eq!(status,  StatusCode::NOT_FOUND);
+		assert_eq!(handled, None);
+		assert_eq!(body,    "Custom not found");
+		let (error_status, error_handled, error_body) = request(app, "/failed").await;
+		assert_eq!(error_status,  StatusCode::INTERNAL_SERVER_ERROR);
+		assert_eq!(error_handled, Some(s!("gracefully")));
+		assert_eq!(error_body,    "Custom server error");
+	}
+	#[tokio::test]
+	async fn error_layer__rendered() {
+		let dir                     = content();
+		let app                     = app(&state(dir.path()));
+		let (status, handled, body) = request(app.clone(), "/missing").await;
+		assert_eq!(status,  StatusCode::NOT_FOUND);
+		assert_eq!(handled, None);
+		assert!(body.contains("Not found"));
+		assert!(!body.contains("Missing"));
+		let response                = app.oneshot(Request::get("/broken").body(Body::empty()).unwrap()).await.unwrap();
+		assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+		assert_eq!(response.headers().get(ERROR_HANDLED_HEADER).unwrap(), "gracefully");
+		assert!(response.headers().get(CONTENT_LENGTH).is_none_or(|length| length != "6"));
+		assert_ne!(response.headers().get(CONTENT_TYPE).unwrap(), "text/plain; charset=utf-8");
+		let error_body              = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		assert!(String::from_utf8_lossy(&error_body).contains("Server error"));
+	}
+	#[tokio::test]
+	async fn error_layer__other_status() {
+		let dir                     = content();
+		let (status, handled, body) = request(app(&state(dir.path())), "/forbidden").await;
+		assert_eq!(status,  StatusCode::FORBIDDEN);
+		assert_eq!(handled, None);
+		assert_eq!(body,    "Forbidden");
+	}
+	
+	//		suggestions															
+	#[test]
+	fn suggestions() {
+		let dir         = content();
+		let state       = state(dir.path());
+		let suggestions = super::suggestions(&state, "guide/setp.md", &[]);
+		assert_eq!(suggestions.first(), Some(&Suggestion { path: s!("guide/setup.md"), title: s!("Setup") }));
+		assert!(paths(&state, "nothing/like/this/at/all.md", &[]).is_empty());
+	}
+	#[test]
+	fn suggestions__moved() {
+		//	A page is found by its file name when it is in a different directory
+		let dir   = content();
+		let state = state(dir.path());
+		assert_eq!(paths(&state, "archive/old/setup.md", &[]).first().map(String::as_str), Some("guide/setup.md"));
+	}
+	#[test]
+	fn suggestions__restricted() {
+		let dir   = content();
+		let state = state(dir.path());
+		assert!(!paths(&state, "hr/pai.md", &[]).contains(&s!("hr/pay.md")));
+		assert!(paths(&state, "hr/pai.md", &[s!("hr")]).contains(&s!("hr/pay.md")));
+	}
+	#[test]
+	fn suggestions__limit() {
+		let dir   = content();
+		let state = state(dir.path());
+		let found = paths(&state, "notes/note1.md", &[]);
+		assert_eq!(found.len(), MAX_SUGGESTIONS);
+		assert!(!found.contains(&s!("notes/note1.md")));
+	}
+}
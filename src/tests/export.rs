@@ -0,0 +1,219 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::config::Config;
+use rubedo::sugar::s;
+use std::{collections::HashMap, io::Read as _, sync::Arc};
+use tempfile::TempDir;
+use zip::ZipArchive;
+
+
+
+//		Functions																										
+
+//		content																	
+/// Creates local content for the tests, with a public page that links to
+/// other pages, and a page restricted to the `hr` role.
+fn content() -> TempDir {
+	let dir  = TempDir::new().unwrap();
+	let root = dir.path().join("content");
+	fs::create_dir_all(root.join("public")).unwrap();
+	fs::create_dir_all(root.join("hr")).unwrap();
+	fs::write(root.join("public/welcome.md"), "# Welcome\n\n[Home](/) [Pay](/hr/pay.md) [Notes](notes.txt)\n").unwrap();
+	fs::write(root.join("public/notes.txt"),  "Public notes\n").unwrap();
+	fs::write(root.join("hr/pay.md"),         "# Pay\n\nTop secret pay details.\n").unwrap();
+	fs::write(root.join("hr/pay.txt"),        "Top secret file\n").unwrap();
+	dir
+}
+
+//		state																	
+/// Creates the application state for the tests, serving the local content
+/// given, with `hr` restricted to the `hr` role.
+fn state(dir: &Path) -> Arc<AppState> {
+	let mut config = Config {
+		logdir: dir.join("log").to_string_lossy().into_owned(),
+		access: HashMap::from([(s!("hr/**"), vec![s!("hr")])]),
+		..Default::default()
+	};
+	config.audit.enabled                      = false;
+	config.markdown.behavior                  = LoadingBehavior::Override;
+	config.markdown.local_path                = dir.join("content");
+	config.assets.protected_assets.behavior   = LoadingBehavior::Override;
+	config.assets.protected_assets.local_path = dir.join("content");
+	Arc::new(AppState::new(config))
+}
+
+//		zip_names																
+/// Lists the names of the entries in a zip file, in order.
+fn zip_names(path: &Path) -> Vec<String> {
+	let archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+	archive.file_names().map(ToOwned::to_owned).collect()
+}
+
+
+
+//		Tests																											
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		export																
+	#[tokio::test]
+	async fn export__roles() {
+		let dir     = content();
+		let state   = state(dir.path());
+		let output  = dir.path().join("site");
+		let summary = export(&state, &ExportOptions { output: output.clone(), ..Default::default() }).await.unwrap();
+		assert!(summary.pages > 0);
+		assert!(summary.zip.is_none());
+		assert!(output.join("index.html").exists());
+		assert!(output.join("public/welcome.html").exists());
+		assert!(output.join("public/notes.txt").exists());
+		assert!(!output.join("public/welcome.md").exists());
+		assert!(!output.join("hr/pay.html").exists());
+		assert!(!output.join("hr/pay.txt").exists());
+		let hr_output = dir.path().join("hr-site");
+		let options   = ExportOptions { output: hr_output.clone(), roles: vec![s!("hr")], zip: false };
+		assert!(export(&state, &options).await.is_ok());
+		assert!(hr_output.join("hr/pay.html").exists());
+		assert!(hr_output.join("hr/pay.txt").exists());
+	}
+	#[tokio::test]
+	async fn export__links() {
+		let dir    = content();
+		let output = dir.path().join("site");
+		assert!(export(&state(dir.path()), &ExportOptions { output: output.clone(), ..Default::default() }).await.is_ok());
+		let html   = fs::read_to_string(output.join("public/welcome.html")).unwrap();
+		assert!(html.contains(r#"<a href="../index.html">Home</a>"#));
+		assert!(html.contains(r#"<a href="../hr/pay.html">Pay</a>"#));
+		assert!(html.contains(r#"<a href="notes.txt">Notes</a>"#));
+		assert!(!html.contains(r#" href="/"#));
+		assert!(!html.contains(r#" src="/"#));
+	}
+	#[tokio::test]
+	async fn export__not_empty() {
+		let dir    = content();
+		let output = dir.path().join("site");
+		write_file(&output.join("existing.txt"), b"Existing").unwrap();
+		let result = export(&state(dir.path()), &ExportOptions { output: output.clone(), ..Default::default() }).await;
+		assert!(matches!(result, Err(ExportError::OutputNotEmpty(ref path)) if *path == output));
+	}
+	#[tokio::test]
+	async fn export__zip() {
+		let dir     = content();
+		let output  = dir.path().join("site");
+		let options = ExportOptions { output: output.clone(), roles: vec![], zip: true };
+		let summary = export(&state(dir.path()), &options).await.unwrap();
+		assert_eq!(summary.zip, Some(dir.path().join("site.zip")));
+		let names   = zip_names(&dir.path().join("site.zip"));
+		assert!(names.iter().all(|name| name.starts_with("site/")));
+		assert!(names.contains(&s!("site/index.html")));
+		assert!(names.contains(&s!("site/public/welcome.html")));
+		assert!(names.contains(&s!("site/public/notes.txt")));
+		assert!(!names.iter().any(|name| name.starts_with("site/hr/")));
+	}
+	
+	//		html_path															
+	#[test]
+	fn html_path() {
+		assert_eq!(super::html_path("index.md"),        "index.html");
+		assert_eq!(super::html_path("guides/setup.md"), "guides/setup.html");
+		assert_eq!(super::html_path("images/logo.png"), "images/logo.png");
+		assert_eq!(super::html_path("notes.md.txt"),    "notes.md.txt");
+	}
+	
+	//		relative_url														
+	#[test]
+	fn relative_url__unchanged() {
+		assert_eq!(relative_url("",                          2), "");
+		assert_eq!(relative_url("#section",                  2), "#section");
+		assert_eq!(relative_url("https://example.com/a.md",  2), "https://example.com/a.md");
+		assert_eq!(relative_url("mailto:joe@example.com",    2), "mailto:joe@example.com");
+		assert_eq!(relative_url("//example.com/a.md",        2), "//example.com/a.md");
+	}
+	#[test]
+	fn relative_url__absolute() {
+		assert_eq!(relative_url("/guides/setup.md", 0), "guides/setup.html");
+		assert_eq!(relative_url("/guides/setup.md", 2), "../../guides/setup.html");
+		assert_eq!(relative_url("/css/styles.css",  1), "../css/styles.css");
+	}
+	#[test]
+	fn relative_url__relative() {
+		assert_eq!(relative_url("setup.md",         2), "setup.html");
+		assert_eq!(relative_url("../index.md",      2), "../index.html");
+		assert_eq!(relative_url("images/logo.png",  2), "images/logo.png");
+	}
+	#[test]
+	fn relative_url__directories() {
+		assert_eq!(relative_url("/",                1), "../index.html");
+		assert_eq!(relative_url("/guides/",         1), "../guides/index.html");
+		assert_eq!(relative_url("/guides",          1), "../guides/index.html");
+		assert_eq!(relative_url("/guides/advanced", 0), "guides/advanced/index.html");
+		assert_eq!(relative_url("guides",           1), "guides/index.html");
+		assert_eq!(relative_url("..",               1), "../index.html");
+	}
+	#[test]
+	fn relative_url__query_and_fragment() {
+		assert_eq!(relative_url("/guides/setup.md?raw#install", 1), "../guides/setup.html#install");
+		assert_eq!(relative_url("/guides?page=2",               1), "../guides/index.html");
+		assert_eq!(relative_url("/#top",                        1), "../index.html#top");
+	}
+	
+	//		rewrite_links														
+	#[test]
+	fn rewrite_links__double_quotes() {
+		assert_eq!(
+			rewrite_links(r#"<a href="/guides/setup.md">Setup</a><img src="/images/logo.png">"#, 1),
+			r#"<a href="../guides/setup.html">Setup</a><img src="../images/logo.png">"#,
+		);
+	}
+	#[test]
+	fn rewrite_links__single_quotes() {
+		assert_eq!(
+			rewrite_links("<a href='/guides'>Guides</a><script src='/js/app.js'></script>", 1),
+			"<a href='../guides/index.html'>Guides</a><script src='../js/app.js'></script>",
+		);
+		assert_eq!(
+			rewrite_links(r#"<a href='/say.md' title="it's">Say</a><a title='"' href="/">Home</a>"#, 0),
+			r#"<a href='say.html' title="it's">Say</a><a title='"' href="index.html">Home</a>"#,
+		);
+	}
+	#[test]
+	fn rewrite_links__other_attributes() {
+		let html = r#"<img data-src="/a.png" srcset="/b.png 2x"><a href=/c.md>C</a><p>href="/d.md"</p>"#;
+		assert_eq!(rewrite_links(html, 1), html);
+	}
+	#[test]
+	fn rewrite_links__unterminated() {
+		assert_eq!(rewrite_links(r#"<a href="/guides/setup.md"#, 1), r#"<a href="../guides/setup.html"#);
+		assert_eq!(rewrite_links("<a href=",                      1), "<a href=");
+	}
+	
+	//		zip_directory														
+	#[test]
+	fn zip_directory() {
+		let dir    = TempDir::new().unwrap();
+		let site   = dir.path().join("site");
+		write_file(&site.join("index.html"),        b"Index").unwrap();
+		write_file(&site.join("guides/setup.html"), b"Setup").unwrap();
+		write_file(&site.join("css/styles.css"),    b"Styles").unwrap();
+		let path   = super::zip_directory(&site).unwrap();
+		assert_eq!(path, dir.path().join("site.zip"));
+		assert_eq!(zip_names(&path), vec![
+			s!("site/"),
+			s!("site/css/"),
+			s!("site/css/styles.css"),
+			s!("site/guides/"),
+			s!("site/guides/setup.html"),
+			s!("site/index.html"),
+		]);
+		let mut archive  = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+		let mut contents = String::new();
+		_ = archive.by_name("site/guides/setup.html").unwrap().read_to_string(&mut contents).unwrap();
+		assert_eq!(contents, "Setup");
+	}
+}
@@ -0,0 +1,97 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::config::Config;
+use rubedo::sugar::s;
+use std::collections::HashMap;
+use terracotta::app::config::LoadingBehavior;
+use tokio::sync::broadcast;
+
+
+
+//		Functions																										
+
+//		page_with_template														
+/// Creates a page that names a template in its front matter.
+fn page_with_template(template: &str) -> CompiledPage {
+	let mut page           = CompiledPage::default();
+	page.metadata.template = Some(template.to_owned());
+	page
+}
+
+//		state																	
+/// Creates the application state for the tests, without any local content,
+/// with `hr` restricted to the `hr` role, and with an additional page
+/// template, `page-wide`.
+fn state() -> AppState {
+	let mut config           = Config {
+		access: HashMap::from([(s!("hr/**"), vec![s!("hr")])]),
+		..Default::default()
+	};
+	config.markdown.behavior = LoadingBehavior::Deny;
+	config.audit.enabled     = false;
+	let mut state            = AppState::new(config);
+	state.tera.add_raw_template("page-wide", r#"{% extends "page" %}"#).unwrap();
+	state
+}
+
+
+
+//		Tests																											
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		next_change															
+	#[tokio::test]
+	async fn next_change() {
+		//	Changes to files the user cannot access are not revealed to them
+		let state        = state();
+		let mut receiver = state.reload.subscribe();
+		for path in ["hr/pay.md", "public/welcome.md", "hr/leave.md"] {
+			_ = state.reload.send(s!(path)).unwrap();
+		}
+		assert_eq!(super::next_change(&mut receiver, &state, &[]).await,          Some(s!("public/welcome.md")));
+		assert_eq!(super::next_change(&mut receiver, &state, &[s!("hr")]).await,  Some(s!("hr/leave.md")));
+	}
+	#[tokio::test]
+	async fn next_change__closed() {
+		let state                  = state();
+		let (sender, mut receiver) = broadcast::channel(4);
+		_ = sender.send(s!("hr/pay.md")).unwrap();
+		drop(sender);
+		assert_eq!(super::next_change(&mut receiver, &state, &[]).await, None);
+	}
+	
+	//		page_context														
+	#[test]
+	fn page_context__default() {
+		let state = state();
+		assert_eq!(page_context(&state, "guide.md", &CompiledPage::default(), &[]).0, "page");
+		assert_eq!(page_context(&state, "index.md", &CompiledPage::default(), &[]).0, "index");
+	}
+	#[test]
+	fn page_context__page_templates() {
+		let state = state();
+		assert_eq!(page_context(&state, "guide.md", &page_with_template("page-wide"), &[]).0, "page-wide");
+		assert_eq!(page_context(&state, "guide.md", &page_with_template("index"),     &[]).0, "index");
+		assert_eq!(page_context(&state, "index.md", &page_with_template("page"),      &[]).0, "page");
+	}
+	#[test]
+	fn page_context__other_templates() {
+		//	Templates for other purposes cannot be chosen by pages
+		let state = state();
+		for template in ["audit", "login", "403-forbidden", "print", "layout", "search", "nav"] {
+			assert_eq!(page_context(&state, "guide.md", &page_with_template(template), &[]).0, "page", "{template}");
+		}
+	}
+	#[test]
+	fn page_context__unknown_template() {
+		let state = state();
+		assert_eq!(page_context(&state, "guide.md", &page_with_template("page-missing"), &[]).0, "page");
+	}
+}
@@ -0,0 +1,365 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use core::{
+	iter,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+use futures_util::{SinkExt as _, StreamExt as _};
+use ldap3_proto::{
+	LdapCodec,
+	LdapFilter,
+	LdapMsg,
+	LdapPartialAttribute,
+	LdapResultCode,
+	LdapSearchResultEntry,
+	SearchRequest,
+	ServerOps,
+};
+use rubedo::sugar::s;
+use std::{net::TcpListener as StdTcpListener, sync::Arc};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::Framed;
+
+
+
+//		Constants																										
+
+/// The base DN of the groups in the directory.
+const GROUPS: &str = "ou=groups,dc=example,dc=com";
+
+/// The base DN of the people in the directory.
+const PEOPLE: &str = "ou=people,dc=example,dc=com";
+
+/// The DN of the service account.
+const SERVICE_DN: &str = "cn=rustmark,dc=example,dc=com";
+
+/// The password of the service account.
+const SERVICE_PASSWORD: &str = "s3rv1c3";
+
+
+
+//		Structs																											
+
+//		StubDirectory															
+/// A local LDAP directory, which supports just enough of the protocol to bind,
+/// find users, and find the groups they are members of.
+#[derive(Clone, Debug)]
+struct StubDirectory {
+	/// Whether searches can be made without binding.
+	anonymous:   bool,
+	
+	/// The number of connections made to the directory.
+	connections: Arc<AtomicUsize>,
+	
+	/// The groups, keyed by name, with the DNs of their members.
+	groups:      HashMap<String, Vec<String>>,
+	
+	/// The number of searches made of the directory.
+	searches:    Arc<AtomicUsize>,
+	
+	/// The passwords of the users and the service account, keyed by DN.
+	users:       HashMap<String, String>,
+}
+
+//󰭅		StubDirectory															
+impl StubDirectory {
+	//		new																	
+	/// Creates a directory with two users, `joe` and `ann`, and a service
+	/// account.
+	fn new() -> Self {
+		let joe = format!("uid=joe,{PEOPLE}");
+		let ann = format!("uid=ann,{PEOPLE}");
+		Self {
+			anonymous:   false,
+			connections: Arc::new(AtomicUsize::new(0)),
+			groups:      HashMap::from([
+				(s!("engineering"), vec![joe.clone()]),
+				(s!("people-team"), vec![ann.clone()]),
+				(s!("unmapped"),    vec![joe.clone(), ann.clone()]),
+			]),
+			searches:    Arc::new(AtomicUsize::new(0)),
+			users:       HashMap::from([
+				(joe,            s!("1a2b3c")),
+				(ann,            s!("4d5e6f")),
+				(s!(SERVICE_DN), s!(SERVICE_PASSWORD)),
+			]),
+		}
+	}
+	
+	//		search																
+	/// Responds to a search.
+	fn search(&self, search: &SearchRequest, bound: bool) -> Vec<LdapMsg> {
+		_ = self.searches.fetch_add(1, Ordering::SeqCst);
+		if !bound && !self.anonymous {
+			return vec![search.gen_error(LdapResultCode::InsufficentAccessRights, s!("Anonymous search is not allowed"))];
+		}
+		if search.base == GROUPS {
+			let LdapFilter::Equality(_, ref member) = search.filter else {
+				return vec![search.gen_success()];
+			};
+			return self.groups
+				.iter()
+				.filter(|&(_, members)| members.contains(member))
+				.map(|(name, _)| search.gen_result_entry(LdapSearchResultEntry {
+					dn:         format!("cn={name},{GROUPS}"),
+					attributes: vec![LdapPartialAttribute { atype: s!("cn"), vals: vec![name.as_bytes().to_vec()] }],
+				}))
+				.chain(iter::once(search.gen_success()))
+				.collect()
+			;
+		}
+		if self.users.contains_key(&search.base) {
+			return vec![
+				search.gen_result_entry(LdapSearchResultEntry { dn: search.base.clone(), attributes: vec![] }),
+				search.gen_success(),
+			];
+		}
+		vec![search.gen_error(LdapResultCode::NoSuchObject, s!("No such object"))]
+	}
+	
+	//		serve																
+	/// Serves a connection to the directory.
+	async fn serve(self, stream: TcpStream) {
+		let mut framed = Framed::new(stream, LdapCodec::default());
+		let mut bound  = false;
+		while let Some(Ok(message)) = framed.next().await {
+			let responses = match ServerOps::try_from(message) {
+				Ok(ServerOps::SimpleBind(bind)) => {
+					bound = !bind.dn.is_empty() && self.users.get(&bind.dn) == Some(&bind.pw);
+					if bound || (bind.dn.is_empty() && bind.pw.is_empty()) {
+						vec![bind.gen_success()]
+					} else {
+						vec![bind.gen_invalid_cred()]
+					}
+				},
+				Ok(ServerOps::Search(search))   => self.search(&search, bound),
+				Ok(_) | Err(())                 => break,
+			};
+			for response in responses {
+				if framed.send(response).await.is_err() {
+					return;
+				}
+			}
+		}
+	}
+	
+	//		start																
+	/// Starts the directory on a random local port, on a thread of its own, so
+	/// that it keeps running whilst the thread of the test is blocked.
+	/// 
+	/// # Returns
+	/// 
+	/// The URL of the directory.
+	/// 
+	fn start(&self) -> String {
+		let socket    = StdTcpListener::bind("127.0.0.1:0").unwrap();
+		let url       = format!("ldap://{}", socket.local_addr().unwrap());
+		let directory = self.clone();
+		socket.set_nonblocking(true).unwrap();
+		drop(thread::spawn(move || {
+			Builder::new_current_thread().enable_all().build().unwrap().block_on(async move {
+				let listener = TcpListener::from_std(socket).unwrap();
+				loop {
+					let (stream, _) = listener.accept().await.unwrap();
+					_ = directory.connections.fetch_add(1, Ordering::SeqCst);
+					drop(spawn(directory.clone().serve(stream)));
+				}
+			});
+		}));
+		url
+	}
+}
+
+
+
+//		Functions																										
+
+//		config																	
+/// Creates the configuration for a directory.
+fn config(url: &str) -> LdapConfig {
+	LdapConfig {
+		url:        url.to_owned(),
+		user_dn:    format!("uid={{username}},{PEOPLE}"),
+		group_base: Some(s!(GROUPS)),
+		role_map:   HashMap::from([
+			(s!("engineering"), vec![s!("staff")]),
+			(s!("people-team"), vec![s!("hr"), s!("staff")]),
+		]),
+		timeout:    2,
+		..Default::default()
+	}
+}
+
+//		service_config															
+/// Creates the configuration for a directory, using the service account.
+fn service_config(url: &str) -> LdapConfig {
+	LdapConfig {
+		bind_dn:       Some(s!(SERVICE_DN)),
+		bind_password: Some(s!(SERVICE_PASSWORD)),
+		..config(url)
+	}
+}
+
+
+
+//		Tests																											
+
+//		LdapUserStore															
+#[cfg(test)]
+mod ldap_user_store {
+	use super::*;
+	
+	//		authenticate														
+	#[test]
+	fn authenticate() {
+		let store = LdapUserStore::new(&config(&StubDirectory::new().start()));
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(store.authenticate("ann", "4d5e6f"));
+		assert_eq!(store.roles("joe"), vec![s!("staff")]);
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+	}
+	#[test]
+	fn authenticate__invalid() {
+		let store = LdapUserStore::new(&config(&StubDirectory::new().start()));
+		assert!(!store.authenticate("joe", "4d5e6f"));
+		assert!(!store.authenticate("bob", "1a2b3c"));
+		assert!(!store.authenticate("joe", ""));
+		assert!(!store.authenticate("",    ""));
+		assert!(!store.authenticate("joe,ou=people", "1a2b3c"));
+	}
+	#[test]
+	fn authenticate__service_account() {
+		let store = LdapUserStore::new(&service_config(&StubDirectory::new().start()));
+		assert!(store.authenticate("ann", "4d5e6f"));
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+	}
+	#[test]
+	fn authenticate__unreachable() {
+		let store = LdapUserStore::new(&config("ldap://127.0.0.1:1"));
+		assert!(!store.authenticate("joe", "1a2b3c"));
+		assert!(!store.contains("joe"));
+	}
+	#[test]
+	fn authenticate__reuses_connections() {
+		let directory = StubDirectory::new();
+		let store     = LdapUserStore::new(&config(&directory.start()));
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(store.authenticate("ann", "4d5e6f"));
+		assert!(!store.authenticate("joe", "4d5e6f"));
+		assert_eq!(directory.connections.load(Ordering::SeqCst), 1);
+	}
+	#[tokio::test]
+	async fn authenticate__current_thread_runtime() {
+		let store = LdapUserStore::new(&config(&StubDirectory::new().start()));
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(!store.authenticate("joe", "4d5e6f"));
+	}
+	#[tokio::test(flavor = "multi_thread")]
+	async fn authenticate__multi_thread_runtime() {
+		let store = LdapUserStore::new(&config(&StubDirectory::new().start()));
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(!store.authenticate("joe", "4d5e6f"));
+	}
+	
+	//		contains															
+	#[test]
+	fn contains__not_logged_in() {
+		//	Users who have not logged in since the application started, such as
+		//	those with sessions from before a restart, are looked up
+		let store = LdapUserStore::new(&service_config(&StubDirectory::new().start()));
+		assert!(store.contains("joe"));
+		assert!(!store.contains("bob"));
+		assert!(!store.contains(""));
+	}
+	#[test]
+	fn contains__anonymous() {
+		let mut directory = StubDirectory::new();
+		directory.anonymous = true;
+		let store         = LdapUserStore::new(&config(&directory.start()));
+		assert!(store.contains("ann"));
+		assert!(!store.contains("bob"));
+	}
+	#[test]
+	fn contains__anonymous_refused() {
+		let store = LdapUserStore::new(&config(&StubDirectory::new().start()));
+		assert!(!store.contains("joe"));
+	}
+	#[test]
+	fn contains__not_found_remembered() {
+		let directory = StubDirectory::new();
+		let store     = LdapUserStore::new(&service_config(&directory.start()));
+		assert!(!store.contains("bob"));
+		assert!(!store.contains("bob"));
+		assert!(store.roles("bob").is_empty());
+		assert_eq!(directory.searches.load(Ordering::SeqCst), 1);
+	}
+	#[test]
+	fn contains__not_found_expired() {
+		let directory = StubDirectory::new();
+		let store     = LdapUserStore::new(&LdapConfig { negative_ttl: 0, ..service_config(&directory.start()) });
+		assert!(!store.contains("bob"));
+		assert!(!store.contains("bob"));
+		assert_eq!(directory.searches.load(Ordering::SeqCst), 2);
+	}
+	#[test]
+	fn contains__unreachable_remembered() {
+		//	The failure is remembered, so that each request does not wait for the
+		//	directory to time out
+		let store = LdapUserStore::new(&config("ldap://127.0.0.1:1"));
+		assert!(!store.contains("joe"));
+		assert_eq!(store.users.read().get("joe").map(|cached| cached.roles.is_none()), Some(true));
+	}
+	
+	//		roles																
+	#[test]
+	fn roles__not_logged_in() {
+		let store = LdapUserStore::new(&service_config(&StubDirectory::new().start()));
+		assert_eq!(store.roles("joe"), vec![s!("staff")]);
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+		assert!(store.roles("bob").is_empty());
+	}
+	#[test]
+	fn roles__remembered() {
+		let directory = StubDirectory::new();
+		let store     = LdapUserStore::new(&service_config(&directory.start()));
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+		assert!(store.contains("ann"));
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+		assert_eq!(directory.connections.load(Ordering::SeqCst), 1);
+		//	Looking up the user and their groups takes two searches
+		assert_eq!(directory.searches.load(Ordering::SeqCst),    2);
+	}
+	#[test]
+	fn roles__expired() {
+		let directory = StubDirectory::new();
+		let store     = LdapUserStore::new(&LdapConfig { cache_ttl: 0, ..service_config(&directory.start()) });
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+		assert_eq!(directory.searches.load(Ordering::SeqCst), 4);
+	}
+	#[test]
+	fn roles__logged_in_expired() {
+		//	Users who log in are looked up again once forgotten, so that they lose
+		//	access once removed from the directory
+		let directory = StubDirectory::new();
+		let store     = LdapUserStore::new(&LdapConfig { cache_ttl: 0, ..service_config(&directory.start()) });
+		assert!(store.authenticate("joe", "1a2b3c"));
+		let searches  = directory.searches.load(Ordering::SeqCst);
+		assert_eq!(store.roles("joe"), vec![s!("staff")]);
+		assert_eq!(directory.searches.load(Ordering::SeqCst), searches.saturating_add(2));
+	}
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn roles__multi_thread_runtime() {
+		let store = LdapUserStore::new(&service_config(&StubDirectory::new().start()));
+		assert_eq!(store.roles("ann"), vec![s!("hr"), s!("staff")]);
+	}
+	#[test]
+	fn roles__no_group_base() {
+		let store = LdapUserStore::new(&LdapConfig { group_base: None, ..config(&StubDirectory::new().start()) });
+		assert!(store.authenticate("ann", "4d5e6f"));
+		assert!(store.roles("ann").is_empty());
+	}
+}
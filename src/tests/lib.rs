@@ -0,0 +1,342 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+
+
+
+//		Functions																										
+
+//		envelope																
+/// Creates the stored form of a compiled page, with a given format and version.
+fn envelope(format: &str, version: u32) -> String {
+	serde_json::to_string(&CompiledPageEnvelope {
+		format:  format.to_owned(),
+		version,
+		page:    serde_json::to_value(parse("# Title\n", false)).unwrap(),
+	}).unwrap()
+}
+
+//		html																	
+/// Parses some Markdown using the options given, and returns the HTML.
+fn html(markdown: &str, options: &MarkdownOptions) -> String {
+	parse_with_options(markdown, false, options, &Pipeline::default()).html
+}
+
+//		metadata																
+/// Parses some front matter, and returns the metadata.
+fn metadata(front_matter: &str) -> PageMetadata {
+	parse_front_matter(front_matter).0.unwrap()
+}
+
+
+
+//		Tests																											
+
+//		CompiledPage															
+#[cfg(test)]
+mod compiled_page {
+	use super::*;
+	
+	//		from_json															
+	#[test]
+	fn from_json() {
+		let page = CompiledPage::from_json(&envelope(COMPILED_PAGE_FORMAT, COMPILED_PAGE_VERSION)).unwrap();
+		assert_eq!(page.title, "Title");
+	}
+	#[test]
+	fn from_json__stale_version() {
+		//	Pages written by an earlier version need to be compiled again, which
+		//	happens whenever they cannot be read
+		let older = COMPILED_PAGE_VERSION.saturating_sub(1);
+		let newer = COMPILED_PAGE_VERSION.saturating_add(1);
+		assert!(matches!(CompiledPage::from_json(&envelope(COMPILED_PAGE_FORMAT, older)), Err(CompiledPageError::UnsupportedVersion(version)) if version == older));
+		assert!(matches!(CompiledPage::from_json(&envelope(COMPILED_PAGE_FORMAT, newer)), Err(CompiledPageError::UnsupportedVersion(version)) if version == newer));
+	}
+	#[test]
+	fn from_json__unknown_format() {
+		assert!(matches!(CompiledPage::from_json(&envelope("other", COMPILED_PAGE_VERSION)), Err(CompiledPageError::UnknownFormat(format)) if format == "other"));
+	}
+	#[test]
+	fn from_json__unversioned() {
+		//	Pages stored before the envelope was added are not misread
+		let bare = serde_json::to_string(&parse("# Title\n", false)).unwrap();
+		assert!(matches!(CompiledPage::from_json(&bare),      Err(CompiledPageError::FailedToDeserialize(_))));
+		assert!(matches!(CompiledPage::from_json("not json"), Err(CompiledPageError::FailedToDeserialize(_))));
+	}
+	
+	//		to_json																
+	#[test]
+	fn to_json() {
+		let page   = parse("---\ntitle: Page\naccess: hr\n---\n# Title\n\nSee [here](other.md).\n", false);
+		let stored = page.to_json().unwrap();
+		let parsed = serde_json::from_str::<JsonValue>(&stored).unwrap();
+		assert_eq!(parsed.get("format"),  Some(&JsonValue::from(COMPILED_PAGE_FORMAT)));
+		assert_eq!(parsed.get("version"), Some(&JsonValue::from(COMPILED_PAGE_VERSION)));
+		let loaded = CompiledPage::from_json(&stored).unwrap();
+		assert_eq!(loaded.title,    page.title);
+		assert_eq!(loaded.html,     page.html);
+		assert_eq!(loaded.metadata, page.metadata);
+		assert_eq!(loaded.links,    page.links);
+		assert_eq!(loaded.source,   page.source);
+	}
+}
+
+//		MarkdownOptions															
+#[cfg(test)]
+mod markdown_options {
+	use super::*;
+	
+	//		check																
+	#[test]
+	fn check() {
+		assert!(MarkdownOptions::default().check().is_ok());
+		for theme in SYNTAX_THEMES {
+			assert!(MarkdownOptions { syntax_theme: theme.to_owned(), ..Default::default() }.check().is_ok(), "{theme}");
+		}
+		assert!(MarkdownOptions { syntax_theme: s!(""), ..Default::default() }.check().is_ok());
+		assert!(MarkdownOptions { syntax_theme: s!("Unknown"), syntax_highlighting: false, ..Default::default() }.check().is_ok());
+		assert!(matches!(
+			MarkdownOptions { syntax_theme: s!("Unknown"), ..Default::default() }.check(),
+			Err(MarkdownOptionsError::UnknownSyntaxTheme(theme)) if theme == "Unknown"
+		));
+	}
+	
+	//		deserialize															
+	#[test]
+	fn deserialize() {
+		//	Options not given keep their defaults
+		let options: MarkdownOptions = toml::from_str("smart = false\nlist_style = \"Star\"\nheader_id_prefix = \"h-\"\n").unwrap();
+		assert_eq!(options, MarkdownOptions {
+			smart:            false,
+			list_style:       ListStyle::Star,
+			header_id_prefix: s!("h-"),
+			..Default::default()
+		});
+		assert_eq!(toml::from_str::<MarkdownOptions>("").unwrap(), MarkdownOptions::default());
+	}
+	
+	//		to_comrak															
+	#[test]
+	fn to_comrak__defaults() {
+		//	The defaults are the settings that were used before the options could
+		//	be configured
+		let comrak = MarkdownOptions::default().to_comrak();
+		assert!(comrak.extension.strikethrough);
+		assert!(comrak.extension.tagfilter);
+		assert!(comrak.extension.table);
+		assert!(comrak.extension.autolink);
+		assert!(comrak.extension.tasklist);
+		assert!(comrak.extension.superscript);
+		assert_eq!(comrak.extension.header_ids,             Some(s!("")));
+		assert!(comrak.extension.footnotes);
+		assert!(comrak.extension.description_lists);
+		assert_eq!(comrak.extension.front_matter_delimiter, Some(s!("---")));
+		assert!(comrak.extension.shortcodes);
+		assert!(comrak.extension.wikilinks_title_after_pipe);
+		assert!(!comrak.extension.math_dollars);
+		assert!(comrak.parse.smart);
+		assert_eq!(comrak.parse.default_info_string,        Some(s!("")));
+		assert!(comrak.parse.relaxed_tasklist_matching);
+		assert!(!comrak.render.hardbreaks);
+		assert!(!comrak.render.github_pre_lang);
+		assert!(comrak.render.full_info_string);
+		assert_eq!(comrak.render.width,                     80);
+		assert!(comrak.render.unsafe_);
+		assert!(!comrak.render.escape);
+		assert!(matches!(comrak.render.list_style,          ListStyleType::Dash));
+		assert!(!comrak.render.sourcepos);
+	}
+	#[test]
+	fn to_comrak__changed() {
+		let comrak = MarkdownOptions {
+			autolink:                  false,
+			default_info_string:       s!("rust"),
+			description_lists:         false,
+			escape:                    true,
+			footnotes:                 false,
+			full_info_string:          false,
+			github_pre_lang:           true,
+			hardbreaks:                true,
+			header_id_prefix:          s!("h-"),
+			list_style:                ListStyle::Plus,
+			math:                      true,
+			relaxed_tasklist_matching: false,
+			shortcodes:                false,
+			smart:                     false,
+			sourcepos:                 true,
+			strikethrough:             false,
+			superscript:               false,
+			table:                     false,
+			tagfilter:                 false,
+			tasklist:                  false,
+			unsafe_html:               false,
+			width:                     100,
+			..Default::default()
+		}.to_comrak();
+		assert!(!comrak.extension.strikethrough);
+		assert!(!comrak.extension.tagfilter);
+		assert!(!comrak.extension.table);
+		assert!(!comrak.extension.autolink);
+		assert!(!comrak.extension.tasklist);
+		assert!(!comrak.extension.superscript);
+		assert_eq!(comrak.extension.header_ids,             Some(s!("h-")));
+		assert!(!comrak.extension.footnotes);
+		assert!(!comrak.extension.description_lists);
+		assert_eq!(comrak.extension.front_matter_delimiter, Some(s!("---")));
+		assert!(!comrak.extension.shortcodes);
+		assert!(comrak.extension.wikilinks_title_after_pipe);
+		assert!(comrak.extension.math_dollars);
+		assert!(!comrak.parse.smart);
+		assert_eq!(comrak.parse.default_info_string,        Some(s!("rust")));
+		assert!(!comrak.parse.relaxed_tasklist_matching);
+		assert!(comrak.render.hardbreaks);
+		assert!(comrak.render.github_pre_lang);
+		assert!(!comrak.render.full_info_string);
+		assert_eq!(comrak.render.width,                     100);
+		assert!(!comrak.render.unsafe_);
+		assert!(comrak.render.escape);
+		assert!(matches!(comrak.render.list_style,          ListStyleType::Plus));
+		assert!(comrak.render.sourcepos);
+		let no_ids = MarkdownOptions { header_ids: false, header_id_prefix: s!("h-"), ..Default::default() }.to_comrak();
+		assert_eq!(no_ids.extension.header_ids, None);
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		parse_front_matter													
+	#[test]
+	fn parse_front_matter__none() {
+		let (parsed, body) = parse_front_matter("# Title\n\nText.\n");
+		assert_eq!(parsed.unwrap(), PageMetadata::default());
+		assert_eq!(body, "# Title\n\nText.\n");
+	}
+	#[test]
+	fn parse_front_matter__yaml() {
+		let (parsed, body) = parse_front_matter("---\ntitle: Page\ntags: [a, b]\norder: 2\nlayout: wide\n---\n# Title\n");
+		let yaml           = parsed.unwrap();
+		assert_eq!(yaml.title.as_deref(), Some("Page"));
+		assert_eq!(yaml.tags,             vec![s!("a"), s!("b")]);
+		assert_eq!(yaml.order,            Some(2));
+		assert_eq!(yaml.extra.get("layout"), Some(&JsonValue::from("wide")));
+		assert_eq!(body, "# Title\n");
+	}
+	#[test]
+	fn parse_front_matter__toml() {
+		let (parsed, body) = parse_front_matter("+++\ntitle = \"Page\"\naccess = [\"hr\"]\ndate = 2024-01-01\n+++\n# Title\n");
+		let toml           = parsed.unwrap();
+		assert_eq!(toml.title.as_deref(), Some("Page"));
+		assert_eq!(toml.access,           vec![s!("hr")]);
+		assert_eq!(toml.date.as_deref(),  Some("2024-01-01"));
+		assert_eq!(body, "# Title\n");
+	}
+	#[test]
+	fn parse_front_matter__empty() {
+		assert_eq!(metadata("---\n---\nText.\n"),  PageMetadata::default());
+		assert_eq!(metadata("+++\n+++\nText.\n"),  PageMetadata::default());
+	}
+	#[test]
+	fn parse_front_matter__unterminated() {
+		let (parsed, body) = parse_front_matter("---\ntitle: Page\n");
+		assert_eq!(parsed.unwrap(), PageMetadata::default());
+		assert_eq!(body, "---\ntitle: Page\n");
+	}
+	#[test]
+	fn parse_front_matter__single_values() {
+		//	A single role or tag can be given without a list
+		let yaml = metadata("---\naccess: admin\ntags: foo\n---\n");
+		assert_eq!(yaml.access, vec![s!("admin")]);
+		assert_eq!(yaml.tags,   vec![s!("foo")]);
+		let toml = metadata("+++\naccess = \"admin\"\ntags = \"foo\"\n+++\n");
+		assert_eq!(toml.access, vec![s!("admin")]);
+		assert_eq!(toml.tags,   vec![s!("foo")]);
+		let null = metadata("---\naccess:\ntags: ~\n---\n");
+		assert!(null.access.is_empty());
+		assert!(null.tags.is_empty());
+	}
+	#[test]
+	fn parse_front_matter__wrong_type() {
+		for front_matter in [
+			"---\ntitle: 2024\n---\nText.\n",
+			"---\naccess: 42\n---\nText.\n",
+			"---\naccess: {hr: true}\n---\nText.\n",
+			"---\norder: first\n---\nText.\n",
+			"---\n- a list\n---\nText.\n",
+		] {
+			let (parsed, body) = parse_front_matter(front_matter);
+			assert!(matches!(parsed, Err(FrontMatterError::WrongType(_))), "{front_matter}");
+			assert_eq!(body, "Text.\n");
+		}
+	}
+	#[test]
+	fn parse_front_matter__invalid_yaml() {
+		let (parsed, body) = parse_front_matter("---\ntitle: [Page\n---\nText.\n");
+		assert!(matches!(parsed, Err(FrontMatterError::InvalidYaml(_))));
+		assert_eq!(body, "Text.\n");
+	}
+	#[test]
+	fn parse_front_matter__invalid_toml() {
+		let (parsed, body) = parse_front_matter("+++\ntitle = Page\n+++\nText.\n");
+		assert!(matches!(parsed, Err(FrontMatterError::InvalidToml(_))));
+		assert_eq!(body, "Text.\n");
+	}
+	
+	//		parse																
+	#[test]
+	fn parse__invalid_front_matter() {
+		let page = parse("---\naccess: admin\ntitle: 2024\n---\n# Secret\n\nText.\n", false);
+		assert!(page.invalid.unwrap().starts_with("Invalid page metadata: "));
+		assert_eq!(page.metadata, PageMetadata::default());
+		assert_eq!(page.title,    "Secret");
+		assert!(page.html.contains("Text."));
+		assert!(parse("---\naccess: admin\n---\n# Secret\n", false).invalid.is_none());
+	}
+	
+	//		parse_with_options													
+	#[test]
+	fn parse_with_options() {
+		let defaults = MarkdownOptions::default();
+		let changed  = MarkdownOptions {
+			hardbreaks:       true,
+			header_id_prefix: s!("h-"),
+			smart:            false,
+			strikethrough:    false,
+			unsafe_html:      false,
+			..Default::default()
+		};
+		let markdown = "## Heading\n\n\"Quoted\" ~~struck~~\nnext line <span>raw</span>\n";
+		let before   = html(markdown, &defaults);
+		assert!(before.contains(r#"id="heading""#));
+		assert!(before.contains("“Quoted”"));
+		assert!(before.contains("<del>struck</del>"));
+		assert!(before.contains("<span>raw</span>"));
+		assert!(!before.contains("<br"));
+		let after    = html(markdown, &changed);
+		assert!(after.contains(r#"id="h-heading""#));
+		assert!(!after.contains("“Quoted”"));
+		assert!(after.contains("~~struck~~"));
+		assert!(!after.contains("<span>raw</span>"));
+		assert!(after.contains("<br"));
+	}
+	#[test]
+	fn parse_with_options__syntax_theme() {
+		//	Without a theme, CSS classes are used instead of inline styles, as they
+		//	are for an unknown theme, which would otherwise cause a panic
+		let markdown = "```rust\nfn main() {}\n```\n";
+		assert!(html(markdown, &MarkdownOptions::default()).contains("style=\""));
+		for theme in ["", "Unknown"] {
+			let output = html(markdown, &MarkdownOptions { syntax_theme: theme.to_owned(), ..Default::default() });
+			assert!(!output.contains("style=\""), "{theme}");
+			assert!(output.contains("class=\""),  "{theme}");
+		}
+		let plain = html(markdown, &MarkdownOptions { syntax_highlighting: false, ..Default::default() });
+		assert!(!plain.contains("style=\""));
+		assert!(plain.contains("fn main() {}"));
+	}
+}
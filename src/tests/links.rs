@@ -0,0 +1,258 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+use crate::parse;
+use rubedo::sugar::s;
+
+
+
+//		Functions																										
+
+//		anchor																	
+/// Creates an `<a href>` link to a target.
+fn anchor(target: &str) -> Link {
+	Link { kind: LinkKind::Anchor, target: target.to_owned() }
+}
+
+//		broken																	
+/// Checks some links in a page, and returns the targets found to be broken,
+/// along with what is missing.
+fn broken(checker: &LinkChecker, page: &str, links: &[Link]) -> Vec<(String, MissingTarget)> {
+	checker.check(page, links).into_iter().map(|link| (link.target, link.missing)).collect()
+}
+
+//		backlinks																
+/// Creates a link graph from some pages, given as paths and Markdown, and
+/// returns the paths of the pages linking to a page.
+fn backlinks(pages: &[(&str, &str)], path: &str) -> Vec<String> {
+	graph(pages).backlinks(path).into_iter().map(|backlink| backlink.path).collect()
+}
+
+//		checker																	
+/// Creates a link checker from some pages, given as paths and Markdown, and
+/// some other files.
+fn checker(pages: &[(&str, &str)], files: &[&str]) -> LinkChecker {
+	let mut checker = LinkChecker::new();
+	for &(path, markdown) in pages {
+		checker.add_page(path, &parse(markdown, false).html);
+	}
+	for file in files {
+		checker.add_file(file);
+	}
+	checker
+}
+
+//		graph																	
+/// Creates a link graph from some pages, given as paths and Markdown.
+fn graph(pages: &[(&str, &str)]) -> LinkGraph {
+	let mut graph = LinkGraph::new();
+	for &(path, markdown) in pages {
+		let page = parse(markdown, false);
+		graph.add_page(path, &page.title, &page.links);
+	}
+	graph
+}
+
+
+
+//		Tests																											
+
+//		BrokenLink																
+#[cfg(test)]
+mod broken_link {
+	use super::*;
+	
+	//		fmt																	
+	#[test]
+	fn fmt() {
+		let link = BrokenLink { page: s!("a.md"), target: s!("b.md#top"), missing: MissingTarget::Anchor };
+		assert_eq!(link.to_string(), "a.md: b.md#top (anchor not found)");
+	}
+}
+
+//		LinkChecker																
+#[cfg(test)]
+mod link_checker {
+	use super::*;
+	
+	//		check																
+	#[test]
+	fn check() {
+		let checker = checker(
+			&[("index.md", "# Home\n"), ("guide/setup.md", "# Setup\n\n## Install\n")],
+			&["images/logo.png"],
+		);
+		let links   = [
+			anchor("guide/setup.md"),
+			anchor("guide/setup.md#install"),
+			anchor("../index.md"),
+			anchor("/guide/setup.md?view=1#install"),
+			anchor("#setup"),
+			anchor("https://example.com/missing.md"),
+			anchor("mailto:someone@example.com"),
+			anchor("/search"),
+			Link { kind: LinkKind::Image, target: s!("../images/logo.png") },
+		];
+		assert!(broken(&checker, "guide/setup.md", &links[2..]).is_empty());
+		assert!(broken(&checker, "index.md",       &links[..2]).is_empty());
+	}
+	#[test]
+	fn check__broken() {
+		let checker = checker(&[("index.md", "# Home\n\n## Intro\n")], &["images/logo.png"]);
+		assert_eq!(broken(&checker, "index.md", &[
+			anchor("missing.md"),
+			anchor("index.md#missing"),
+			anchor("#intro"),
+			anchor("#outro"),
+			anchor("/Missing.MD"),
+			Link { kind: LinkKind::Image, target: s!("images/other.png") },
+			anchor("../index.md"),
+		]), vec![
+			(s!("missing.md"),       MissingTarget::Page),
+			(s!("index.md#missing"), MissingTarget::Anchor),
+			(s!("#outro"),           MissingTarget::Anchor),
+			(s!("/Missing.MD"),      MissingTarget::Page),
+			(s!("images/other.png"), MissingTarget::File),
+			(s!("../index.md"),      MissingTarget::Page),
+		]);
+	}
+	#[test]
+	fn check__encoded() {
+		let checker = checker(&[("my page.md", "# My page\n\n## Über\n")], &[]);
+		assert!(broken(&checker, "index.md", &[anchor("my%20page.md"), anchor("my%20page.md#%C3%BCber")]).is_empty());
+	}
+	
+	//		add_page															
+	#[test]
+	fn add_page__replaces() {
+		let mut checker = checker(&[("a.md", "# A\n\n## Old\n")], &[]);
+		checker.add_page("a.md", &parse("# A\n\n## New\n", false).html);
+		assert_eq!(broken(&checker, "b.md", &[anchor("a.md#old"), anchor("a.md#new")]), vec![
+			(s!("a.md#old"), MissingTarget::Anchor),
+		]);
+	}
+	
+	//		remove_page															
+	#[test]
+	fn remove_page() {
+		let mut checker = checker(&[("a.md", "# A\n")], &[]);
+		checker.remove_page("a.md");
+		checker.remove_page("b.md");
+		assert_eq!(broken(&checker, "c.md", &[anchor("a.md")]), vec![(s!("a.md"), MissingTarget::Page)]);
+	}
+}
+
+//		LinkGraph																
+#[cfg(test)]
+mod link_graph {
+	use super::*;
+	
+	//		add_page															
+	#[test]
+	fn add_page__replaces() {
+		let mut graph = graph(&[("a.md", "# A\n\nSee [B](b.md).\n")]);
+		graph.add_page("a.md", "A", &[anchor("c.md")]);
+		assert!(graph.backlinks("b.md").is_empty());
+		assert_eq!(graph.backlinks("c.md").len(), 1);
+	}
+	
+	//		backlinks															
+	#[test]
+	fn backlinks() {
+		//	Linking pages are ordered by title, and each is listed once however
+		//	many times it links to the page
+		let graph = graph(&[
+			("guide/setup.md", "# Setup\n\nSee [run](run.md) and [again](run.md#step).\n"),
+			("about.md",       "# About\n\nSee [running](/guide/run.md).\n"),
+			("guide/run.md",   "# Run\n"),
+		]);
+		assert_eq!(graph.backlinks("guide/run.md"), vec![
+			Backlink { path: s!("about.md"),       title: s!("About") },
+			Backlink { path: s!("guide/setup.md"), title: s!("Setup") },
+		]);
+		assert!(graph.backlinks("about.md").is_empty());
+		assert!(graph.backlinks("missing.md").is_empty());
+	}
+	#[test]
+	fn backlinks__ignored() {
+		//	Only links to other Markdown pages count, and not images, files, or
+		//	other sites
+		assert!(super::backlinks(&[("a.md", "# A\n\n[Self](a.md) [Top](#a) [Site](https://example.com/a.md)\n")], "a.md").is_empty());
+		assert!(super::backlinks(&[("a.md", "# A\n\n[File](b.png) ![Image](b.png)\n")], "b.png").is_empty());
+	}
+	
+	//		remove_page															
+	#[test]
+	fn remove_page() {
+		let mut graph = graph(&[("a.md", "# A\n\n[C](c.md)\n"), ("b.md", "# B\n\n[C](c.md)\n")]);
+		graph.remove_page("a.md");
+		graph.remove_page("missing.md");
+		assert_eq!(graph.backlinks("c.md"), vec![Backlink { path: s!("b.md"), title: s!("B") }]);
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		find_links															
+	#[test]
+	fn find_links() {
+		let document = Document::from(r##"<p><a href="a.md">A</a> <a name="x">X</a> <img src="b.png"> <a href="#c">C</a></p>"##);
+		assert_eq!(super::find_links(&document), vec![
+			anchor("a.md"),
+			Link { kind: LinkKind::Image, target: s!("b.png") },
+			anchor("#c"),
+		]);
+	}
+	
+	//		is_external															
+	#[test]
+	fn is_external() {
+		assert!(super::is_external("https://example.com/"));
+		assert!(super::is_external("mailto:someone@example.com"));
+		assert!(super::is_external("//example.com/page.md"));
+		assert!(super::is_external("svn+ssh://example.com/"));
+		assert!(!super::is_external("page.md"));
+		assert!(!super::is_external("/page.md"));
+		assert!(!super::is_external("#anchor"));
+		assert!(!super::is_external(":not-a-scheme"));
+		assert!(!super::is_external("dir/file:name.md"));
+	}
+	
+	//		percent_decode														
+	#[test]
+	fn percent_decode() {
+		assert_eq!(super::percent_decode("a%20b%2Fc"),   "a b/c");
+		assert_eq!(super::percent_decode("%C3%BCber"),   "über");
+		assert_eq!(super::percent_decode("100%"),        "100%");
+		assert_eq!(super::percent_decode("%zz%2"),       "%zz%2");
+	}
+	
+	//		resolve																
+	#[test]
+	fn resolve() {
+		let resolved = |target| super::resolve("guide/setup.md", target);
+		assert_eq!(resolved("run.md"),             Some((s!("guide/run.md"), None)));
+		assert_eq!(resolved("./run.md#step-1"),    Some((s!("guide/run.md"), Some(s!("step-1")))));
+		assert_eq!(resolved("../index.md?x=1#"),   Some((s!("index.md"), None)));
+		assert_eq!(resolved("/about.md"),          Some((s!("about.md"), None)));
+		assert_eq!(resolved("#install"),           Some((s!("guide/setup.md"), Some(s!("install")))));
+		assert_eq!(resolved("../../outside.md"),   Some((s!("../outside.md"), None)));
+		assert_eq!(resolved("/images/logo.png"),   None);
+		assert_eq!(resolved("https://example.com"), None);
+		assert_eq!(resolved(""),                   None);
+	}
+	
+	//		resolve_path														
+	#[test]
+	fn resolve_path() {
+		assert_eq!(super::resolve_path("guide/setup.md", "/images/logo.png"), Some((s!("images/logo.png"), None)));
+		assert_eq!(super::resolve_path("guide/setup.md", "../a%20b.png"),     Some((s!("a b.png"), None)));
+		assert_eq!(super::resolve_path("index.md",       "../../a.png"),      Some((s!("../../a.png"), None)));
+	}
+}
@@ -0,0 +1,113 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+use core::fmt::Write as _;
+use crate::{MarkdownOptions, parse_with_options, pipeline::Pipeline};
+use serde_json::json;
+
+
+
+//		Functions																										
+
+//		convert																	
+/// Runs a maths processor over some inline maths, as marked by the Markdown
+/// parser, and returns the result.
+fn convert(processor: &MathProcessor, maths: &[&str]) -> String {
+	let html     = maths.iter().fold(String::new(), |mut html, tex| {
+		_ = write!(html, r#"<span data-math-style="inline">{tex}</span>"#);
+		html
+	});
+	let document = Document::from(&html);
+	processor.process(&document);
+	document.html().to_string()
+}
+
+//		html																	
+/// Parses some Markdown with maths enabled or disabled, using the default
+/// pipeline, and returns the HTML.
+fn html(markdown: &str, math: bool) -> String {
+	parse_with_options(markdown, false, &MarkdownOptions { math, ..Default::default() }, &Pipeline::default()).html
+}
+
+
+
+//		Tests																											
+
+//		MathProcessor															
+#[cfg(test)]
+mod math_processor {
+	use super::*;
+	
+	//		from_options														
+	#[test]
+	fn from_options() {
+		assert!(MathProcessor::from_options(&JsonValue::Null).is_ok());
+		assert!(MathProcessor::from_options(&json!({ "macros": { "R": "\\mathbb{R}" } })).is_ok());
+		assert!(matches!(
+			MathProcessor::from_options(&json!({ "macros": ["R"] })),
+			Err(PipelineError::InvalidOptions(name, _)) if name == "math"
+		));
+	}
+	
+	//		new																	
+	#[test]
+	fn new__invalid_macro() {
+		//	The macro that failed is named, whether its name or its definition is
+		//	at fault
+		for (name, definition) in [("bad name", "x"), ("unknown", "\\notacommand")] {
+			let options = MathOptions { macros: BTreeMap::from([(s!("fine"), s!("y")), (name.to_owned(), definition.to_owned())]) };
+			assert!(matches!(
+				MathProcessor::new(options),
+				Err(PipelineError::InvalidOptions(processor, message)) if processor == "math" && message.starts_with(&format!("Macro {name}: "))
+			), "{name}");
+		}
+	}
+	
+	//		process																
+	#[test]
+	fn process() {
+		let output   = html("Inline $x^2$ and display:\n\n$$\\frac{a}{b}$$\n", true);
+		let document = Document::from(&output);
+		assert_eq!(document.select("math").length(),                   2);
+		assert_eq!(document.select(r#"math[display="block"]"#).length(), 1);
+		assert!(document.select("math[display=\"block\"] mfrac").exists());
+		assert!(document.select("math:not([display]) msup").exists());
+		assert!(!document.select("[data-math-style]").exists());
+	}
+	#[test]
+	fn process__disabled() {
+		//	Without the option, dollar signs are left as they are
+		let output = html("Costs $5 and $10, or $x^2$.\n", false);
+		assert!(output.contains("Costs $5 and $10, or $x^2$."));
+		assert!(!output.contains("<math"));
+	}
+	#[test]
+	fn process__invalid() {
+		//	The TeX is left in place, so that it can still be read
+		let output   = html("Broken $\\frac{a}$ maths.\n", true);
+		let document = Document::from(&output);
+		let span     = document.select("span.math-error");
+		assert_eq!(span.length(), 1);
+		assert!(span.attr("title").unwrap().starts_with("Could not render maths: "));
+		assert_eq!(span.text().to_string(), "\\frac{a}");
+		assert!(!document.select("math").exists());
+	}
+	#[test]
+	fn process__macros() {
+		let processor = MathProcessor::new(MathOptions { macros: BTreeMap::from([
+			(s!("\\R"),   s!("\\mathbb{R}")),
+			(s!("pair"), s!("\\langle #1, #2 \\rangle")),
+		]) }).unwrap();
+		let output    = convert(&processor, &["x \\in \\R", "\\pair{a}{b}"]);
+		let document  = Document::from(&output);
+		assert_eq!(document.select("math").length(), 2);
+		assert!(output.contains("ℝ"));
+		assert!(output.contains("⟨"));
+		assert!(!document.select(".math-error").exists());
+		//	Macros are not known to processors without them
+		assert!(Document::from(&convert(&MathProcessor::default(), &["\\pair{a}{b}"])).select(".math-error").exists());
+	}
+}
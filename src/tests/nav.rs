@@ -0,0 +1,160 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+
+
+
+//		Functions																										
+
+//		outline																	
+/// Summarises the entries in a list as titles, paths, and pages, indented by
+/// depth.
+fn outline(items: &[NavItem]) -> Vec<String> {
+	let mut lines = vec![];
+	for item in items {
+		lines.push(format!("{} {} {}", item.title, item.path, item.page.as_deref().unwrap_or("-")));
+		lines.extend(outline(&item.children).into_iter().map(|line| format!("  {line}")));
+	}
+	lines
+}
+
+//		tree																	
+/// Creates a sorted navigation tree from some pages, given as paths, titles,
+/// and orders.
+fn tree(pages: &[(&str, &str, Option<i64>)]) -> NavTree {
+	let mut nav = NavTree::new();
+	for &(path, title, order) in pages {
+		nav.insert(path, title, order);
+	}
+	nav.sort();
+	nav
+}
+
+
+
+//		Tests																											
+
+//		NavTree																	
+#[cfg(test)]
+mod nav_tree {
+	use super::*;
+	
+	//		filtered															
+	#[test]
+	fn filtered() {
+		//	A directory is kept for its allowed pages, but not linked to if its
+		//	own page is not allowed
+		let nav      = tree(&[
+			("guide/index.md", "Guide", None),
+			("guide/a.md",     "A",     None),
+			("guide/b.md",     "B",     None),
+			("hr/pay.md",      "Pay",   None),
+		]);
+		let filtered = nav.filtered(|path| path != "guide/index.md" && path != "guide/b.md" && !path.starts_with("hr/"));
+		assert_eq!(outline(filtered.items()), vec![
+			"Guide guide -",
+			"  A guide/a.md guide/a.md",
+		]);
+		assert!(nav.filtered(|_| false).is_empty());
+		assert_eq!(nav.filtered(|_| true), nav);
+	}
+	
+	//		insert																
+	#[test]
+	fn insert() {
+		let nav = tree(&[
+			("index.md",           "Home",  None),
+			("guide/index.md",     "Guide", None),
+			("guide/setup/run.md", "Run",   None),
+			("about.md",           "About", None),
+		]);
+		assert_eq!(outline(nav.items()), vec![
+			"About about.md about.md",
+			"Guide guide guide/index.md",
+			"  setup guide/setup -",
+			"    Run guide/setup/run.md guide/setup/run.md",
+		]);
+		assert!(NavTree::new().is_empty());
+	}
+	#[test]
+	fn insert__replaces() {
+		let mut nav = tree(&[("a.md", "A", None), ("b.md", "B", None)]);
+		nav.insert("a.md", "Z", Some(5));
+		nav.sort();
+		assert_eq!(outline(nav.items()), vec!["Z a.md a.md", "B b.md b.md"]);
+		assert_eq!(nav.items().first().unwrap().order, Some(5));
+	}
+	
+	//		pages																
+	#[test]
+	fn pages() {
+		let nav = tree(&[
+			("guide/b.md",     "B",     Some(1)),
+			("guide/a.md",     "A",     Some(2)),
+			("guide/index.md", "Guide", None),
+			("about.md",       "About", None),
+		]);
+		assert_eq!(nav.pages(""),      vec!["about.md", "guide/index.md", "guide/b.md", "guide/a.md"]);
+		assert_eq!(nav.pages("guide"), vec!["guide/index.md", "guide/b.md", "guide/a.md"]);
+		assert!(nav.pages("missing").is_empty());
+	}
+	
+	//		remove																
+	#[test]
+	fn remove() {
+		let mut nav = tree(&[
+			("about.md",           "About", None),
+			("guide/setup/run.md", "Run",   None),
+		]);
+		nav.remove("guide/setup/run.md");
+		nav.remove("missing/page.md");
+		nav.remove("index.md");
+		assert_eq!(outline(nav.items()), vec!["About about.md about.md"]);
+	}
+	#[test]
+	fn remove__directory_page() {
+		//	A directory whose own page is removed keeps its other entries, but is
+		//	no longer linked to and goes back to being named after the directory
+		let mut nav = tree(&[
+			("guide/index.md", "Guide", Some(1)),
+			("guide/a.md",     "A",     None),
+		]);
+		nav.remove("guide/index.md");
+		assert_eq!(outline(nav.items()), vec![
+			"guide guide -",
+			"  A guide/a.md guide/a.md",
+		]);
+		assert_eq!(nav.items().first().unwrap().order, None);
+		nav.remove("guide/a.md");
+		assert!(nav.is_empty());
+	}
+	
+	//		sort																
+	#[test]
+	fn sort() {
+		//	Entries with an order come first, and the rest are ordered by title,
+		//	ignoring case, at every level
+		let nav = tree(&[
+			("c.md",       "charlie", None),
+			("a.md",       "Alpha",   None),
+			("z.md",       "Zulu",    Some(2)),
+			("y.md",       "Yankee",  Some(-1)),
+			("dir/b.md",   "bravo",   None),
+			("dir/a.md",   "Alpha",   None),
+			("dir/z.md",   "Zulu",    Some(0)),
+		]);
+		assert_eq!(outline(nav.items()), vec![
+			"Yankee y.md y.md",
+			"Zulu z.md z.md",
+			"Alpha a.md a.md",
+			"charlie c.md c.md",
+			"dir dir -",
+			"  Zulu dir/z.md dir/z.md",
+			"  Alpha dir/a.md dir/a.md",
+			"  bravo dir/b.md dir/b.md",
+		]);
+	}
+}
@@ -0,0 +1,465 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::{
+	config::Config,
+	routes::app,
+	state::AppState,
+};
+use axum::{
+	Form,
+	Json,
+	Router,
+	body::Body,
+	extract::State,
+	http::{Request, StatusCode, header::{COOKIE, LOCATION, SET_COOKIE}},
+	response::{IntoResponse as _, Response as AxumResponse},
+	routing::{get, post},
+};
+use base64::engine::general_purpose::STANDARD;
+use chrono::Utc;
+use jsonwebtoken::{EncodingKey, Header, encode};
+use parking_lot::Mutex;
+use reqwest::Url;
+use rubedo::sugar::s;
+use serde_json::json;
+use std::{fs, path::Path, sync::Arc};
+use tempfile::TempDir;
+use terracotta::app::config::LoadingBehavior;
+use tokio::{net::TcpListener, spawn};
+use tower::ServiceExt as _;
+
+
+
+//		Constants																										
+
+/// The client ID registered with the mock provider.
+const CLIENT_ID: &str = "rustmark";
+
+/// The client secret registered with the mock provider.
+const CLIENT_SECRET: &str = "client-secret";
+
+/// The ID of the mock provider's signing key.
+const KEY_ID: &str = "test-key";
+
+/// The private part of the mock provider's Ed25519 signing key, as PKCS#8 DER
+/// encoded in base64.
+const PRIVATE_KEY: &str = "MC4CAQAwBQYDK2VwBCIEIB/b+W/7a31EBKDc8VzkUHpRcGiw7++5IXKGu7jADVh+";
+
+/// The public part of the mock provider's Ed25519 signing key, encoded in
+/// unpadded URL-safe base64.
+const PUBLIC_KEY: &str = "4nlmdIbsB8ZugbFZJyeQmN_IkUvZhvySHyZaKPa7dgY";
+
+
+
+//		Structs																											
+
+//		Issued																	
+/// An authorisation code issued by the mock provider.
+#[derive(Clone, Debug)]
+struct Issued {
+	/// The algorithm to sign the ID token with.
+	algorithm: Algorithm,
+	
+	/// The PKCE code challenge sent with the authorisation request.
+	challenge: String,
+	
+	/// Claims to add to, or replace in, the ID token.
+	claims:    JsonMap<String, JsonValue>,
+	
+	/// The nonce sent with the authorisation request.
+	nonce:     String,
+}
+
+//		MockProvider															
+/// A local OpenID Connect provider, which issues ID tokens for whoever asks.
+#[derive(Clone, Debug)]
+struct MockProvider {
+	/// The authorisation codes issued, which can each be used once.
+	codes: Arc<Mutex<HashMap<String, Issued>>>,
+	
+	/// The URL of the provider, which is also its issuer.
+	url:   String,
+}
+
+//󰭅		MockProvider															
+impl MockProvider {
+	//		start																
+	/// Starts the provider on a random local port.
+	async fn start() -> Self {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let mock     = Self {
+			codes: Arc::new(Mutex::new(HashMap::new())),
+			url:   format!("http://{}", listener.local_addr().unwrap()),
+		};
+		let router   = Router::new()
+			.route("/.well-known/openid-configuration",       get(discovery))
+			.route("/other/.well-known/openid-configuration", get(discovery))
+			.route("/jwks",                                   get(jwks))
+			.route("/token",                                  post(token))
+			.with_state(mock.clone())
+		;
+		drop(spawn(async move { axum::serve(listener, router).await.unwrap() }));
+		mock
+	}
+	
+	//		authorize															
+	/// Acts on an authorisation URL as the provider would once the user has
+	/// logged in, issuing a code for the ID token claims given.
+	/// 
+	/// # Returns
+	/// 
+	/// The state and authorisation code to send back to the application.
+	/// 
+	fn authorize(&self, url: &str, algorithm: Algorithm, claims: &JsonValue) -> (String, String) {
+		let params = Url::parse(url).unwrap().query_pairs().into_owned().collect::<HashMap<_, _>>();
+		let code   = format!("code-{}", self.codes.lock().len());
+		drop(self.codes.lock().insert(code.clone(), Issued {
+			algorithm,
+			challenge: params.get("code_challenge").cloned().unwrap_or_default(),
+			claims:    claims.as_object().cloned().unwrap_or_default(),
+			nonce:     params.get("nonce").cloned().unwrap_or_default(),
+		}));
+		(params.get("state").cloned().unwrap_or_default(), code)
+	}
+	
+	//		config																
+	/// The configuration for logging in with the provider.
+	fn config(&self) -> OidcConfig {
+		OidcConfig {
+			enabled:       true,
+			issuer:        self.url.clone(),
+			client_id:     s!(CLIENT_ID),
+			client_secret: s!(CLIENT_SECRET),
+			role_map:      HashMap::from([
+				(s!("staff"),  vec![s!("hr")]),
+				(s!("admins"), vec![s!("admin"), s!("hr")]),
+			]),
+			..Default::default()
+		}
+	}
+	
+	//		login																
+	/// Starts a login with the provider, and has it issue a code for the ID
+	/// token claims given.
+	/// 
+	/// # Returns
+	/// 
+	/// The details of the login in progress, and the state and authorisation
+	/// code to send back to the application.
+	/// 
+	async fn login(&self, provider: &OidcProvider, algorithm: Algorithm, claims: &JsonValue) -> (PendingLogin, String, String) {
+		let (url, pending) = provider.authorization_url("/").await.unwrap();
+		let (state, code)  = self.authorize(&url, algorithm, claims);
+		(pending, state, code)
+	}
+}
+
+
+
+//		Functions																										
+
+//		discovery																
+/// Serves the mock provider's configuration.
+async fn discovery(State(mock): State<MockProvider>) -> Json<JsonValue> {
+	Json(json!({
+		"issuer":                 mock.url,
+		"authorization_endpoint": format!("{}/authorize", mock.url),
+		"token_endpoint":         format!("{}/token",     mock.url),
+		"jwks_uri":               format!("{}/jwks",      mock.url),
+	}))
+}
+
+//		jwks																	
+/// Serves the mock provider's signing keys.
+async fn jwks() -> Json<JsonValue> {
+	Json(json!({ "keys": [{
+		"kty": "OKP",
+		"crv": "Ed25519",
+		"x":   PUBLIC_KEY,
+		"kid": KEY_ID,
+		"alg": "EdDSA",
+		"use": "sig",
+	}] }))
+}
+
+//		token																	
+/// Exchanges an authorisation code for an ID token, if the PKCE code verifier
+/// matches the challenge the code was issued for.
+async fn token(State(mock): State<MockProvider>, Form(form): Form<HashMap<String, String>>) -> AxumResponse {
+	let verifier   = form.get("code_verifier").cloned().unwrap_or_default();
+	let challenge  = URL_SAFE_NO_PAD.encode(Sha256::digest(&verifier));
+	let code       = form.get("code").and_then(|code| mock.codes.lock().remove(code));
+	let Some(issued) = code.filter(|issued| issued.challenge == challenge) else {
+		return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid_grant" }))).into_response();
+	};
+	let now        = Utc::now().timestamp();
+	let mut claims = json!({
+		"iss":                mock.url,
+		"aud":                CLIENT_ID,
+		"sub":                "sub-1",
+		"iat":                now,
+		"exp":                now.saturating_add(300),
+		"nonce":              issued.nonce,
+		"preferred_username": "ann",
+		"groups":             ["staff"],
+	}).as_object().cloned().unwrap();
+	claims.extend(issued.claims);
+	let id_token   = if issued.algorithm == Algorithm::EdDSA {
+		let mut header = Header::new(Algorithm::EdDSA);
+		header.kid     = Some(s!(KEY_ID));
+		encode(&header, &claims, &EncodingKey::from_ed_der(&STANDARD.decode(PRIVATE_KEY).unwrap()))
+	} else {
+		encode(&Header::new(issued.algorithm), &claims, &EncodingKey::from_secret(CLIENT_SECRET.as_bytes()))
+	};
+	Json(json!({
+		"access_token": "access-token",
+		"token_type":   "Bearer",
+		"id_token":     id_token.unwrap(),
+	})).into_response()
+}
+
+
+
+//		Tests																											
+
+//		OidcProvider															
+#[cfg(test)]
+mod oidc_provider {
+	use super::*;
+	
+	//		authorization_url													
+	#[tokio::test]
+	async fn authorization_url() {
+		let mock           = MockProvider::start().await;
+		let provider       = OidcProvider::new(&mock.config());
+		let (url, pending) = provider.authorization_url("/guides/").await.unwrap();
+		let params         = Url::parse(&url).unwrap().query_pairs().into_owned().collect::<HashMap<_, _>>();
+		assert!(url.starts_with(&format!("{}/authorize?", mock.url)));
+		assert_eq!(pending.uri, "/guides/");
+		assert_eq!(params.get("response_type").map(String::as_str),         Some("code"));
+		assert_eq!(params.get("client_id").map(String::as_str),             Some(CLIENT_ID));
+		assert_eq!(params.get("scope").map(String::as_str),                 Some("openid profile email"));
+		assert_eq!(params.get("state").map(String::as_str),                 Some(pending.state.as_str()));
+		assert_eq!(params.get("nonce").map(String::as_str),                 Some(pending.nonce.as_str()));
+		assert_eq!(params.get("code_challenge_method").map(String::as_str), Some("S256"));
+		assert_eq!(params.get("code_challenge"),                            Some(&URL_SAFE_NO_PAD.encode(Sha256::digest(&pending.verifier))));
+	}
+	#[tokio::test]
+	async fn authorization_url__unique() {
+		let mock           = MockProvider::start().await;
+		let provider       = OidcProvider::new(&mock.config());
+		let (_, first)     = provider.authorization_url("/").await.unwrap();
+		let (_, second)    = provider.authorization_url("/").await.unwrap();
+		assert_ne!(first.state,    second.state);
+		assert_ne!(first.nonce,    second.nonce);
+		assert_ne!(first.verifier, second.verifier);
+	}
+	#[tokio::test]
+	async fn authorization_url__issuer_mismatch() {
+		let mock     = MockProvider::start().await;
+		let provider = OidcProvider::new(&OidcConfig { issuer: format!("{}/other", mock.url), ..mock.config() });
+		let err      = provider.authorization_url("/").await.unwrap_err();
+		assert!(matches!(err, OidcError::InvalidIssuer(ref issuer) if *issuer == mock.url), "{err}");
+	}
+	
+	//		login																
+	#[tokio::test]
+	async fn login() {
+		let mock                   = MockProvider::start().await;
+		let provider               = OidcProvider::new(&mock.config());
+		let (pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &json!({})).await;
+		let user                   = provider.login(&pending, &state, &code).await.unwrap();
+		assert_eq!(user.id,       "oidc:sub-1");
+		assert_eq!(user.username, "ann");
+		assert_eq!(user.roles,    vec![s!("hr")]);
+	}
+	#[tokio::test]
+	async fn login__role_map() {
+		let mock     = MockProvider::start().await;
+		let provider = OidcProvider::new(&mock.config());
+		for (groups, roles) in [
+			(json!("admins"),                     vec![s!("admin"), s!("hr")]),
+			(json!(["staff", "admins", "other"]), vec![s!("admin"), s!("hr")]),
+			(json!(["other"]),                    vec![]),
+			(json!(null),                         vec![]),
+		] {
+			let (pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &json!({ "groups": groups })).await;
+			let user                   = provider.login(&pending, &state, &code).await.unwrap();
+			assert_eq!(user.roles, roles, "{groups}");
+		}
+	}
+	#[tokio::test]
+	async fn login__state_mismatch() {
+		let mock                = MockProvider::start().await;
+		let provider            = OidcProvider::new(&mock.config());
+		let (pending, _, code)  = mock.login(&provider, Algorithm::EdDSA, &json!({})).await;
+		let err                 = provider.login(&pending, "forged", &code).await.unwrap_err();
+		assert!(matches!(err, OidcError::InvalidState), "{err}");
+	}
+	#[tokio::test]
+	async fn login__nonce_mismatch() {
+		let mock                   = MockProvider::start().await;
+		let provider               = OidcProvider::new(&mock.config());
+		let (pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &json!({ "nonce": "replayed" })).await;
+		let err                    = provider.login(&pending, &state, &code).await.unwrap_err();
+		assert!(matches!(err, OidcError::InvalidNonce), "{err}");
+	}
+	#[tokio::test]
+	async fn login__nonce_missing() {
+		let mock                   = MockProvider::start().await;
+		let provider               = OidcProvider::new(&mock.config());
+		let (pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &json!({ "nonce": null })).await;
+		let err                    = provider.login(&pending, &state, &code).await.unwrap_err();
+		assert!(matches!(err, OidcError::InvalidNonce), "{err}");
+	}
+	#[tokio::test]
+	async fn login__pkce_mismatch() {
+		let mock                       = MockProvider::start().await;
+		let provider                   = OidcProvider::new(&mock.config());
+		let (mut pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &json!({})).await;
+		pending.verifier               = s!("not-the-verifier");
+		let err                        = provider.login(&pending, &state, &code).await.unwrap_err();
+		assert!(matches!(err, OidcError::CouldNotExchangeCode(_)), "{err}");
+	}
+	#[tokio::test]
+	async fn login__symmetric_algorithm() {
+		let mock     = MockProvider::start().await;
+		let provider = OidcProvider::new(&mock.config());
+		for algorithm in [Algorithm::HS256, Algorithm::HS384, Algorithm::HS512] {
+			let (pending, state, code) = mock.login(&provider, algorithm, &json!({})).await;
+			let err                    = provider.login(&pending, &state, &code).await.unwrap_err();
+			assert!(matches!(err, OidcError::UnsupportedAlgorithm(alg) if alg == algorithm), "{err}");
+		}
+	}
+	#[tokio::test]
+	async fn login__invalid_claims() {
+		let mock     = MockProvider::start().await;
+		let provider = OidcProvider::new(&mock.config());
+		for claims in [
+			json!({ "aud": "another-client" }),
+			json!({ "iss": "http://127.0.0.1:1" }),
+			json!({ "exp": Utc::now().timestamp().saturating_sub(3_600) }),
+		] {
+			let (pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &claims).await;
+			let err                    = provider.login(&pending, &state, &code).await.unwrap_err();
+			assert!(matches!(err, OidcError::InvalidToken(_)), "{claims}: {err}");
+		}
+	}
+	#[tokio::test]
+	async fn login__missing_username() {
+		let mock                   = MockProvider::start().await;
+		let provider               = OidcProvider::new(&mock.config());
+		let (pending, state, code) = mock.login(&provider, Algorithm::EdDSA, &json!({ "preferred_username": null })).await;
+		let err                    = provider.login(&pending, &state, &code).await.unwrap_err();
+		assert!(matches!(err, OidcError::MissingClaim(ref claim) if claim == "preferred_username"), "{err}");
+	}
+	
+	//		remember															
+	#[tokio::test]
+	async fn remember() {
+		let mock     = MockProvider::start().await;
+		let provider = OidcProvider::new(&mock.config());
+		let user     = User { id: s!("oidc:sub-1"), username: s!("ann"), roles: vec![s!("hr")] };
+		assert!(provider.user("oidc:sub-1").is_none());
+		provider.remember(&user);
+		assert_eq!(provider.user("oidc:sub-1"), Some(user));
+	}
+}
+
+//		Handlers																
+#[cfg(test)]
+mod handlers {
+	use super::*;
+	
+	//		cookie																
+	/// Gets the session cookie set by a response, if any.
+	fn cookie(response: &AxumResponse) -> Option<String> {
+		response.headers().get(SET_COOKIE).map(|value| {
+			value.to_str().unwrap().split(';').next().unwrap().to_owned()
+		})
+	}
+	
+	//		request																
+	/// Makes a request to the application, with the session cookie if given.
+	async fn request(app: &Router, uri: &str, cookie: Option<&str>) -> AxumResponse {
+		let mut request = Request::get(uri);
+		if let Some(value) = cookie {
+			request = request.header(COOKIE, value);
+		}
+		app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+	}
+	
+	//		state																
+	/// Creates the application state for the tests, with content in `staff`
+	/// restricted to the `staff` role, and content in `admin` restricted to
+	/// the `admin` role.
+	fn state(dir: &Path, mock: &MockProvider, roles: HashMap<String, Vec<String>>) -> Arc<AppState> {
+		let root       = dir.join("content");
+		fs::create_dir_all(root.join("staff")).unwrap();
+		fs::create_dir_all(root.join("admin")).unwrap();
+		fs::write(root.join("staff/page.md"), "# Staff\n\nStaff only.\n").unwrap();
+		fs::write(root.join("admin/page.md"), "# Admin\n\nAdmins only.\n").unwrap();
+		let mut config = Config {
+			logdir: dir.join("log").to_string_lossy().into_owned(),
+			access: HashMap::from([
+				(s!("staff/**"), vec![s!("staff")]),
+				(s!("admin/**"), vec![s!("admin")]),
+			]),
+			oidc:   OidcConfig { role_map: HashMap::new(), ..mock.config() },
+			roles,
+			..Default::default()
+		};
+		config.audit.enabled                      = false;
+		config.markdown.behavior                  = LoadingBehavior::Override;
+		config.markdown.local_path                = root.clone();
+		config.assets.protected_assets.behavior   = LoadingBehavior::Override;
+		config.assets.protected_assets.local_path = root;
+		Arc::new(AppState::new(config))
+	}
+	
+	//		get_oidc_callback													
+	#[tokio::test]
+	async fn get_oidc_callback__configured_roles() {
+		let mock    = MockProvider::start().await;
+		let dir     = TempDir::new().unwrap();
+		let app     = app(&state(dir.path(), &mock, HashMap::from([
+			(s!("admin"),      vec![s!("admin")]),
+			(s!("oidc:sub-1"), vec![s!("staff")]),
+		])));
+		//	The username matches a local user, whose roles must not be given
+		let start   = request(&app, "/auth/oidc/login?uri=/staff/page.md", None).await;
+		assert_eq!(start.status(), StatusCode::SEE_OTHER);
+		let session = cookie(&start).unwrap();
+		let (state, code) = mock.authorize(
+			start.headers().get(LOCATION).unwrap().to_str().unwrap(),
+			Algorithm::EdDSA,
+			&json!({ "preferred_username": "admin" }),
+		);
+		let back    = request(&app, &format!("/auth/oidc/callback?state={state}&code={code}"), Some(&session)).await;
+		assert_eq!(back.status(), StatusCode::SEE_OTHER);
+		assert_eq!(back.headers().get(LOCATION).unwrap(), "/staff/page.md");
+		let user    = cookie(&back).unwrap_or(session);
+		assert_eq!(request(&app, "/staff/page.md", Some(&user)).await.status(), StatusCode::OK);
+		assert_ne!(request(&app, "/admin/page.md", Some(&user)).await.status(), StatusCode::OK);
+	}
+	#[tokio::test]
+	async fn get_oidc_callback__state_mismatch() {
+		let mock    = MockProvider::start().await;
+		let dir     = TempDir::new().unwrap();
+		let app     = app(&state(dir.path(), &mock, HashMap::from([(s!("oidc:sub-1"), vec![s!("staff")])])));
+		let start   = request(&app, "/auth/oidc/login?uri=/staff/page.md", None).await;
+		let session = cookie(&start).unwrap();
+		let (_, code) = mock.authorize(
+			start.headers().get(LOCATION).unwrap().to_str().unwrap(),
+			Algorithm::EdDSA,
+			&json!({}),
+		);
+		let back    = request(&app, &format!("/auth/oidc/callback?state=forged&code={code}"), Some(&session)).await;
+		assert_ne!(back.status(), StatusCode::SEE_OTHER);
+		let user    = cookie(&back).unwrap_or(session);
+		assert_ne!(request(&app, "/staff/page.md", Some(&user)).await.status(), StatusCode::OK);
+	}
+}
@@ -0,0 +1,175 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::config::Config;
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc};
+use tempfile::TempDir;
+
+
+
+//		Constants																										
+
+/// The contents of the public image.
+const LOGO: &[u8] = b"logo";
+
+/// The contents of the restricted image.
+const CHART: &[u8] = b"chart";
+
+
+
+//		Functions																										
+
+//		content																	
+/// Creates local content for the tests, with public pages, one of which is
+/// restricted by its front matter, and a section restricted to the `hr` role.
+/// Each section has an image, and there is one outside of the content root.
+fn content() -> TempDir {
+	let dir  = TempDir::new().unwrap();
+	let root = dir.path().join("content");
+	fs::create_dir_all(root.join("public")).unwrap();
+	fs::create_dir_all(root.join("hr")).unwrap();
+	fs::write(root.join("public/welcome.md"), "# Welcome\n\n![Logo](logo.png) ![Chart](../hr/chart.png)\n").unwrap();
+	fs::write(root.join("public/draft.md"),   "---\naccess: [editor]\n---\n# Draft\n\nUnfinished draft.\n").unwrap();
+	fs::write(root.join("public/logo.png"),   LOGO).unwrap();
+	fs::write(root.join("hr/pay.md"),         "# Pay\n\nTop secret pay details.\n").unwrap();
+	fs::write(root.join("hr/chart.png"),      CHART).unwrap();
+	fs::write(dir.path().join("outside.png"), LOGO).unwrap();
+	dir
+}
+
+//		data_uri																
+/// Creates the data URI that an image is inlined as.
+fn data_uri(bytes: &[u8]) -> String {
+	format!("data:image/png;base64,{}", STANDARD.encode(bytes))
+}
+
+//		state																	
+/// Creates the application state for the tests, serving the local content
+/// given, with `hr` restricted to the `hr` role.
+fn state(dir: &Path) -> Arc<AppState> {
+	let mut config = Config {
+		logdir: dir.join("log").to_string_lossy().into_owned(),
+		access: HashMap::from([(s!("hr/**"), vec![s!("hr")])]),
+		..Default::default()
+	};
+	config.audit.enabled                      = false;
+	config.markdown.behavior                  = LoadingBehavior::Override;
+	config.markdown.local_path                = dir.join("content");
+	config.assets.protected_assets.behavior   = LoadingBehavior::Override;
+	config.assets.protected_assets.local_path = dir.join("content");
+	Arc::new(AppState::new(config))
+}
+
+
+
+//		Tests																											
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		print_document														
+	#[tokio::test]
+	async fn print_document__page() {
+		let dir  = content();
+		let html = print_document(&state(dir.path()), "public/welcome.md", &[]).await.unwrap();
+		assert!(html.contains("Welcome"));
+		assert!(html.contains(&data_uri(LOGO)));
+		assert!(!html.contains(&data_uri(CHART)));
+		assert!(html.contains(r#"src="../hr/chart.png""#));
+	}
+	#[tokio::test]
+	async fn print_document__escaped() {
+		let dir  = content();
+		fs::write(dir.path().join("content/public/tips.md"), "---\ntitle: Tips & <tricks>\n---\n# Tips\n").unwrap();
+		let html = print_document(&state(dir.path()), "public/tips.md", &[]).await.unwrap();
+		assert!(html.contains("<title>Tips &amp; &lt;tricks&gt; - "));
+		assert!(!html.contains("<tricks>"));
+	}
+	#[tokio::test]
+	async fn print_document__page_with_roles() {
+		let dir  = content();
+		let html = print_document(&state(dir.path()), "public/welcome.md", &[s!("hr")]).await.unwrap();
+		assert!(html.contains(&data_uri(CHART)));
+	}
+	#[tokio::test]
+	async fn print_document__directory() {
+		let dir   = content();
+		let state = state(dir.path());
+		let html  = print_document(&state, "public", &[]).await.unwrap();
+		assert!(html.contains("Welcome"));
+		assert!(!html.contains("Unfinished draft."));
+		let draft = print_document(&state, "public", &[s!("editor")]).await.unwrap();
+		assert!(draft.contains("Unfinished draft."));
+	}
+	#[tokio::test]
+	async fn print_document__forbidden() {
+		let dir   = content();
+		let state = state(dir.path());
+		assert!(matches!(print_document(&state, "hr",              &[]).await, Err(PrintError::NotFound(_))));
+		assert!(matches!(print_document(&state, "hr/pay.md",       &[]).await, Err(PrintError::NotFound(_))));
+		assert!(matches!(print_document(&state, "public/draft.md", &[]).await, Err(PrintError::NotFound(_))));
+		assert!(print_document(&state, "hr/pay.md", &[s!("hr")]).await.is_ok());
+	}
+	#[tokio::test]
+	async fn print_document__missing() {
+		let dir = content();
+		assert!(matches!(print_document(&state(dir.path()), "public/missing.md", &[]).await, Err(PrintError::NotFound(_))));
+	}
+	
+	//		prepare_html														
+	#[test]
+	fn prepare_html__images() {
+		let dir   = content();
+		let state = state(dir.path());
+		let html  = prepare_html(&state, "public/welcome.md", r#"<img src="logo.png"><img src="/public/logo.png?v=1">"#, "", &[]);
+		assert_eq!(html, format!(r#"<img src="{0}"><img src="{0}">"#, data_uri(LOGO)));
+	}
+	#[test]
+	fn prepare_html__traversal() {
+		let dir   = content();
+		let state = state(dir.path());
+		for src in [
+			"../../outside.png",
+			"%2e%2e/%2e%2e/outside.png",
+			"..%2f..%2foutside.png",
+			"%2e/..%2fhr/chart.png",
+			"/public/%2e%2e/hr/chart.png",
+		] {
+			let html = format!(r#"<img src="{src}">"#);
+			assert_eq!(prepare_html(&state, "public/welcome.md", &html, "", &[]), html, "{src}");
+		}
+	}
+	#[test]
+	fn prepare_html__expands_and_prefixes() {
+		let dir   = content();
+		let html  = prepare_html(&state(dir.path()), "public/welcome.md", r##"<details id="more"><summary>More</summary></details><a href="#more">More</a>"##, "page-1-", &[]);
+		assert_eq!(html, r##"<details id="page-1-more" open=""><summary>More</summary></details><a href="#page-1-more">More</a>"##);
+	}
+	
+	//		toc_tree															
+	#[test]
+	fn toc_tree() {
+		let heading = |level: u8, id: &str| -> Heading {
+			serde_json::from_value(json!({ "level": level, "id": id, "text": id.to_uppercase() })).unwrap()
+		};
+		let item    = |id: &str, children| TocItem { id: format!("p-{id}"), text: id.to_uppercase(), children };
+		let toc     = super::toc_tree(&[
+			heading(1, "title"),
+			heading(2, "a"),
+			heading(3, "a1"),
+			heading(4, "a1x"),
+			heading(3, "a2"),
+			heading(2, "b"),
+			heading(4, "b1x"),
+		], "p-");
+		assert_eq!(toc, vec![
+			item("a", vec![item("a1", vec![item("a1x", vec![])]), item("a2", vec![])]),
+			item("b", vec![item("b1x", vec![])]),
+		]);
+	}
+}
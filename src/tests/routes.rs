@@ -0,0 +1,411 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::{
+	config::{TokenConfig, TokenScope},
+	tokens::hash_token,
+};
+use axum::{
+	body::{Body, to_bytes},
+	http::{
+		Request,
+		StatusCode,
+		header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, VARY},
+	},
+};
+use chrono::{DateTime, Duration, Utc};
+use rubedo::sugar::s;
+use serde_json::Value as JsonValue;
+use std::{collections::HashMap, fs, path::Path};
+use tempfile::TempDir;
+use terracotta::app::config::LoadingBehavior;
+use tower::ServiceExt as _;
+
+
+
+//		Functions																										
+
+//		content																	
+/// Creates local content for the tests, with a public section, a restricted
+/// section, and a page outside of the content root.
+fn content() -> TempDir {
+	let dir  = TempDir::new().unwrap();
+	let root = dir.path().join("content");
+	fs::create_dir_all(root.join("public")).unwrap();
+	fs::create_dir_all(root.join("hr")).unwrap();
+	fs::write(root.join("public/welcome.md"), "# Welcome\n\nHello, world.\n").unwrap();
+	fs::write(root.join("public/notes.txt"),  "Public notes\n").unwrap();
+	fs::write(root.join("hr/secret.md"),      "# Secret\n\nTop secret pay details.\n").unwrap();
+	fs::write(root.join("hr/secret.txt"),     "Top secret file\n").unwrap();
+	fs::write(dir.path().join("outside.md"),  "# Outside\n\nOutside the content root.\n").unwrap();
+	dir
+}
+
+//		get																		
+/// Makes a request to the application, and returns the status and body.
+async fn get(app: Router, uri: &str) -> (StatusCode, String) {
+	request(app, Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+//		get_with_accept															
+/// Makes a request to the application with an `Accept` header, and returns the
+/// content type, the `Vary` header, and the body.
+async fn get_with_accept(app: Router, uri: &str, accept: &str) -> (String, String, String) {
+	let response     = app.oneshot(Request::get(uri).header(ACCEPT, accept).body(Body::empty()).unwrap()).await.unwrap();
+	let headers      = response.headers();
+	let content_type = headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()).unwrap_or_default().to_owned();
+	let vary         = headers.get(VARY).and_then(|value| value.to_str().ok()).unwrap_or_default().to_owned();
+	let body         = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	(content_type, vary, String::from_utf8_lossy(&body).into_owned())
+}
+
+//		get_with_token															
+/// Makes a request to the application using an API token, and returns the
+/// status and body.
+async fn get_with_token(app: Router, uri: &str, token: &str) -> (StatusCode, String) {
+	request(app, Request::get(uri).header(AUTHORIZATION, format!("Bearer {token}")).body(Body::empty()).unwrap()).await
+}
+
+//		request																	
+/// Makes a request to the application, and returns the status and body.
+async fn request(app: Router, request: Request<Body>) -> (StatusCode, String) {
+	let response = app.oneshot(request).await.unwrap();
+	let status   = response.status();
+	let body     = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	(status, String::from_utf8_lossy(&body).into_owned())
+}
+
+//		search																	
+/// Searches the content using the content API token, and returns the paths of
+/// the pages found.
+async fn search(app: Router, query: &str) -> Vec<String> {
+	let (_, body) = request(app, Request::get(format!("/search?q={query}"))
+		.header(ACCEPT,        "application/json")
+		.header(AUTHORIZATION, "Bearer content")
+		.body(Body::empty())
+		.unwrap()
+	).await;
+	let response  = serde_json::from_str::<JsonValue>(&body).unwrap();
+	response.get("results").and_then(JsonValue::as_array).unwrap()
+		.iter()
+		.map(|result| result.get("path").and_then(JsonValue::as_str).unwrap().to_owned())
+		.collect()
+}
+
+//		state																	
+/// Creates the application state for the tests, serving the local content
+/// given, with `public` as a public section and `hr` restricted to the `hr`
+/// role. There are API tokens for content, for statistics, and one that has
+/// expired, which are their names.
+fn state(dir: &Path) -> Arc<AppState> {
+	state_with_public(dir, &["public"])
+}
+
+//		state_with_public														
+/// Creates the application state for the tests in the same way as [`state()`],
+/// but with the public path prefixes given.
+fn state_with_public(dir: &Path, public: &[&str]) -> Arc<AppState> {
+	let token      = |name: &str, scope: TokenScope, expires: Option<DateTime<Utc>>| (s!(name), TokenConfig {
+		hash:   hash_token(name),
+		scopes: vec![scope],
+		expires,
+		..Default::default()
+	});
+	let mut config = Config {
+		logdir: dir.join("log").to_string_lossy().into_owned(),
+		access: HashMap::from([(s!("hr/**"), vec![s!("hr")])]),
+		public: public.iter().map(|&prefix| prefix.to_owned()).collect(),
+		tokens: HashMap::from([
+			token("content", TokenScope::Content, None),
+			token("stats",   TokenScope::Stats,   None),
+			token("expired", TokenScope::Stats,   Some(Utc::now().checked_sub_signed(Duration::hours(1)).unwrap())),
+		]),
+		..Default::default()
+	};
+	config.audit.enabled                      = false;
+	config.markdown.behavior                  = LoadingBehavior::Override;
+	config.markdown.local_path                = dir.join("content");
+	config.assets.protected_assets.behavior   = LoadingBehavior::Override;
+	config.assets.protected_assets.local_path = dir.join("content");
+	Arc::new(AppState::new(config))
+}
+
+
+
+//		Tests																											
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		app																	
+	#[tokio::test]
+	async fn app__public_content() {
+		let dir                = content();
+		let app                = app(&state(dir.path()));
+		let (page_status, page) = get(app.clone(), "/public/welcome.md").await;
+		assert_eq!(page_status, StatusCode::OK);
+		assert!(page.contains("Hello, world."));
+		let (file_status, file) = get(app, "/public/notes.txt").await;
+		assert_eq!(file_status, StatusCode::OK);
+		assert_eq!(file, "Public notes\n");
+	}
+	#[tokio::test]
+	async fn app__public_files() {
+		//	A single file can be made public, and a public file is still subject to
+		//	access control
+		let dir = content();
+		let app = app(&state_with_public(dir.path(), &["/public/welcome.md/", "hr/secret.md"]));
+		let (page_status, page)     = get(app.clone(), "/public/welcome.md").await;
+		assert_eq!(page_status, StatusCode::OK);
+		assert!(page.contains("Hello, world."));
+		assert_ne!(get(app.clone(), "/public/notes.txt").await.0, StatusCode::OK);
+		let (secret_status, secret) = get(app, "/hr/secret.md").await;
+		assert_eq!(secret_status, StatusCode::FORBIDDEN);
+		assert!(!secret.contains("Top secret"));
+	}
+	#[tokio::test]
+	async fn app__public_index() {
+		let dir = content();
+		fs::write(dir.path().join("content/index.md"), "# Home\n\nThe home page.\n").unwrap();
+		let (status, body) = get(app(&state_with_public(dir.path(), &["index.md"])), "/").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("The home page."));
+		assert_ne!(get(app(&state(dir.path())), "/").await.0, StatusCode::OK);
+		assert_ne!(get(app(&state_with_public(dir.path(), &["index.md"])), "/public/welcome.md").await.0, StatusCode::OK);
+	}
+	#[tokio::test]
+	async fn app__restricted_content() {
+		let dir            = content();
+		let app            = app(&state(dir.path()));
+		let (status, body) = get(app, "/hr/secret.md").await;
+		assert_ne!(status, StatusCode::OK);
+		assert!(!body.contains("Top secret"));
+	}
+	#[tokio::test]
+	async fn app__invalid_front_matter() {
+		//	The access list cannot be trusted if the rest of the front matter is
+		//	wrong, so the page is not served to anyone
+		let dir            = content();
+		fs::write(dir.path().join("content/public/broken.md"), "---\naccess: hr\ntitle: 2024\n---\nTop secret draft.\n").unwrap();
+		let app            = app(&state(dir.path()));
+		let (status, body) = get(app.clone(), "/public/broken.md").await;
+		assert_eq!(status, StatusCode::FORBIDDEN);
+		assert!(!body.contains("Top secret"));
+		assert!(!search(app, "secret").await.contains(&s!("public/broken.md")));
+	}
+	#[tokio::test]
+	async fn app__escaped_titles() {
+		let dir            = content();
+		fs::write(dir.path().join("content/public/tips.md"), "---\ntitle: Tips & <tricks>\n---\n## A <b>bold</b> & brave heading\n").unwrap();
+		let (status, body) = get(app(&state(dir.path())), "/public/tips.md").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("<title>Tips &amp; &lt;tricks&gt;"));
+		assert!(!body.contains("<tricks>"));
+		assert!(!body.contains("<span>A <b>"));
+	}
+	#[tokio::test]
+	async fn app__local_changes() {
+		//	Changes are picked up when live reloading reports them
+		let dir   = content();
+		let state = state(dir.path());
+		let app   = app(&state);
+		let root  = dir.path().join("content");
+		assert!(search(app.clone(), "hello").await.contains(&s!("public/welcome.md")));
+		fs::write(root.join("public/added.md"),   "# Added\n\nFreshly added.\n").unwrap();
+		fs::write(root.join("public/welcome.md"), "# Welcome\n\nGoodbye, world. Farewell.\n").unwrap();
+		state.refresh_local_page("public/added.md");
+		state.refresh_local_page("public/welcome.md");
+		assert!(search(app.clone(), "freshly").await.contains(&s!("public/added.md")));
+		assert!(get(app.clone(), "/public/welcome.md").await.1.contains(">Added</a>"));
+		assert!(get(app.clone(), "/public/adde.md").await.1.contains(">Added</a>"));
+		assert!(search(app.clone(), "farewell").await.contains(&s!("public/welcome.md")));
+		assert!(!search(app.clone(), "hello").await.contains(&s!("public/welcome.md")));
+		fs::remove_file(root.join("public/added.md")).unwrap();
+		state.refresh_local_page("public/added.md");
+		assert!(!search(app.clone(), "freshly").await.contains(&s!("public/added.md")));
+		assert!(!get(app.clone(), "/public/welcome.md").await.1.contains(">Added</a>"));
+		assert!(!get(app.clone(), "/public/adde.md").await.1.contains(">Added</a>"));
+		assert_eq!(get(app, "/public/added.md").await.0, StatusCode::NOT_FOUND);
+	}
+	#[tokio::test]
+	async fn app__local_deleted() {
+		//	Deleted pages are also noticed when they are next requested
+		let dir   = content();
+		let app   = app(&state(dir.path()));
+		fs::remove_file(dir.path().join("content/public/welcome.md")).unwrap();
+		assert_eq!(get(app.clone(), "/public/welcome.md").await.0, StatusCode::NOT_FOUND);
+		assert!(!search(app, "hello").await.contains(&s!("public/welcome.md")));
+	}
+	#[tokio::test]
+	async fn app__page_formats() {
+		let dir = content();
+		let app = app(&state(dir.path()));
+		let (html_type, html_vary, html) = get_with_accept(app.clone(), "/public/welcome.md", "text/html,*/*;q=0.8").await;
+		assert!(html_type.starts_with("text/html"));
+		assert_eq!(html_vary, "Accept");
+		assert!(html.contains("Hello, world."));
+		let (markdown_type, markdown_vary, markdown) = get_with_accept(app.clone(), "/public/welcome.md", "text/markdown").await;
+		assert!(markdown_type.starts_with("text/markdown"));
+		assert_eq!(markdown_vary, "Accept");
+		assert_eq!(markdown, "# Welcome\n\nHello, world.\n");
+		let (json_type, json_vary, json) = get_with_accept(app.clone(), "/public/welcome.md", "*/*, application/json").await;
+		assert!(json_type.starts_with("application/json"));
+		assert_eq!(json_vary, "Accept");
+		assert!(json.contains("Welcome"));
+		let (refused_type, _, refused) = get_with_accept(app, "/public/welcome.md", "text/markdown;q=0, */*").await;
+		assert!(refused_type.starts_with("text/html"));
+		assert_ne!(refused, "# Welcome\n\nHello, world.\n");
+	}
+	#[tokio::test]
+	async fn app__search_formats() {
+		let dir    = content();
+		let app    = app(&state(dir.path()));
+		let format = async |accept: &str| {
+			let response = app.clone().oneshot(Request::get("/search?q=hello")
+				.header(ACCEPT,        accept)
+				.header(AUTHORIZATION, "Bearer content")
+				.body(Body::empty())
+				.unwrap()
+			).await.unwrap();
+			let headers  = response.headers();
+			assert_eq!(headers.get(VARY).and_then(|value| value.to_str().ok()), Some("Accept"));
+			headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()).unwrap_or_default().to_owned()
+		};
+		assert!(format("application/json").await.starts_with("application/json"));
+		assert!(format("text/html;q=0.1, application/json").await.starts_with("application/json"));
+		assert!(format("application/json;q=0.5, text/html").await.starts_with("text/html"));
+		assert!(format("application/json;q=0, */*").await.starts_with("text/html"));
+		assert!(format("").await.starts_with("text/html"));
+	}
+	#[tokio::test]
+	async fn app__traversal_into_restricted_content() {
+		let dir = content();
+		let app = app(&state(dir.path()));
+		for uri in [
+			"/public/../hr/secret.md",
+			"/public/../hr/secret.txt",
+			"/public/%2e%2e/hr/secret.md",
+			"/public/.%2E/hr/secret.md",
+			"/public/./../hr/secret.md",
+			"/public//../hr/secret.md",
+		] {
+			let (status, body) = get(app.clone(), uri).await;
+			assert_eq!(status, StatusCode::NOT_FOUND, "{uri}");
+			assert!(!body.contains("Top secret"), "{uri}");
+		}
+	}
+	#[tokio::test]
+	async fn app__traversal_outside_content() {
+		let dir = content();
+		let app = app(&state(dir.path()));
+		for uri in [
+			"/public/../../outside.md",
+			"/public/%2e%2e/%2e%2e/outside.md",
+		] {
+			let (status, body) = get(app.clone(), uri).await;
+			assert_eq!(status, StatusCode::NOT_FOUND, "{uri}");
+			assert!(!body.contains("Outside the content root"), "{uri}");
+		}
+	}
+	
+	#[tokio::test]
+	async fn app__public_stats() {
+		let dir = content();
+		let app = app(&state(dir.path()));
+		let (status, body) = get(app.clone(), "/api/stats").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("\"cache\""));
+		assert!(!body.contains("\"tokens\""));
+		assert_eq!(get(app, "/api/stats/history").await.0, StatusCode::OK);
+	}
+	#[tokio::test]
+	async fn app__public_with_other_token() {
+		//	A token without the scope for a public route is ignored, rather than
+		//	rejected, as the route can be used without one
+		let dir = content();
+		let app = app(&state(dir.path()));
+		assert_eq!(get_with_token(app.clone(), "/api/stats",         "content").await.0, StatusCode::OK);
+		assert_eq!(get_with_token(app.clone(), "/api/stats/history", "content").await.0, StatusCode::OK);
+		let (status, body) = get_with_token(app.clone(), "/public/welcome.md", "stats").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("Hello, world."));
+		assert_eq!(get_with_token(app.clone(), "/hr/secret.md",      "stats").await.0, StatusCode::FORBIDDEN);
+		assert_eq!(get_with_token(app,         "/api/stats",         "expired").await.0, StatusCode::UNAUTHORIZED);
+	}
+	#[tokio::test]
+	async fn app__token_stats() {
+		let dir = content();
+		let app = app(&state(dir.path()));
+		assert_ne!(get(app.clone(), "/api/stats/tokens").await.0, StatusCode::OK);
+		let (status, body) = get_with_token(app.clone(), "/api/stats/tokens", "stats").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("\"content\""));
+		assert!(body.contains("\"stats\":{\"requests\":1,"));
+		assert_eq!(get_with_token(app.clone(), "/api/stats/tokens", "content").await.0, StatusCode::FORBIDDEN);
+		assert_eq!(get_with_token(app.clone(), "/api/stats/tokens", "expired").await.0, StatusCode::UNAUTHORIZED);
+		assert_eq!(get_with_token(app,         "/api/stats/tokens", "unknown").await.0, StatusCode::UNAUTHORIZED);
+	}
+	
+	//		check_public														
+	#[test]
+	fn check_public() {
+		let config = |public: &[&str]| Config { public: public.iter().map(|&prefix| prefix.to_owned()).collect(), ..Default::default() };
+		assert!(super::check_public(&config(&["public", "index.md", "api", "css/extra", "/admin/"])).is_ok());
+		for (prefix, route) in [
+			("search",       "/search"),
+			("/api/stats/",  "/api/stats"),
+			("login",        "/login"),
+			("css",          "/css/{*path}"),
+			("admin/audit",  "/admin/audit"),
+		] {
+			let err = super::check_public(&config(&["public", prefix])).unwrap_err();
+			assert!(matches!(err, RouteError::ReservedPublicPrefix(ref found, ref path) if found == prefix.trim_matches('/') && path == route), "{prefix}");
+		}
+	}
+	#[test]
+	fn check_public__routable() {
+		//	Every prefix that passes the check can be routed without a clash
+		let dir   = content();
+		let state = state_with_public(dir.path(), &["public", "index.md", "api", "css/extra", "admin", "auth/oidc"]);
+		assert!(super::check_public(&state.config).is_ok());
+		drop(app(&state));
+	}
+	
+	//		is_public															
+	#[test]
+	fn is_public() {
+		let dir   = content();
+		let state = state(dir.path());
+		assert!(super::is_public(&state, "/api/stats"));
+		assert!(super::is_public(&state, "/css/{*path}"));
+		assert!(super::is_public(&state, "/public"));
+		assert!(super::is_public(&state, "/public/{*path}"));
+		assert!(!super::is_public(&state, "/"));
+		assert!(!super::is_public(&state, "/search"));
+		assert!(!super::is_public(&state, "/{*path}"));
+	}
+	
+	//		public_content														
+	#[test]
+	fn public_content() {
+		let dir    = content();
+		let routes = |public| super::public_content(&state_with_public(dir.path(), public)).into_iter().map(|route| route.0).collect::<Vec<_>>();
+		assert_eq!(routes(&["public"]),             vec!["/public", "/public/{*path}"]);
+		assert_eq!(routes(&["index.md", "a/b.md"]), vec!["/", "/a/b.md", "/a/b.md/{*path}", "/index.md", "/index.md/{*path}"]);
+		assert!(routes(&[]).is_empty());
+	}
+	
+	//		public_prefixes														
+	#[test]
+	fn public_prefixes() {
+		let config = Config {
+			public: vec![s!("/docs/"), s!("docs"), s!("/"), s!(""), s!("guide/setup.md")],
+			..Default::default()
+		};
+		assert_eq!(super::public_prefixes(&config).into_iter().collect::<Vec<_>>(), vec!["docs", "guide/setup.md"]);
+	}
+}
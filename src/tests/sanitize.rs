@@ -0,0 +1,127 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use crate::{MarkdownOptions, parse_with_options, pipeline::Pipeline};
+
+
+
+//		Constants																										
+
+/// The SVG namespace declaration, which sanitisation removes, as it is implied
+/// for `<svg>` elements in HTML.
+const SVG_XMLNS: &str = r#" xmlns="http://www.w3.org/2000/svg""#;
+
+
+
+//		Functions																										
+
+//		render																	
+/// Renders some Markdown with the built-in processors and maths enabled, and
+/// returns the HTML without and with sanitisation.
+fn render(markdown: &str) -> (String, String) {
+	let html = |sanitize| {
+		let options = MarkdownOptions { math: true, sanitize, ..Default::default() };
+		parse_with_options(markdown, false, &options, &Pipeline::default()).html
+	};
+	(html(false), html(true))
+}
+
+
+
+//		Tests																											
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		sanitize_html														
+	#[test]
+	fn sanitize_html__unsafe() {
+		assert_eq!(
+			sanitize_html(r#"<p onclick="alert(1)">Text<script>alert(1)</script><a href="javascript:alert(1)">link</a></p>"#),
+			"<p>Text<a>link</a></p>",
+		);
+	}
+	#[test]
+	fn sanitize_html__callouts() {
+		let (raw, clean) = render("> **Note**\n> Take care.\n");
+		assert_eq!(clean, raw);
+		assert!(clean.contains(r#"<blockquote class="callout note">"#));
+		assert!(clean.contains(r#"<details open="" class="callout-collapse"><summary>"#));
+	}
+	#[test]
+	fn sanitize_html__details() {
+		let (raw, clean) = render("> -> More information\n>\n> Hidden until opened.\n");
+		assert_eq!(clean, raw);
+		assert!(clean.contains("<details"));
+		assert!(clean.contains("<summary>More information</summary>"));
+	}
+	#[test]
+	fn sanitize_html__headings() {
+		let (raw, clean) = render("## Section\n\nText\n");
+		assert_eq!(clean, raw);
+		assert!(clean.contains(r#"<details open="" class="heading-collapse h2"><summary><h2>"#));
+		assert!(clean.contains(r##"<a inert="" href="#section" aria-hidden="true" class="anchor" id="section">"##));
+	}
+	#[test]
+	fn sanitize_html__diagrams() {
+		let (raw, clean) = render("```dot\ndigraph { a -> b }\n```\n\n```mermaid\ngraph TD; A-->B\n```\n");
+		assert_eq!(clean, raw.replace(SVG_XMLNS, ""));
+		assert!(clean.contains(r#"<figure class="diagram diagram-dot"><svg width="86" height="180" viewBox="0 0 86 180">"#));
+		assert!(clean.contains(r#"<figure class="diagram diagram-mermaid"><svg width="#));
+		assert!(clean.contains(r#"markerWidth="10" markerHeight="7" refX="0" refY="3.5""#));
+		assert!(clean.contains(r##"<textPath href="#diagram-1-arrow0" startOffset="50%""##));
+		assert!(clean.contains(r#"font-size="14px" font-family="Times, serif""#));
+		assert!(clean.contains(r#"data-edge-id="edge-0""#));
+	}
+	#[test]
+	fn sanitize_html__math() {
+		let (raw, clean) = render("Inline $x^2 + \\frac{a}{b}$ maths.\n\n$$\\sqrt{x} = \\sum_{i=0}^n i$$\n");
+		assert_eq!(clean, raw);
+		assert!(clean.contains("<math><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mfrac><mi>a</mi><mi>b</mi></mfrac></math>"));
+		assert!(clean.contains(r#"<math display="block"><msqrt><mi>x</mi></msqrt>"#));
+		assert!(clean.contains(r#"<mo lspace="0" rspace="0">=</mo>"#));
+	}
+	#[test]
+	fn sanitize_html__svg_namespace() {
+		//	Names are only case-adjusted, and so allowed, inside SVG
+		assert_eq!(
+			sanitize_html(r#"<svg VIEWBOX="0 0 1 1"><TEXTPATH STARTOFFSET="2">A</TEXTPATH></svg>"#),
+			r#"<svg viewBox="0 0 1 1"><textPath startOffset="2">A</textPath></svg>"#,
+		);
+		assert_eq!(sanitize_html(r#"<p><textPath startOffset="1">A</textPath></p>"#), "<p>A</p>");
+	}
+	#[test]
+	fn sanitize_html__svg_unsafe() {
+		assert_eq!(
+			sanitize_html(r#"<svg><rect onload="alert(1)"/><script>alert(1)</script><foreignObject><img src="x" onerror="alert(1)"></foreignObject><a href="javascript:alert(1)">A</a></svg>"#),
+			"<svg><rect></rect><a>A</a></svg>",
+		);
+	}
+	#[test]
+	fn sanitize_html__math_namespace() {
+		//	MathML elements are only allowed inside MathML
+		assert!(!sanitize_html("<p><mi>x</mi></p>").contains("<mi>"));
+		assert_eq!(
+			sanitize_html(r#"<math><mi mathvariant="bold" onclick="alert(1)">x</mi><script>alert(1)</script><annotation-xml encoding="text/html"><img src="x" onerror="alert(1)"></annotation-xml></math>"#),
+			r#"<math><mi mathvariant="bold">x</mi></math>"#,
+		);
+	}
+	#[test]
+	fn sanitize_html__namespace_confusion() {
+		//	Markup that is parsed differently once serialised and parsed again
+		for html in [
+			r#"<svg></p><style><a id="</style><img src=1 onerror=alert(1)>">"#,
+			"<math><mtext><table><mglyph><style><img src=x onerror=alert(1)>",
+			"<math><mi><svg><style><img src=x onerror=alert(1)></style></svg></mi></math>",
+		] {
+			let clean = sanitize_html(html);
+			assert!(!clean.contains("onerror"), "{html}");
+			assert!(!clean.contains("<style"),  "{html}");
+			assert_eq!(sanitize_html(&clean), clean, "{html}");
+		}
+	}
+}
@@ -0,0 +1,208 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+use crate::parse;
+use core::iter;
+use include_dir::include_dir;
+use rubedo::sugar::s;
+use serde_json::{Value as JsonValue, json};
+use std::sync::Arc;
+use tera::Context;
+use terracotta::app::init::setup_tera;
+
+
+
+//		Functions																										
+
+//		index																	
+/// Creates a search index from some pages, given as paths and Markdown.
+fn index(pages: &[(&str, &str)]) -> SearchIndex {
+	let mut index = SearchIndex::new();
+	for &(path, markdown) in pages {
+		let page = parse(markdown, false);
+		index.add(path, &page.title, &page.toc, &page.html);
+	}
+	index
+}
+
+//		render_search															
+/// Renders the search page template for a query and some results, in the same
+/// way as the `/search` endpoint.
+fn render_search(query: &str, results: &[SearchResult]) -> String {
+	let tera     = setup_tera(&Arc::new(include_dir!("html"))).unwrap();
+	let listed   = results.iter().map(|result| {
+		let mut value = serde_json::to_value(result).unwrap();
+		drop(value.as_object_mut().unwrap().insert(s!("url"), json!(format!("/{}", result.path))));
+		value
+	}).collect::<Vec<JsonValue>>();
+	let mut context = Context::new();
+	context.insert("Title",   "Search");
+	context.insert("ToC",     &Vec::<Heading>::new());
+	context.insert("Nav",     &Vec::<JsonValue>::new());
+	context.insert("Path",    "");
+	context.insert("Query",   query);
+	context.insert("Results", &listed);
+	tera.render("search", &context).unwrap()
+}
+
+
+
+//		Tests																											
+
+//		SearchIndex																
+#[cfg(test)]
+mod search_index {
+	use super::*;
+	
+	//		add																	
+	#[test]
+	fn add() {
+		let index = index(&[("a.md", "# Apples\n\nCrisp."), ("b.md", "# Pears\n\nSoft.")]);
+		assert_eq!(index.len(), 2);
+		assert!(!index.is_empty());
+		assert!(index.contains("a.md"));
+		assert!(!index.contains("c.md"));
+		assert!(SearchIndex::new().is_empty());
+	}
+	#[test]
+	fn add__replaces() {
+		let mut index = index(&[("a.md", "# Apples\n\nCrisp."), ("b.md", "# Pears\n\nSoft.")]);
+		let page      = parse("# Apples\n\nJuicy.", false);
+		index.add("a.md", &page.title, &page.toc, &page.html);
+		assert_eq!(index.len(), 2);
+		assert!(index.search("crisp", 10).is_empty());
+		assert_eq!(index.search("juicy", 10).len(), 1);
+		assert_eq!(index.search("apples", 10).len(), 1);
+	}
+	
+	//		remove																
+	#[test]
+	fn remove() {
+		let mut index = index(&[
+			("a.md", "# Apples\n\nShared text."),
+			("b.md", "# Pears\n\nShared text."),
+			("c.md", "# Plums\n\nShared text."),
+		]);
+		index.remove("a.md");
+		index.remove("d.md");
+		assert_eq!(index.len(), 2);
+		assert!(!index.contains("a.md"));
+		assert!(index.search("apples", 10).is_empty());
+		//	The pages after the one removed are still found correctly
+		let paths = index.search("shared", 10).into_iter().map(|result| result.path).collect::<Vec<_>>();
+		assert_eq!(paths, vec!["b.md", "c.md"]);
+		assert_eq!(index.search("plums", 10).first().map(|result| result.title.as_str()), Some("Plums"));
+	}
+	
+	//		search																
+	#[test]
+	fn search() {
+		let index   = index(&[("a.md", "# Apples\n\nApples are crisp."), ("b.md", "# Pears\n\nSoft pears.")]);
+		let results = index.search("crisp", 10);
+		assert_eq!(results.len(), 1);
+		let result  = results.first().unwrap();
+		assert_eq!(result.path,    "a.md");
+		assert_eq!(result.title,   "Apples");
+		assert_eq!(result.snippet, "Apples Apples are crisp.");
+		assert!(index.search("plums", 10).is_empty());
+		assert!(index.search("",      10).is_empty());
+	}
+	#[test]
+	fn search__ranking() {
+		//	A match in the title outweighs one in the body
+		let index = index(&[
+			("body.md",  "# Fruit\n\nSome notes on apples."),
+			("title.md", "# Apples\n\nSome notes on fruit."),
+		]);
+		let paths = index.search("apples", 10).into_iter().map(|result| result.path).collect::<Vec<_>>();
+		assert_eq!(paths, vec!["title.md", "body.md"]);
+	}
+	#[test]
+	fn search__prefix() {
+		//	Only the last term of the query is matched as a prefix
+		let index = index(&[("a.md", "# Apples\n\nCrisp green apples.")]);
+		assert_eq!(index.search("app",       10).len(), 1);
+		assert_eq!(index.search("green app", 10).len(), 1);
+		assert!(index.search("gree fruit",   10).is_empty());
+	}
+	#[test]
+	fn search__section() {
+		let index   = index(&[("a.md", "# Fruit\n\nIntro.\n\n## Apples\n\nCrisp.\n\n## Pears\n\nSoft.")]);
+		let soft  = index.search("soft", 10).into_iter().next().unwrap();
+		assert_eq!(soft.anchor.as_deref(),  Some("pears"));
+		assert_eq!(soft.heading.as_deref(), Some("Pears"));
+		let intro = super::index(&[("b.md", "Intro.\n\n## Pears\n\nSoft.")]).search("intro", 10).into_iter().next().unwrap();
+		assert_eq!(intro.anchor,  None);
+		assert_eq!(intro.heading, None);
+	}
+	#[test]
+	fn search__limit() {
+		let index = index(&[
+			("1.md", "# One\n\nShared text."),
+			("2.md", "# Two\n\nShared text."),
+			("3.md", "# Three\n\nShared text."),
+			("4.md", "# Four\n\nShared text."),
+			("5.md", "# Five\n\nShared text."),
+		]);
+		assert_eq!(index.search("shared", 3).len(), 3);
+		assert_eq!(index.search("shared", 10).len(), 5);
+	}
+	#[test]
+	fn search__rendered_escaped() {
+		//	The snippet is taken from the decoded text of the page, so markup in a
+		//	code block comes back as plain text, and must be escaped when shown
+		let index   = index(&[("a.md", "# Tips & \"tricks\"\n\n```html\n<script>alert(1)</script>\n```\n")]);
+		let results = index.search("script", 10);
+		assert!(results.first().unwrap().snippet.contains("<script>alert(1)</script>"));
+		let html    = render_search(r#""><script>alert(2)</script>"#, &results);
+		assert!(!html.contains("<script>alert"));
+		assert!(html.contains(r#"value="&quot;&gt;&lt;script&gt;alert(2)&lt;&#x2F;script&gt;""#));
+		assert!(html.contains("&lt;script&gt;alert(1)&lt;&#x2F;script&gt;"));
+		assert!(html.contains("<strong>Tips &amp; “tricks”</strong>"));
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		normalize															
+	#[test]
+	fn normalize() {
+		assert_eq!(super::normalize("  one\n\ttwo   three "), "one two three");
+		assert_eq!(super::normalize(""),                       "");
+	}
+	
+	//		snippet																
+	#[test]
+	fn snippet() {
+		assert_eq!(super::snippet("Short text.", 6), "Short text.");
+		let text = iter::repeat_n("word", 100).collect::<Vec<_>>().join(" ");
+		let cut  = super::snippet(&text, 200);
+		assert!(cut.starts_with('…'));
+		assert!(cut.ends_with('…'));
+		assert!((SNIPPET_LENGTH..=SNIPPET_LENGTH.saturating_add(2)).contains(&cut.chars().count()));
+		assert!(super::snippet(&text, 0).starts_with("word"));
+	}
+	#[test]
+	fn snippet__multibyte() {
+		let text = "é".repeat(300);
+		assert_eq!(super::snippet(&text, 200).chars().filter(|&c| c == 'é').count(), SNIPPET_LENGTH);
+	}
+	
+	//		tokenize															
+	#[test]
+	fn tokenize() {
+		assert_eq!(super::tokenize("Hello, World! x2"), vec![
+			(0,  s!("hello")),
+			(7,  s!("world")),
+			(14, s!("x2")),
+		]);
+		assert_eq!(super::tokenize("Ünïcode café"), vec![(0, s!("ünïcode")), (10, s!("café"))]);
+		assert!(super::tokenize(" -- ").is_empty());
+	}
+}
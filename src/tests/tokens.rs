@@ -0,0 +1,144 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use chrono::Duration;
+use rubedo::sugar::s;
+
+
+
+//		Constants																										
+
+/// The token used in the tests.
+const SECRET: &str = "rmk_test-token";
+
+
+
+//		Functions																										
+
+//		store																	
+/// Creates a token store with a single token, for [`SECRET`].
+fn store(scopes: &[TokenScope], expires: Option<DateTime<Utc>>) -> TokenStore {
+	TokenStore::new(&HashMap::from([(s!("ci"), TokenConfig {
+		hash:    hash_token(SECRET),
+		scopes:  scopes.to_vec(),
+		roles:   vec![s!("staff")],
+		expires,
+	})]))
+}
+
+
+
+//		Tests																											
+
+//		TokenStore																
+#[cfg(test)]
+mod token_store {
+	use super::*;
+	
+	//		new																	
+	#[test]
+	fn new__ignores_missing_hash() {
+		let tokens = TokenStore::new(&HashMap::from([
+			(s!("ci"),     TokenConfig { hash: hash_token(SECRET), ..Default::default() }),
+			(s!("broken"), TokenConfig::default()),
+		]));
+		assert_eq!(tokens.usage().keys().collect::<Vec<_>>(), vec!["ci"]);
+		assert!(matches!(tokens.authenticate("", TokenScope::Content), Err(TokenError::Unknown)));
+	}
+	
+	//		authenticate														
+	#[test]
+	fn authenticate() {
+		let user = store(&[TokenScope::Content], None).authenticate(SECRET, TokenScope::Content).unwrap();
+		assert_eq!(user, User { id: s!("token:ci"), username: s!("ci"), roles: vec![s!("staff")] });
+	}
+	#[test]
+	fn authenticate__by_hash() {
+		//	Tokens are found by the hash of the secret sent, so sending the hash
+		//	from the configuration does not work
+		let tokens = store(&[TokenScope::Content], None);
+		assert!(matches!(tokens.authenticate(&hash_token(SECRET), TokenScope::Content), Err(TokenError::Unknown)));
+		assert!(matches!(tokens.authenticate("rmk_other-token",   TokenScope::Content), Err(TokenError::Unknown)));
+		assert!(matches!(tokens.authenticate("RMK_TEST-TOKEN",    TokenScope::Content), Err(TokenError::Unknown)));
+	}
+	#[test]
+	fn authenticate__expired() {
+		let tokens = store(&[TokenScope::Content], Some(Utc::now().checked_sub_signed(Duration::seconds(1)).unwrap()));
+		assert!(matches!(tokens.authenticate(SECRET, TokenScope::Content), Err(TokenError::Expired(ref name)) if name == "ci"));
+	}
+	#[test]
+	fn authenticate__not_yet_expired() {
+		let tokens = store(&[TokenScope::Content], Some(Utc::now().checked_add_signed(Duration::hours(1)).unwrap()));
+		assert!(tokens.authenticate(SECRET, TokenScope::Content).is_ok());
+	}
+	#[test]
+	fn authenticate__expired_before_scope() {
+		//	An expired token is reported as such, even for a scope it lacks
+		let tokens = store(&[TokenScope::Content], Some(Utc::now().checked_sub_signed(Duration::seconds(1)).unwrap()));
+		assert!(matches!(tokens.authenticate(SECRET, TokenScope::Stats), Err(TokenError::Expired(_))));
+	}
+	#[test]
+	fn authenticate__missing_scope() {
+		let tokens = store(&[TokenScope::Content], None);
+		assert!(matches!(
+			tokens.authenticate(SECRET, TokenScope::Stats),
+			Err(TokenError::MissingScope(ref name, TokenScope::Stats)) if name == "ci"
+		));
+		assert!(store(&[TokenScope::Content, TokenScope::Stats], None).authenticate(SECRET, TokenScope::Stats).is_ok());
+	}
+	
+	//		usage																
+	#[test]
+	fn usage() {
+		let tokens = store(&[TokenScope::Content], None);
+		assert_eq!(tokens.usage().get("ci"), Some(&TokenUsage::default()));
+		assert!(tokens.authenticate(SECRET, TokenScope::Content).is_ok());
+		assert!(tokens.authenticate(SECRET, TokenScope::Content).is_ok());
+		let usage  = tokens.usage().get("ci").copied().unwrap();
+		assert_eq!(usage.requests, 2);
+		assert!(usage.last_used.is_some());
+	}
+	#[test]
+	fn usage__not_counted_when_rejected() {
+		let tokens = store(&[TokenScope::Content], None);
+		assert!(tokens.authenticate(SECRET, TokenScope::Stats).is_err());
+		assert_eq!(tokens.usage().get("ci"), Some(&TokenUsage::default()));
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		generate_token														
+	#[test]
+	fn generate_token() {
+		let (token, hash) = super::generate_token().unwrap();
+		assert!(token.starts_with(TOKEN_PREFIX));
+		assert_eq!(token.len(), 47);
+		assert_eq!(hash, super::hash_token(&token));
+		assert_ne!(super::generate_token().unwrap().0, token);
+	}
+	
+	//		hash_token															
+	#[test]
+	fn hash_token() {
+		assert_eq!(super::hash_token(""),    "47DEQpj8HBSa-_TImW-5JCeuQeRkm5NMpJWZG3hSuFU");
+		assert_eq!(super::hash_token("abc"), "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0");
+	}
+	
+	//		scope_for_path														
+	#[test]
+	fn scope_for_path() {
+		assert_eq!(super::scope_for_path("/api/stats"),         TokenScope::Stats);
+		assert_eq!(super::scope_for_path("/api/stats/tokens"),  TokenScope::Stats);
+		assert_eq!(super::scope_for_path("/api/stats/history"), TokenScope::Stats);
+		assert_eq!(super::scope_for_path("/api/statsfeed"),     TokenScope::Content);
+		assert_eq!(super::scope_for_path("/guides/stats.md"),   TokenScope::Content);
+		assert_eq!(super::scope_for_path("/search"),            TokenScope::Content);
+		assert_eq!(super::scope_for_path("/"),                  TokenScope::Content);
+	}
+}
@@ -0,0 +1,274 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use argon2::{Algorithm, Params, Version};
+use core::time::Duration;
+use rubedo::sugar::s;
+use std::{fs::File, io::Write as _};
+use tempfile::NamedTempFile;
+
+
+
+//		Functions																										
+
+//		cheap_hash																
+/// Hashes a password using Argon2 with the lowest costs allowed, so that tests
+/// do not need to wait for the default costs.
+fn cheap_hash(password: &str) -> String {
+	Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::new(8, 1, 1, None).unwrap())
+		.hash_password(password.as_bytes())
+		.unwrap()
+		.to_string()
+}
+
+//		hash_params																
+/// Gets the algorithm, version, and parameters of an Argon2 PHC string, which
+/// are everything apart from the salt and the hash itself.
+fn hash_params(hash: &str) -> String {
+	hash.rsplitn(3, '$').last().unwrap().to_owned()
+}
+
+//		touch																	
+/// Sets the modification time of a file, so that a change is detected even if
+/// the file system only records the time to the second.
+fn touch(file: &NamedTempFile, seconds: u64) {
+	File::options()
+		.write(true)
+		.open(file.path())
+		.unwrap()
+		.set_modified(SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds)).unwrap())
+		.unwrap()
+	;
+}
+
+//		users_file																
+/// Creates a users file with the given contents.
+fn users_file(contents: &str) -> NamedTempFile {
+	let mut file = NamedTempFile::new().unwrap();
+	file.write_all(contents.as_bytes()).unwrap();
+	touch(&file, 1_000);
+	file
+}
+
+
+
+//		Tests																											
+
+//		ChainedUserStore														
+#[cfg(test)]
+mod chained_user_store {
+	use super::*;
+	
+	//		authenticate														
+	#[test]
+	fn authenticate() {
+		let first  = ConfigUserStore::new(&HashMap::from([(s!("joe"), cheap_hash("1a2b3c"))]));
+		let second = ConfigUserStore::new(&HashMap::from([(s!("ann"), cheap_hash("4d5e6f"))]));
+		let store  = ChainedUserStore::new(vec![Box::new(first), Box::new(second)]);
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(store.authenticate("ann", "4d5e6f"));
+		assert!(!store.authenticate("joe", "4d5e6f"));
+		assert!(store.contains("ann"));
+		assert!(!store.contains("bob"));
+	}
+	
+	//		roles																
+	#[test]
+	fn roles() {
+		let file  = users_file(&format!("[users]\njoe = \"{}\"\n\n[roles]\njoe = [\"hr\"]\n", cheap_hash("1a2b3c")));
+		let store = ChainedUserStore::new(vec![
+			Box::new(ConfigUserStore::default()),
+			Box::new(FileUserStore::new(UserFileFormat::Toml, file.path().to_owned())),
+		]);
+		assert_eq!(store.roles("joe"), vec![s!("hr")]);
+		assert!(store.roles("ann").is_empty());
+	}
+}
+
+//		ConfigUserStore															
+#[cfg(test)]
+mod config_user_store {
+	use super::*;
+	
+	//		new																	
+	#[test]
+	fn new__ignores_plain_text() {
+		let store = ConfigUserStore::new(&HashMap::from([
+			(s!("joe"), cheap_hash("1a2b3c")),
+			(s!("ann"), s!("password")),
+		]));
+		assert!(store.contains("joe"));
+		assert!(!store.contains("ann"));
+		assert!(!store.authenticate("ann", "password"));
+	}
+	
+	//		authenticate														
+	#[test]
+	fn authenticate() {
+		let store = ConfigUserStore::new(&HashMap::from([(s!("joe"), cheap_hash("1a2b3c"))]));
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(!store.authenticate("joe", "1A2B3C"));
+		assert!(!store.authenticate("Joe", "1a2b3c"));
+		assert!(!store.authenticate("bob", "1a2b3c"));
+	}
+}
+
+//		FileUserStore															
+#[cfg(test)]
+mod file_user_store {
+	use super::*;
+	
+	//		new																	
+	#[test]
+	fn new__htpasswd() {
+		let file  = users_file(&format!("# Users\n\njoe:{}\nann:password\n", cheap_hash("1a2b3c")));
+		let store = FileUserStore::new(UserFileFormat::Htpasswd, file.path().to_owned());
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert!(!store.contains("ann"));
+		assert!(store.roles("joe").is_empty());
+	}
+	#[test]
+	fn new__toml() {
+		let file  = users_file(&format!("[users]\njoe = \"{}\"\n\n[roles]\njoe = [\"hr\", \"it\"]\n", cheap_hash("1a2b3c")));
+		let store = FileUserStore::new(UserFileFormat::Toml, file.path().to_owned());
+		assert!(store.authenticate("joe", "1a2b3c"));
+		assert_eq!(store.roles("joe"), vec![s!("hr"), s!("it")]);
+	}
+	#[test]
+	fn new__missing_file() {
+		let store = FileUserStore::new(UserFileFormat::Toml, PathBuf::from("/nonexistent/users.toml"));
+		assert!(!store.contains("joe"));
+		assert!(!store.authenticate("joe", "1a2b3c"));
+	}
+	
+	//		refresh																
+	#[test]
+	fn refresh__reloads_when_modified() {
+		let file  = users_file(&format!("joe:{}\n", cheap_hash("1a2b3c")));
+		let store = FileUserStore::new(UserFileFormat::Htpasswd, file.path().to_owned());
+		assert!(store.contains("joe"));
+		fs::write(file.path(), format!("ann:{}\n", cheap_hash("4d5e6f"))).unwrap();
+		touch(&file, 2_000);
+		assert!(!store.contains("joe"));
+		assert!(store.authenticate("ann", "4d5e6f"));
+	}
+	#[test]
+	fn refresh__unchanged_when_not_modified() {
+		let file  = users_file(&format!("joe:{}\n", cheap_hash("1a2b3c")));
+		let store = FileUserStore::new(UserFileFormat::Htpasswd, file.path().to_owned());
+		fs::write(file.path(), format!("ann:{}\n", cheap_hash("4d5e6f"))).unwrap();
+		touch(&file, 1_000);
+		assert!(store.contains("joe"));
+		assert!(!store.contains("ann"));
+	}
+	#[test]
+	fn refresh__keeps_users_when_invalid() {
+		let file  = users_file(&format!("[users]\njoe = \"{}\"\n", cheap_hash("1a2b3c")));
+		let store = FileUserStore::new(UserFileFormat::Toml, file.path().to_owned());
+		fs::write(file.path(), "[users\n").unwrap();
+		touch(&file, 2_000);
+		assert!(store.authenticate("joe", "1a2b3c"));
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		hash_password														
+	#[test]
+	fn hash_password__argon2() {
+		let hash = hash_password("1a2b3c", HashAlgorithm::Argon2).unwrap();
+		assert!(hash.starts_with("$argon2id$"));
+		assert!(verify_password("1a2b3c", &hash));
+		assert!(!verify_password("4d5e6f", &hash));
+	}
+	#[test]
+	fn hash_password__bcrypt() {
+		let hash = hash_password("1a2b3c", HashAlgorithm::Bcrypt).unwrap();
+		assert!(hash.starts_with("$2b$"));
+		assert!(verify_password("1a2b3c", &hash));
+		assert!(!verify_password("4d5e6f", &hash));
+	}
+	#[test]
+	fn hash_password__salted() {
+		assert_ne!(cheap_hash("1a2b3c"), cheap_hash("1a2b3c"));
+	}
+	
+	//		hashed_users														
+	#[test]
+	fn hashed_users() {
+		let hash  = cheap_hash("1a2b3c");
+		let users = super::hashed_users(HashMap::from([
+			(s!("joe"), hash.clone()),
+			(s!("ann"), s!("password")),
+			(s!("bob"), s!("$1$md5$notsupported")),
+		]), "test");
+		assert_eq!(users, HashMap::from([(s!("joe"), hash)]));
+	}
+	
+	//		is_supported_hash													
+	#[test]
+	fn is_supported_hash() {
+		assert!(super::is_supported_hash("$argon2id$v=19$m=8,t=1,p=1$c2FsdA$aGFzaA"));
+		assert!(super::is_supported_hash("$2a$04$abcdefghijklmnopqrstuu"));
+		assert!(super::is_supported_hash("$2y$04$abcdefghijklmnopqrstuu"));
+		assert!(!super::is_supported_hash("$apr1$salt$hash"));
+		assert!(!super::is_supported_hash("password"));
+	}
+	
+	//		parse_htpasswd														
+	#[test]
+	fn parse_htpasswd() {
+		let users = super::parse_htpasswd("# Comment\n\n  joe:$2b$hash  \nann:$argon2id:with:colons\nnocolon\n");
+		assert_eq!(users, HashMap::from([
+			(s!("joe"), s!("$2b$hash")),
+			(s!("ann"), s!("$argon2id:with:colons")),
+		]));
+	}
+	
+	//		verify_password														
+	#[test]
+	fn verify_password__argon2() {
+		let hash = cheap_hash("1a2b3c");
+		assert!(verify_password("1a2b3c", &hash));
+		assert!(!verify_password("1a2b3", &hash));
+		assert!(!verify_password("", &hash));
+	}
+	#[test]
+	fn verify_password__bcrypt() {
+		let hash = bcrypt::hash("1a2b3c", 4).unwrap();
+		assert!(verify_password("1a2b3c", &hash));
+		assert!(!verify_password("1a2b3", &hash));
+	}
+	#[test]
+	fn verify_password__invalid() {
+		assert!(!verify_password("password", "password"));
+		assert!(!verify_password("1a2b3c", "$argon2id$invalid"));
+		assert!(!verify_password("1a2b3c", "$2b$invalid"));
+	}
+	
+	//		verify_user															
+	#[test]
+	fn verify_user__existing() {
+		let hash = cheap_hash("1a2b3c");
+		assert!(verify_user(Some(&hash), "1a2b3c"));
+		assert!(!verify_user(Some(&hash), "4d5e6f"));
+	}
+	#[test]
+	fn verify_user__missing() {
+		assert!(!verify_user(None, "1a2b3c"));
+		assert!(!verify_user(None, "not a real password"));
+	}
+	#[test]
+	fn verify_user__dummy_hash_costs() {
+		//	A missing user is checked against a hash with the same costs as a real
+		//	one, so that both take the same time.
+		let real = hash_password("1a2b3c", HashAlgorithm::Argon2).unwrap();
+		assert!(PasswordHash::new(&DUMMY_HASH).is_ok());
+		assert_eq!(hash_params(&DUMMY_HASH), hash_params(&real));
+	}
+}
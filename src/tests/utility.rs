@@ -0,0 +1,167 @@
+#![allow(non_snake_case, reason = "To enable test function name organisation")]
+
+//		Packages																										
+
+use super::*;
+use axum::http::HeaderValue;
+use rubedo::sugar::s;
+
+
+
+//		Functions																										
+
+//		headers																	
+/// Creates request headers with the `Accept` header values given.
+fn headers(accept: &[&'static str]) -> HeaderMap {
+	let mut headers = HeaderMap::new();
+	for &value in accept {
+		_ = headers.append(header::ACCEPT, HeaderValue::from_static(value));
+	}
+	headers
+}
+
+//		page																	
+/// Chooses the format of a page response, from those that pages can be given
+/// in, for an `Accept` header value.
+fn page(accept: &'static str) -> ResponseFormat {
+	negotiate(&headers(&[accept]), &[ResponseFormat::Html, ResponseFormat::Markdown, ResponseFormat::Json])
+}
+
+
+
+//		Tests																											
+
+//		MediaRange																
+#[cfg(test)]
+mod media_range {
+	use super::*;
+	
+	//		parse																
+	#[test]
+	fn parse() {
+		assert_eq!(MediaRange::parse("text/html"),               Some(MediaRange { media_type: s!("text/html"),  quality: 1000 }));
+		assert_eq!(MediaRange::parse(" Text/HTML ; q=0.5 "),     Some(MediaRange { media_type: s!("text/html"),  quality: 500 }));
+		assert_eq!(MediaRange::parse("text/*;level=1;Q=0.25"),   Some(MediaRange { media_type: s!("text/*"),     quality: 250 }));
+		assert_eq!(MediaRange::parse("*/*;q=0"),                 Some(MediaRange { media_type: s!("*/*"),        quality: 0 }));
+	}
+	#[test]
+	fn parse__invalid() {
+		assert_eq!(MediaRange::parse(""),                   None);
+		assert_eq!(MediaRange::parse("text"),               None);
+		assert_eq!(MediaRange::parse("text/"),              None);
+		assert_eq!(MediaRange::parse("*/html"),             None);
+		assert_eq!(MediaRange::parse("text/html;q=2"),      None);
+		assert_eq!(MediaRange::parse("text/html;q=high"),   None);
+	}
+	
+	//		specificity															
+	#[test]
+	fn specificity() {
+		let range = |media_type: &str| MediaRange { media_type: media_type.to_owned(), quality: 1000 };
+		assert_eq!(range("text/markdown").specificity("text/markdown"), Some(2));
+		assert_eq!(range("text/*").specificity("text/markdown"),        Some(1));
+		assert_eq!(range("*/*").specificity("text/markdown"),           Some(0));
+		assert_eq!(range("text/html").specificity("text/markdown"),     None);
+		assert_eq!(range("application/*").specificity("text/markdown"), None);
+		assert_eq!(range("text/*").specificity("texts/markdown"),       None);
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		negotiate															
+	#[test]
+	fn negotiate__html_or_json() {
+		let listing = |accept: &[&'static str]| negotiate(&headers(accept), &[ResponseFormat::Html, ResponseFormat::Json]);
+		assert_eq!(listing(&["application/json"]),                    ResponseFormat::Json);
+		assert_eq!(listing(&["text/html;q=0.5, application/json"]),   ResponseFormat::Json);
+		assert_eq!(listing(&["text/markdown"]),                       ResponseFormat::Html);
+		assert_eq!(listing(&[]),                                      ResponseFormat::Html);
+		assert_eq!(listing(&["*/*"]),                                 ResponseFormat::Html);
+		assert_eq!(listing(&["application/json;q=0.5, text/html"]),   ResponseFormat::Html);
+		assert_eq!(listing(&["application/jsonl"]),                   ResponseFormat::Html);
+	}
+	#[test]
+	fn negotiate__default() {
+		assert_eq!(negotiate(&headers(&[]), &[ResponseFormat::Html, ResponseFormat::Json]), ResponseFormat::Html);
+		assert_eq!(negotiate(&headers(&[]), &[ResponseFormat::Json, ResponseFormat::Html]), ResponseFormat::Json);
+		assert_eq!(negotiate(&headers(&[]), &[]),                                           ResponseFormat::Html);
+		assert_eq!(page("*/*"),                                                             ResponseFormat::Html);
+		assert_eq!(page("image/png"),                                                       ResponseFormat::Html);
+		assert_eq!(page("not a media type"),                                                ResponseFormat::Html);
+	}
+	#[test]
+	fn negotiate__browser() {
+		assert_eq!(page("text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"), ResponseFormat::Html);
+	}
+	#[test]
+	fn negotiate__exact() {
+		assert_eq!(page("text/markdown"),    ResponseFormat::Markdown);
+		assert_eq!(page("application/json"), ResponseFormat::Json);
+		assert_eq!(page("TEXT/MARKDOWN"),    ResponseFormat::Markdown);
+	}
+	#[test]
+	fn negotiate__quality() {
+		assert_eq!(page("text/markdown;q=0.5, application/json"),           ResponseFormat::Json);
+		assert_eq!(page("text/markdown, application/json;q=0.9"),           ResponseFormat::Markdown);
+		assert_eq!(page("text/html;q=0.1, text/markdown;q=0.2"),            ResponseFormat::Markdown);
+		assert_eq!(page("application/json;q=0.001, text/html;q=0"),         ResponseFormat::Json);
+	}
+	#[test]
+	fn negotiate__not_acceptable() {
+		//	A quality of zero rules a format out, even if a wildcard allows it
+		assert_eq!(page("text/markdown;q=0"),                               ResponseFormat::Html);
+		assert_eq!(page("text/markdown;q=0, */*"),                          ResponseFormat::Html);
+		assert_eq!(page("*/*, text/markdown;q=0"),                          ResponseFormat::Html);
+		assert_eq!(page("text/html;q=0, text/markdown;q=0, */*"),           ResponseFormat::Json);
+		assert_eq!(page("text/*;q=0, */*"),                                 ResponseFormat::Json);
+		assert_eq!(page("text/html;q=0, text/markdown;q=0, application/json;q=0"), ResponseFormat::Html);
+	}
+	#[test]
+	fn negotiate__wildcards() {
+		//	An exact match takes precedence over a wildcard, wherever it appears
+		assert_eq!(page("*/*, application/json"),                           ResponseFormat::Json);
+		assert_eq!(page("application/json, */*"),                           ResponseFormat::Json);
+		assert_eq!(page("text/*, application/json"),                        ResponseFormat::Json);
+		assert_eq!(page("text/*"),                                          ResponseFormat::Html);
+		assert_eq!(page("text/*;q=0.5, */*;q=0.1"),                         ResponseFormat::Html);
+		assert_eq!(page("text/html;q=0.5, text/*"),                         ResponseFormat::Markdown);
+	}
+	#[test]
+	fn negotiate__order() {
+		//	Between equally good matches, the first given is used
+		assert_eq!(page("application/json, text/markdown"),                 ResponseFormat::Json);
+		assert_eq!(page("text/markdown, application/json"),                 ResponseFormat::Markdown);
+		assert_eq!(page("application/json, text/html"),                     ResponseFormat::Json);
+	}
+	#[test]
+	fn negotiate__several_headers() {
+		let several = headers(&["text/html;q=0.5", "application/json"]);
+		assert_eq!(negotiate(&several, &[ResponseFormat::Html, ResponseFormat::Json]), ResponseFormat::Json);
+	}
+	
+	//		parse_quality														
+	#[test]
+	fn parse_quality() {
+		assert_eq!(super::parse_quality("1"),     Some(1000));
+		assert_eq!(super::parse_quality("1.000"), Some(1000));
+		assert_eq!(super::parse_quality("0"),     Some(0));
+		assert_eq!(super::parse_quality("0.5"),   Some(500));
+		assert_eq!(super::parse_quality("0.05"),  Some(50));
+		assert_eq!(super::parse_quality("0.001"), Some(1));
+		assert_eq!(super::parse_quality("0."),    Some(0));
+	}
+	#[test]
+	fn parse_quality__invalid() {
+		assert_eq!(super::parse_quality(""),       None);
+		assert_eq!(super::parse_quality("1.5"),    None);
+		assert_eq!(super::parse_quality("2"),      None);
+		assert_eq!(super::parse_quality("0.0001"), None);
+		assert_eq!(super::parse_quality("0.-1"),   None);
+		assert_eq!(super::parse_quality(".5"),     None);
+		assert_eq!(super::parse_quality("-0"),     None);
+	}
+}
@@ -0,0 +1,140 @@
+#![allow(non_snake_case,     reason = "To enable test function name organisation")]
+#![allow(clippy::unwrap_used, reason = "Somewhat acceptable in tests")]
+
+//		Packages																										
+
+use super::*;
+use crate::parse;
+
+
+
+//		Functions																										
+
+//		index																	
+/// Creates a page index from some pages, given as paths and titles.
+fn index(pages: &[(&str, &str)]) -> PageIndex {
+	let mut index = PageIndex::new();
+	for &(path, title) in pages {
+		index.add(path, title);
+	}
+	index
+}
+
+
+
+//		Tests																											
+
+//		PageIndex																
+#[cfg(test)]
+mod page_index {
+	use super::*;
+	
+	//		add																	
+	#[test]
+	fn add__replaces() {
+		let mut index = index(&[("a.md", "Old")]);
+		index.add("a.md", "New");
+		assert_eq!(index.resolve("new"), Some("a.md"));
+		assert_eq!(index.resolve("old"), None);
+	}
+	
+	//		remove																
+	#[test]
+	fn remove() {
+		let mut index = index(&[("a.md", "A")]);
+		index.remove("a.md");
+		index.remove("b.md");
+		assert_eq!(index.resolve("a"), None);
+	}
+	
+	//		resolve																
+	#[test]
+	fn resolve__path() {
+		let index = index(&[("guide/setup.md", "Getting started"), ("guide/setup", "Not Markdown")]);
+		assert_eq!(index.resolve("guide/setup.md"),    Some("guide/setup.md"));
+		assert_eq!(index.resolve(" /guide/setup.md "), Some("guide/setup.md"));
+		assert_eq!(index.resolve("guide/setup"),       Some("guide/setup"));
+		assert_eq!(index.resolve("Guide/Setup.md"),    None);
+		assert_eq!(index.resolve("missing"),           None);
+		assert_eq!(index.resolve("  "),                None);
+	}
+	#[test]
+	fn resolve__path_without_extension() {
+		let index = index(&[("guide/setup.md", "Getting started")]);
+		assert_eq!(index.resolve("guide/setup"),  Some("guide/setup.md"));
+		assert_eq!(index.resolve("/guide/setup"), Some("guide/setup.md"));
+	}
+	#[test]
+	fn resolve__title() {
+		let index = index(&[("guide/setup.md", "Getting Started"), ("run.md", "Run")]);
+		assert_eq!(index.resolve("Getting Started"), Some("guide/setup.md"));
+		assert_eq!(index.resolve("getting started"), Some("guide/setup.md"));
+		assert_eq!(index.resolve("Getting"),         None);
+	}
+	#[test]
+	fn resolve__stem() {
+		let index = index(&[("guide/setup.md", "Getting started")]);
+		assert_eq!(index.resolve("setup"), Some("guide/setup.md"));
+		assert_eq!(index.resolve("SETUP"), Some("guide/setup.md"));
+		assert_eq!(index.resolve("guide"), None);
+	}
+	#[test]
+	fn resolve__preference() {
+		//	A path beats a title, which beats a file stem, and otherwise the first
+		//	path in alphabetical order wins
+		let index = index(&[
+			("b/setup.md", "Install"),
+			("a/setup.md", "Other"),
+			("guide.md",   "Setup"),
+			("other.md",   "Something else"),
+		]);
+		assert_eq!(index.resolve("other"),   Some("other.md"));
+		assert_eq!(index.resolve("setup"),   Some("guide.md"));
+		assert_eq!(index.resolve("install"), Some("b/setup.md"));
+		assert_eq!(super::index(&[("b/setup.md", "B"), ("a/setup.md", "A")]).resolve("setup"), Some("a/setup.md"));
+	}
+}
+
+//		Functions																
+#[cfg(test)]
+mod functions {
+	use super::*;
+	
+	//		resolve_wiki_links													
+	#[test]
+	fn resolve_wiki_links() {
+		let index      = index(&[("guide/setup.md", "Getting Started")]);
+		let mut page   = parse("See [[Getting Started|the guide]] and [[Missing Page]].\n", false);
+		let unresolved = super::resolve_wiki_links(&mut page, &index);
+		assert_eq!(unresolved, vec!["Missing Page"]);
+		let document   = Document::from(&page.html);
+		let resolved   = document.select(r#"a[href="/guide/setup.md"]"#);
+		assert_eq!(resolved.text().to_string(), "the guide");
+		assert!(!resolved.has_class(UNRESOLVED_CLASS));
+		let missing    = document.select(&format!("a.{UNRESOLVED_CLASS}"));
+		assert!(missing.attr("href").is_none());
+		assert_eq!(missing.attr(REFERENCE_ATTRIBUTE).unwrap().to_string(), "Missing Page");
+		assert_eq!(page.links.iter().map(|link| link.target.as_str()).collect::<Vec<_>>(), vec!["/guide/setup.md"]);
+	}
+	#[test]
+	fn resolve_wiki_links__again() {
+		//	Links can be resolved again once the content tree changes, including
+		//	those that were resolved before
+		let mut index = index(&[("old.md", "Target")]);
+		let mut page  = parse("[[Target]] [[New]]\n", false);
+		assert_eq!(super::resolve_wiki_links(&mut page, &index), vec!["New"]);
+		index.remove("old.md");
+		index.add("target.md", "Target");
+		index.add("new.md",    "New");
+		assert!(super::resolve_wiki_links(&mut page, &index).is_empty());
+		assert_eq!(page.links.iter().map(|link| link.target.as_str()).collect::<Vec<_>>(), vec!["/target.md", "/new.md"]);
+		assert!(!page.html.contains(UNRESOLVED_CLASS));
+	}
+	#[test]
+	fn resolve_wiki_links__none() {
+		let mut page = parse("# Page\n\nSee [other](other.md).\n", false);
+		let html     = page.html.clone();
+		assert!(super::resolve_wiki_links(&mut page, &PageIndex::new()).is_empty());
+		assert_eq!(page.html, html);
+	}
+}
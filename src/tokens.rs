@@ -0,0 +1,333 @@
+//! API token functionality.
+//! 
+//! API tokens allow scripts and other automated clients to access protected
+//! routes without logging in, by sending an `Authorization: Bearer` header.
+//! Tokens are defined in the configuration by name, along with the SHA-256
+//! hash of the token, the scopes it covers, the roles it has, and an optional
+//! expiry time. Tokens are long and random, so a fast hash is sufficient, and
+//! allows each token to be found by its hash without checking every one.
+//! 
+//! Each use of a token is logged, and counted in the application statistics.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/tokens.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	auth::User,
+	config::{TokenConfig, TokenScope},
+	routes::is_public,
+	state::AppState,
+};
+use axum::{
+	body::Body,
+	extract::{MatchedPath, State},
+	http::{HeaderValue, Request, StatusCode, header::{AUTHORIZATION, WWW_AUTHENTICATE}},
+	middleware::Next,
+	response::{IntoResponse as _, Response},
+};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use sha2::{Digest as _, Sha256};
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::Arc,
+};
+use terracotta::auth::middleware::Context as AuthContext;
+use thiserror::Error as ThisError;
+use tracing::{info, warn};
+
+
+
+//		Constants																										
+
+/// The prefix of the IDs of users authenticated by an API token, which keeps
+/// them distinct from the usernames of users in the user store.
+const ID_PREFIX:    &str = "token:";
+
+/// The prefix of generated tokens, which makes them easy to recognise, for
+/// instance when scanning for leaked secrets.
+const TOKEN_PREFIX: &str = "rmk_";
+
+
+
+//		Enums																											
+
+//		TokenError																
+/// Errors that can occur when authenticating with an API token.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum TokenError {
+	/// Random bytes could not be generated for a new token.
+	#[error("Could not generate random data: {0}")]
+	CouldNotGenerateRandom(getrandom::Error),
+	
+	/// The token has expired.
+	#[error("Token {0} has expired")]
+	Expired(String),
+	
+	/// The token does not cover the part of the application requested.
+	#[error("Token {0} does not have the {1:?} scope")]
+	MissingScope(String, TokenScope),
+	
+	/// The token is not known.
+	#[error("Unknown token")]
+	Unknown,
+}
+
+
+
+//		Structs																											
+
+//		Token																	
+/// An API token, as defined in the configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Token {
+	//		Private properties													
+	/// The name of the token.
+	name:    String,
+	
+	/// The parts of the application that the token can be used to access.
+	scopes:  Vec<TokenScope>,
+	
+	/// The roles given to the token.
+	roles:   Vec<String>,
+	
+	/// When the token expires, if ever.
+	expires: Option<DateTime<Utc>>,
+}
+
+//		TokenStore																
+/// The API tokens that can be used, and a record of their use.
+#[derive(Debug, Default)]
+pub struct TokenStore {
+	//		Private properties													
+	/// The tokens, keyed by hash.
+	tokens: HashMap<String, Token>,
+	
+	/// The use of each token since the application started, keyed by name.
+	usage:  Mutex<HashMap<String, TokenUsage>>,
+}
+
+//󰭅		TokenStore																
+impl TokenStore {
+	//		new																	
+	/// Creates a new token store.
+	/// 
+	/// Tokens without a hash are ignored, and a warning is logged.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `tokens` - The tokens, keyed by name.
+	/// 
+	pub fn new(tokens: &HashMap<String, TokenConfig>) -> Self {
+		Self {
+			tokens: tokens
+				.iter()
+				.filter(|&(name, token)| {
+					if token.hash.is_empty() {
+						warn!("Ignoring API token {name}, as it has no hash");
+					}
+					!token.hash.is_empty()
+				})
+				.map(|(name, token)| (token.hash.clone(), Token {
+					name:    name.clone(),
+					scopes:  token.scopes.clone(),
+					roles:   token.roles.clone(),
+					expires: token.expires,
+				}))
+				.collect()
+			,
+			usage:  Mutex::new(HashMap::new()),
+		}
+	}
+	
+	//		authenticate														
+	/// Authenticates a request using a token.
+	/// 
+	/// If successful, the use of the token is recorded.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `secret` - The token sent with the request.
+	/// * `scope`  - The scope needed for the request.
+	/// 
+	/// # Returns
+	/// 
+	/// The user that the token represents.
+	/// 
+	/// # Errors
+	/// 
+	/// If the token is not known, has expired, or does not have the required
+	/// scope, an error is returned.
+	/// 
+	pub fn authenticate(&self, secret: &str, scope: TokenScope) -> Result<User, TokenError> {
+		let token = self.tokens.get(&hash_token(secret)).ok_or(TokenError::Unknown)?;
+		let now   = Utc::now();
+		if token.expires.is_some_and(|expires| expires <= now) {
+			return Err(TokenError::Expired(token.name.clone()));
+		}
+		if !token.scopes.contains(&scope) {
+			return Err(TokenError::MissingScope(token.name.clone(), scope));
+		}
+		let mut usage   = self.usage.lock();
+		let entry       = usage.entry(token.name.clone()).or_default();
+		entry.requests  = entry.requests.saturating_add(1);
+		entry.last_used = Some(now);
+		drop(usage);
+		Ok(User {
+			id:       format!("{ID_PREFIX}{}", token.name),
+			username: token.name.clone(),
+			roles:    token.roles.clone(),
+		})
+	}
+	
+	//		usage																
+	/// Gets the use of each token since the application started.
+	/// 
+	/// Tokens that have not been used are included, with no requests.
+	/// 
+	pub fn usage(&self) -> BTreeMap<String, TokenUsage> {
+		let usage = self.usage.lock();
+		self.tokens
+			.values()
+			.map(|token| (token.name.clone(), usage.get(&token.name).copied().unwrap_or_default()))
+			.collect()
+	}
+}
+
+//		TokenUsage																
+/// A record of the use of an API token.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TokenUsage {
+	//		Public properties													
+	/// The number of requests made using the token.
+	pub requests:  u64,
+	
+	/// When the token was last used, if at all.
+	pub last_used: Option<DateTime<Utc>>,
+}
+
+
+
+//		Functions																										
+
+//		generate_token															
+/// Generates a new random API token.
+/// 
+/// # Returns
+/// 
+/// The token, and its hash for use in the configuration.
+/// 
+/// # Errors
+/// 
+/// If random bytes cannot be generated, an error is returned.
+/// 
+pub fn generate_token() -> Result<(String, String), TokenError> {
+	let mut bytes = [0_u8; 32];
+	getrandom::fill(&mut bytes).map_err(TokenError::CouldNotGenerateRandom)?;
+	let token     = format!("{TOKEN_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes));
+	let hash      = hash_token(&token);
+	Ok((token, hash))
+}
+
+//		hash_token																
+/// Hashes an API token, for storing in or comparing with the configuration.
+/// 
+/// # Parameters
+/// 
+/// * `token` - The token.
+/// 
+pub fn hash_token(token: &str) -> String {
+	URL_SAFE_NO_PAD.encode(Sha256::digest(token))
+}
+
+//		scope_for_path															
+/// Determines the scope needed to access a path.
+/// 
+/// # Parameters
+/// 
+/// * `path` - The path of the request.
+/// 
+fn scope_for_path(path: &str) -> TokenScope {
+	if path == "/api/stats" || path.starts_with("/api/stats/") {
+		TokenScope::Stats
+	} else {
+		TokenScope::Content
+	}
+}
+
+//		token_layer																
+/// Authenticates requests that carry an API token.
+/// 
+/// This middleware runs after the session has been checked, and if the request
+/// has an `Authorization: Bearer` header, it replaces the current user with the
+/// one the token represents, so that the protected routes can be accessed.
+/// Requests with a token that is not valid are rejected, rather than being
+/// treated as not logged in, so that mistakes in scripts are easy to spot.
+/// The exception is a valid token without the scope needed for a public route,
+/// which is ignored, as the route does not need a token at all.
+/// 
+/// # Parameters
+/// 
+/// * `state`   - The application state.
+/// * `request` - The request.
+/// * `next`    - The next middleware.
+/// 
+pub async fn token_layer(
+	State(state): State<Arc<AppState>>,
+	mut request:  Request<Body>,
+	next:         Next,
+) -> Response {
+	let Some(secret) = request
+		.headers()
+		.get(AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "))
+		.map(|secret| secret.trim().to_owned())
+	else {
+		return next.run(request).await;
+	};
+	let path         = request.uri().path().to_owned();
+	match state.tokens.authenticate(&secret, scope_for_path(&path)) {
+		Ok(user) => {
+			info!("API token {} used for {path}", user.username);
+			if let Some(auth_cx) = request.extensions_mut().get_mut::<AuthContext<User>>() {
+				auth_cx.current_user = Some(user);
+			}
+			next.run(request).await
+		},
+		Err(TokenError::MissingScope(name, _)) if request
+			.extensions()
+			.get::<MatchedPath>()
+			.is_some_and(|route| is_public(&state, route.as_str()))
+		=> {
+			info!("API token {name} ignored for public {path}, as it does not have the scope");
+			next.run(request).await
+		},
+		Err(err) => {
+			warn!("Rejected API token for {path}: {err}");
+			let (status, challenge) = if matches!(err, TokenError::MissingScope(..)) {
+				(StatusCode::FORBIDDEN,    r#"Bearer error="insufficient_scope""#)
+			} else {
+				(StatusCode::UNAUTHORIZED, r#"Bearer error="invalid_token""#)
+			};
+			(
+				status,
+				[(WWW_AUTHENTICATE, HeaderValue::from_static(challenge))],
+			).into_response()
+		},
+	}
+}
+
+
@@ -0,0 +1,513 @@
+//! User store functionality.
+//! 
+//! Users can be listed in the application configuration, in an htpasswd file,
+//! or in a separate TOML file, or can be authenticated against an LDAP
+//! directory. Passwords are always stored as hashes, in PHC string format for
+//! Argon2, or in the standard format for bcrypt, and are verified in constant
+//! time. Users listed in files are reloaded automatically whenever the file
+//! changes, so that they can be updated without restarting the application.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/users.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use crate::{
+	config::{Config, UserSource},
+	ldap::LdapUserStore,
+};
+use argon2::{
+	Argon2,
+	password_hash::{Error as Argon2Error, PasswordHasher as _, PasswordVerifier as _, phc::PasswordHash},
+};
+use bcrypt::BcryptError;
+use core::{fmt::Debug, hint::black_box};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::{
+	collections::HashMap,
+	fs,
+	io::Error as IoError,
+	path::PathBuf,
+	sync::LazyLock,
+	time::SystemTime,
+};
+use thiserror::Error as ThisError;
+use toml::de::Error as TomlError;
+use tracing::{info, warn};
+
+
+
+//		Constants																										
+
+/// A hash to verify against when a user does not exist, so that the time taken
+/// to reject a login does not reveal whether the username is valid.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(||
+	hash_password("not a real password", HashAlgorithm::Argon2).unwrap_or_default()
+);
+
+
+
+//		Enums																											
+
+//		HashAlgorithm															
+/// The algorithms that can be used to hash passwords.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+	/// Argon2id, producing a PHC string. This is the recommended algorithm.
+	#[default]
+	Argon2,
+	
+	/// bcrypt, as commonly used in htpasswd files.
+	Bcrypt,
+}
+
+//		UserFileFormat															
+/// The formats of file that users can be loaded from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum UserFileFormat {
+	/// An htpasswd file, with one `username:hash` entry per line.
+	Htpasswd,
+	
+	/// A TOML file, with a `[users]` table mapping usernames to hashes, and
+	/// an optional `[roles]` table mapping usernames to lists of roles.
+	Toml,
+}
+
+//		UserStoreError															
+/// Errors that can occur when loading users or hashing passwords.
+#[derive(Debug, ThisError)]
+#[non_exhaustive]
+pub enum UserStoreError {
+	/// The password could not be hashed using Argon2.
+	#[error("Could not hash password: {0}")]
+	CouldNotHashArgon2(Argon2Error),
+	
+	/// The password could not be hashed using bcrypt.
+	#[error("Could not hash password: {0}")]
+	CouldNotHashBcrypt(#[from] BcryptError),
+	
+	/// The users file could not be read.
+	#[error("Could not read users file {0}: {1}")]
+	CouldNotReadFile(PathBuf, IoError),
+	
+	/// The users file is not valid TOML, or is not in the expected format.
+	#[error("Invalid users file {0}: {1}")]
+	InvalidTomlFile(PathBuf, TomlError),
+}
+
+
+
+//		Structs																											
+
+//		ChainedUserStore														
+/// Users from several stores, which are checked in order.
+#[derive(Debug)]
+pub struct ChainedUserStore {
+	//		Private properties													
+	/// The stores, in the order they are checked.
+	stores: Vec<Box<dyn UserStore>>,
+}
+
+//󰭅		ChainedUserStore														
+impl ChainedUserStore {
+	//		new																	
+	/// Creates a new store from a list of stores.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `stores` - The stores, in the order they are checked.
+	/// 
+	pub fn new(stores: Vec<Box<dyn UserStore>>) -> Self {
+		Self { stores }
+	}
+}
+
+//󰭅		UserStore																
+impl UserStore for ChainedUserStore {
+	//		authenticate														
+	fn authenticate(&self, username: &str, password: &str) -> bool {
+		self.stores.iter().any(|store| store.authenticate(username, password))
+	}
+	
+	//		contains															
+	fn contains(&self, username: &str) -> bool {
+		self.stores.iter().any(|store| store.contains(username))
+	}
+	
+	//		roles																
+	fn roles(&self, username: &str) -> Vec<String> {
+		self.stores
+			.iter()
+			.filter(|store| store.contains(username))
+			.flat_map(|store| store.roles(username))
+			.collect()
+	}
+}
+
+//		ConfigUserStore															
+/// Users listed in the application configuration.
+#[derive(Debug, Default)]
+pub struct ConfigUserStore {
+	//		Private properties													
+	/// The password hashes of the users, keyed by username.
+	users: HashMap<String, String>,
+}
+
+//󰭅		ConfigUserStore															
+impl ConfigUserStore {
+	//		new																	
+	/// Creates a new store from the users listed in the configuration.
+	/// 
+	/// Users whose passwords are not hashed are ignored, with a warning.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `users` - The users, and their password hashes.
+	/// 
+	pub fn new(users: &HashMap<String, String>) -> Self {
+		Self { users: hashed_users(users.clone(), "the configuration") }
+	}
+}
+
+//󰭅		UserStore																
+impl UserStore for ConfigUserStore {
+	//		authenticate														
+	fn authenticate(&self, username: &str, password: &str) -> bool {
+		verify_user(self.users.get(username).map(String::as_str), password)
+	}
+	
+	//		contains															
+	fn contains(&self, username: &str) -> bool {
+		self.users.contains_key(username)
+	}
+}
+
+//		FileUserStore															
+/// Users listed in a file.
+/// 
+/// The file is checked for changes whenever a user is looked up, and reloaded
+/// if it has been modified. If the file cannot be loaded, the users previously
+/// loaded remain in effect.
+/// 
+#[derive(Debug)]
+pub struct FileUserStore {
+	//		Private properties													
+	/// The format of the file.
+	format: UserFileFormat,
+	
+	/// The path to the file.
+	path:   PathBuf,
+	
+	/// The users most recently loaded from the file.
+	users:  RwLock<LoadedUsers>,
+}
+
+//󰭅		FileUserStore															
+impl FileUserStore {
+	//		new																	
+	/// Creates a new store for users listed in a file, and loads them.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `format` - The format of the file.
+	/// * `path`   - The path to the file.
+	/// 
+	pub fn new(format: UserFileFormat, path: PathBuf) -> Self {
+		let store = Self {
+			format,
+			path,
+			users: RwLock::new(LoadedUsers::default()),
+		};
+		store.refresh();
+		store
+	}
+	
+	//		load																
+	/// Loads the users from the file.
+	/// 
+	/// # Errors
+	/// 
+	/// If the file cannot be read or parsed, an error is returned.
+	/// 
+	fn load(&self) -> Result<UsersFile, UserStoreError> {
+		let text      = fs::read_to_string(&self.path)
+			.map_err(|err| UserStoreError::CouldNotReadFile(self.path.clone(), err))?
+		;
+		let mut file  = match self.format {
+			UserFileFormat::Htpasswd => UsersFile { users: parse_htpasswd(&text), ..Default::default() },
+			UserFileFormat::Toml     => toml::from_str::<UsersFile>(&text)
+				.map_err(|err| UserStoreError::InvalidTomlFile(self.path.clone(), err))?
+			,
+		};
+		file.users    = hashed_users(file.users, &self.path.display().to_string());
+		Ok(file)
+	}
+	
+	//		refresh																
+	/// Reloads the users if the file has changed since they were last loaded.
+	fn refresh(&self) {
+		let modified = fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok();
+		if modified.is_some() && modified == self.users.read().modified {
+			return;
+		}
+		//	The modification time is recorded even on failure, so that the same
+		//	problem is not reported on every login attempt.
+		match self.load() {
+			Ok(file) => {
+				info!("Loaded {} users from {}", file.users.len(), self.path.display());
+				*self.users.write() = LoadedUsers { modified, roles: file.roles, users: file.users };
+			},
+			Err(err) => {
+				warn!("{err}");
+				self.users.write().modified = modified;
+			},
+		}
+	}
+}
+
+//󰭅		UserStore																
+impl UserStore for FileUserStore {
+	//		authenticate														
+	fn authenticate(&self, username: &str, password: &str) -> bool {
+		self.refresh();
+		let hash = self.users.read().users.get(username).cloned();
+		verify_user(hash.as_deref(), password)
+	}
+	
+	//		contains															
+	fn contains(&self, username: &str) -> bool {
+		self.refresh();
+		self.users.read().users.contains_key(username)
+	}
+	
+	//		roles																
+	fn roles(&self, username: &str) -> Vec<String> {
+		self.refresh();
+		self.users.read().roles.get(username).cloned().unwrap_or_default()
+	}
+}
+
+//		LoadedUsers																
+/// Users loaded from a file.
+#[derive(Debug, Default)]
+struct LoadedUsers {
+	//		Private properties													
+	/// The modification time of the file when it was last loaded.
+	modified: Option<SystemTime>,
+	
+	/// The roles of the users, keyed by username.
+	roles:    HashMap<String, Vec<String>>,
+	
+	/// The password hashes of the users, keyed by username.
+	users:    HashMap<String, String>,
+}
+
+//		UsersFile																
+/// The structure of a TOML users file.
+#[derive(Debug, Default, Deserialize)]
+struct UsersFile {
+	//		Private properties													
+	/// The roles of the users, keyed by username.
+	#[serde(default)]
+	roles: HashMap<String, Vec<String>>,
+	
+	/// The users, and their password hashes.
+	#[serde(default)]
+	users: HashMap<String, String>,
+}
+
+
+
+//		Traits																											
+
+//§		UserStore																
+/// A source of users that can be authenticated.
+pub trait UserStore: Debug + Send + Sync {
+	//		authenticate														
+	/// Checks whether the password given is correct for the user.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// * `password` - The password to check.
+	/// 
+	fn authenticate(&self, username: &str, password: &str) -> bool;
+	
+	//		contains															
+	/// Checks whether the user exists.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// 
+	fn contains(&self, username: &str) -> bool;
+	
+	//		roles																
+	/// Gets the roles the user has been given by the store.
+	/// 
+	/// Stores that do not support roles return none, which is the default.
+	/// Roles can also be given to users in the application configuration,
+	/// regardless of the store used.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `username` - The username.
+	/// 
+	fn roles(&self, _username: &str) -> Vec<String> {
+		vec![]
+	}
+}
+
+
+
+//		Functions																										
+
+//		hash_password															
+/// Hashes a password.
+/// 
+/// # Parameters
+/// 
+/// * `password`  - The password to hash.
+/// * `algorithm` - The algorithm to use.
+/// 
+/// # Errors
+/// 
+/// If the password cannot be hashed, an error is returned.
+/// 
+pub fn hash_password(password: &str, algorithm: HashAlgorithm) -> Result<String, UserStoreError> {
+	Ok(match algorithm {
+		HashAlgorithm::Argon2 => Argon2::default()
+			.hash_password(password.as_bytes())
+			.map_err(UserStoreError::CouldNotHashArgon2)?
+			.to_string()
+		,
+		HashAlgorithm::Bcrypt => bcrypt::hash(password, bcrypt::DEFAULT_COST)?,
+	})
+}
+
+//		hashed_users															
+/// Removes any users whose passwords are not hashed.
+/// 
+/// Each user removed is reported with a warning, as plain-text passwords are
+/// not supported.
+/// 
+/// # Parameters
+/// 
+/// * `users`  - The users, and their password hashes.
+/// * `source` - Where the users were loaded from, for reporting purposes.
+/// 
+fn hashed_users(mut users: HashMap<String, String>, source: &str) -> HashMap<String, String> {
+	users.retain(|username, hash| {
+		let supported = is_supported_hash(hash);
+		if !supported {
+			warn!("Ignoring user {username} in {source}, as the password is not a supported hash");
+		}
+		supported
+	});
+	users
+}
+
+//		is_supported_hash														
+/// Checks whether a string is a password hash that can be verified.
+/// 
+/// # Parameters
+/// 
+/// * `hash` - The string to check.
+/// 
+fn is_supported_hash(hash: &str) -> bool {
+	hash.starts_with("$argon2") || ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix))
+}
+
+//		parse_htpasswd															
+/// Parses the contents of an htpasswd file.
+/// 
+/// Each line contains a username and a password hash, separated by a colon.
+/// Blank lines and comments starting with `#` are ignored.
+/// 
+/// # Parameters
+/// 
+/// * `text` - The contents of the file.
+/// 
+fn parse_htpasswd(text: &str) -> HashMap<String, String> {
+	text
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.filter_map(|line| line.split_once(':'))
+		.map(|(username, hash)| (username.to_owned(), hash.to_owned()))
+		.collect()
+}
+
+//		user_store																
+/// Creates the user store specified by the configuration.
+/// 
+/// # Parameters
+/// 
+/// * `config` - The application configuration.
+/// 
+pub fn user_store(config: &Config) -> Box<dyn UserStore> {
+	let path                        = config.user_store.path.clone();
+	let store: Box<dyn UserStore>   = match config.user_store.source {
+		UserSource::Config   => return Box::new(ConfigUserStore::new(&config.users)),
+		UserSource::Htpasswd => Box::new(FileUserStore::new(UserFileFormat::Htpasswd, path)),
+		UserSource::Toml     => Box::new(FileUserStore::new(UserFileFormat::Toml, path)),
+		UserSource::Ldap     => Box::new(LdapUserStore::new(&config.ldap)),
+	};
+	if config.user_store.include_config {
+		Box::new(ChainedUserStore::new(vec![Box::new(ConfigUserStore::new(&config.users)), store]))
+	} else {
+		store
+	}
+}
+
+//		verify_password															
+/// Verifies a password against a hash, in constant time.
+/// 
+/// # Parameters
+/// 
+/// * `password` - The password to verify.
+/// * `hash`     - The hash to verify against, as an Argon2 PHC string or a
+///                bcrypt hash.
+/// 
+pub fn verify_password(password: &str, hash: &str) -> bool {
+	if hash.starts_with("$argon2") {
+		PasswordHash::new(hash)
+			.is_ok_and(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+	} else {
+		is_supported_hash(hash) && bcrypt::verify(password, hash).unwrap_or(false)
+	}
+}
+
+//		verify_user																
+/// Verifies a password for a user who may not exist.
+/// 
+/// If the user does not exist, the password is verified against a dummy hash
+/// anyway, so that the time taken does not reveal whether the user exists.
+/// 
+/// # Parameters
+/// 
+/// * `hash`     - The password hash of the user, if the user exists.
+/// * `password` - The password to verify.
+/// 
+fn verify_user(hash: Option<&str>, password: &str) -> bool {
+	hash.map_or_else(
+		|| {
+			_ = black_box(verify_password(password, &DUMMY_HASH));
+			false
+		},
+		|stored| verify_password(password, stored),
+	)
+}
+
+
@@ -2,15 +2,158 @@
 
 
 
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/utility.rs"]
+mod tests;
+
+
+
 //		Packages																										
 
+use crate::{audit, handlers, responses};
+use axum::http::{HeaderMap, header};
+use core::cmp::Reverse;
 use terracotta::{health, stats};
-use utoipa::OpenApi;
+use utoipa::{
+	Modify,
+	OpenApi,
+	openapi::{
+		OpenApi as OpenApiDoc,
+		security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme},
+	},
+};
+
+
+
+//		Enums																											
+
+//		ResponseFormat															
+/// The formats a response can be given in, chosen according to the `Accept`
+/// header of the request.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ResponseFormat {
+	/// HTML, for browsers.
+	Html,
+	
+	/// JSON, for scripts.
+	Json,
+	
+	/// The original Markdown of a page.
+	Markdown,
+}
+
+//󰭅		ResponseFormat															
+impl ResponseFormat {
+	//		media_type															
+	/// The media type of the format.
+	#[must_use]
+	pub const fn media_type(self) -> &'static str {
+		match self {
+			Self::Html     => "text/html",
+			Self::Json     => "application/json",
+			Self::Markdown => "text/markdown",
+		}
+	}
+}
 
 
 
 //		Structs																											
 
+//		ApiTokenSecurity														
+/// Adds API token authentication to the OpenAPI documentation.
+/// 
+/// The protected endpoints can be used with an API token, sent in an
+/// `Authorization: Bearer` header, as an alternative to logging in.
+/// 
+#[derive(Clone, Copy, Debug)]
+struct ApiTokenSecurity;
+
+//󰭅		Modify																	
+impl Modify for ApiTokenSecurity {
+	//		modify																
+	fn modify(&self, openapi: &mut OpenApiDoc) {
+		openapi.components.get_or_insert_with(Default::default).add_security_scheme(
+			"api_token",
+			SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+		);
+		openapi.security = Some(vec![SecurityRequirement::new("api_token", Vec::<String>::new())]);
+	}
+}
+
+//		MediaRange																
+/// A media range from an `Accept` header, such as `text/*;q=0.5`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct MediaRange {
+	//		Private properties													
+	/// The media type, such as `text/html`, which can use `*` wildcards for the
+	/// subtype, or for both the type and the subtype. This is lowercase.
+	media_type: String,
+	
+	/// How much the media type is wanted, in thousandths, from `0`, meaning
+	/// not acceptable, to `1000`, which is the default.
+	quality:    u16,
+}
+
+//󰭅		MediaRange																
+impl MediaRange {
+	//		parse																
+	/// Parses a media range.
+	/// 
+	/// Parameters other than the quality are ignored.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `range` - A single media range, as found between the commas of an
+	///             `Accept` header.
+	/// 
+	/// # Returns
+	/// 
+	/// The media range, or [`None`] if it is not valid.
+	/// 
+	fn parse(range: &str) -> Option<Self> {
+		let mut parts       = range.split(';');
+		let media_type      = parts.next()?.trim().to_ascii_lowercase();
+		let (kind, subtype) = media_type.split_once('/')?;
+		if kind.is_empty() || subtype.is_empty() || (kind == "*" && subtype != "*") {
+			return None;
+		}
+		let quality         = parts
+			.filter_map(|parameter| parameter.split_once('='))
+			.find(|&(name, _)| name.trim().eq_ignore_ascii_case("q"))
+			.map_or(Some(1000), |(_, value)| parse_quality(value.trim()))?
+		;
+		Some(Self { media_type, quality })
+	}
+	
+	//		specificity															
+	/// Checks whether the range matches a media type, and if so, how closely.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `media_type` - The media type to match, which must be lowercase.
+	/// 
+	/// # Returns
+	/// 
+	/// `2` for an exact match, `1` for a match of the type with a wildcard
+	/// subtype, `0` for a match of everything, or [`None`] if the range does
+	/// not match.
+	/// 
+	fn specificity(&self, media_type: &str) -> Option<u8> {
+		if self.media_type == media_type {
+			return Some(2);
+		}
+		match self.media_type.strip_suffix("/*") {
+			Some("*")                                                                       => Some(0),
+			Some(kind) if media_type.split_once('/').is_some_and(|(other, _)| other == kind) => Some(1),
+			Some(_) | None                                                                  => None,
+		}
+	}
+}
+
 //		ApiDoc																	
 /// The OpenAPI documentation for the API.
 #[derive(OpenApi)]
@@ -18,9 +161,14 @@ use utoipa::OpenApi;
 	paths(
 		health::handlers::get_ping,
 		health::handlers::get_version,
-		stats::handlers::get_stats,
 		stats::handlers::get_stats_history,
 		stats::handlers::get_stats_feed,
+		handlers::get_audit,
+		handlers::get_page,
+		handlers::get_reload_feed,
+		handlers::get_search,
+		handlers::get_stats,
+		handlers::get_token_stats,
 	),
 	components(
 		schemas(
@@ -29,12 +177,96 @@ use utoipa::OpenApi;
 			stats::responses::StatsResponse,
 			stats::responses::StatsResponseForPeriod,
 			stats::responses::StatsHistoryResponse,
+			audit::AuditEvent,
+			responses::AppStatsResponse,
+			responses::CacheStatsResponse,
+			responses::HeadingResponse,
+			responses::PageResponse,
+			responses::SearchResponse,
+			responses::SearchResultResponse,
+			responses::TokenStatsResponse,
 		),
 	),
+	modifiers(&ApiTokenSecurity),
 	tags(
-		(name = "health", description = "Health check endpoints"),
+		(name = "health",  description = "Health check endpoints"),
+		(name = "content", description = "Content endpoints"),
+		(name = "admin",   description = "Administration endpoints"),
 	),
 )]
 pub struct ApiDoc;
 
 
+
+//		Functions																										
+
+//		negotiate																
+/// Chooses the format of a response, according to the `Accept` header of the
+/// request.
+/// 
+/// Each format is given the quality of the most specific media range that
+/// matches it, so `text/markdown;q=0` rules out Markdown even if `*/*` is also
+/// accepted. The format with the highest quality is chosen. Ties are broken in
+/// favour of an exact match over a wildcard, then the media range given first,
+/// and then the format offered first. If there is no `Accept` header, or none
+/// of the formats are acceptable, the first format offered is used, as a
+/// browser can always display something.
+/// 
+/// # Parameters
+/// 
+/// * `headers` - The request headers.
+/// * `offered` - The formats the response can be given in, in order of
+///               preference.
+/// 
+pub fn negotiate(headers: &HeaderMap, offered: &[ResponseFormat]) -> ResponseFormat {
+	let ranges  = headers
+		.get_all(header::ACCEPT)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.filter_map(MediaRange::parse)
+		.collect::<Vec<_>>()
+	;
+	let default = offered.first().copied().unwrap_or(ResponseFormat::Html);
+	offered
+		.iter()
+		.enumerate()
+		.filter_map(|(index, &format)| {
+			let (specificity, position, quality) = ranges
+				.iter()
+				.enumerate()
+				.filter_map(|(position, range)| range.specificity(format.media_type()).map(|specificity| (specificity, position, range.quality)))
+				.max_by_key(|&(specificity, position, _)| (specificity, Reverse(position)))?
+			;
+			(quality > 0).then_some(((quality, specificity, Reverse(position), Reverse(index)), format))
+		})
+		.max_by_key(|&(rank, _)| rank)
+		.map_or(default, |(_, format)| format)
+}
+
+//		parse_quality															
+/// Parses the quality of a media range.
+/// 
+/// # Parameters
+/// 
+/// * `value` - The value of the `q` parameter, from `0` to `1`, with up to
+///             three decimal places.
+/// 
+/// # Returns
+/// 
+/// The quality in thousandths, or [`None`] if it is not valid.
+/// 
+fn parse_quality(value: &str) -> Option<u16> {
+	let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+	if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+		return None;
+	}
+	let thousandths       = format!("{fraction:0<3}").parse::<u16>().ok()?;
+	match whole {
+		"0"                     => Some(thousandths),
+		"1" if thousandths == 0 => Some(1000),
+		_                       => None,
+	}
+}
+
+
@@ -0,0 +1,187 @@
+//! Wiki link functionality.
+//! 
+//! Wiki links are written as `[[Page Title]]` or `[[path/to/page|label]]`, and
+//! are rendered by the Markdown parser as anchors marked with a
+//! `data-wikilink` attribute. They cannot be resolved during parsing, because
+//! they can refer to any page in the content tree, so they are resolved
+//! afterwards against a [`PageIndex`] of all the pages known.
+//! 
+
+
+
+//		Modules																											
+
+#[cfg(test)]
+#[path = "tests/wiki.rs"]
+mod tests;
+
+
+
+//		Packages																										
+
+use super::{
+	CompiledPage,
+	links::{find_links, percent_decode},
+};
+use nipper::Document;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	path::Path,
+};
+
+
+
+//		Constants																										
+
+/// The CSS class added to wiki links that cannot be resolved.
+pub const UNRESOLVED_CLASS: &str = "wikilink-unresolved";
+
+/// The attribute used to record the original reference of a wiki link, so that
+/// it can be resolved again if the content tree changes.
+const REFERENCE_ATTRIBUTE:  &str = "data-wikilink-ref";
+
+
+
+//		Structs																											
+
+//		PageIndex																
+/// An index of pages, for resolving wiki links.
+/// 
+/// This records the path and title of every known page. It can be serialised,
+/// so that the index of the baked-in content can be built at build time and
+/// then supplemented with local content at runtime.
+/// 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct PageIndex {
+	//		Private properties													
+	/// The known pages, and their titles, keyed by path.
+	pages: BTreeMap<String, String>,
+}
+
+//󰭅		PageIndex																
+impl PageIndex {
+	//		new																	
+	/// Creates a new, empty page index.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	//		add																	
+	/// Adds a page to the index.
+	/// 
+	/// If the page is already known, its title is replaced, which allows
+	/// baked-in pages to be overridden by local ones, and local pages to be
+	/// updated when they change.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path`  - The path of the page, relative to the content root.
+	/// * `title` - The title of the page.
+	/// 
+	pub fn add(&mut self, path: &str, title: &str) {
+		drop(self.pages.insert(path.to_owned(), title.to_owned()));
+	}
+	
+	//		remove																
+	/// Removes a page from the index, so that it is no longer linked to.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `path` - The path of the page, relative to the content root.
+	/// 
+	pub fn remove(&mut self, path: &str) {
+		drop(self.pages.remove(path));
+	}
+	
+	//		resolve																
+	/// Resolves a wiki link reference to the path of a page.
+	/// 
+	/// The reference is matched, in order of preference, against the path of
+	/// each page (with or without the `.md` extension), the title of each
+	/// page, and the file stem of each page. Titles and file stems are matched
+	/// case-insensitively. If several pages match equally well, the one
+	/// with the first path in alphabetical order is used.
+	/// 
+	/// # Parameters
+	/// 
+	/// * `reference` - The page referred to by the wiki link.
+	/// 
+	/// # Returns
+	/// 
+	/// The path of the page, relative to the content root, or `None` if no
+	/// page matches.
+	/// 
+	#[must_use]
+	pub fn resolve(&self, reference: &str) -> Option<&str> {
+		let trimmed   = reference.trim().trim_start_matches('/');
+		if trimmed.is_empty() {
+			return None;
+		}
+		let with_ext  = format!("{trimmed}.md");
+		let lowercase = trimmed.to_lowercase();
+		if let Some((path, _)) = self.pages.get_key_value(trimmed).or_else(|| self.pages.get_key_value(&with_ext)) {
+			return Some(path);
+		}
+		self.pages
+			.iter()
+			.find(|&(_, title)| title.to_lowercase() == lowercase)
+			.or_else(|| self.pages.iter().find(|&(path, _)|
+				Path::new(path).file_stem().is_some_and(|stem| stem.to_string_lossy().to_lowercase() == lowercase)
+			))
+			.map(|(path, _)| path.as_str())
+	}
+}
+
+
+
+//		Functions																										
+
+//		resolve_wiki_links														
+/// Resolves the wiki links in a page.
+/// 
+/// Resolved links are pointed at the page they refer to, and unresolved links
+/// have their target removed, and are marked with the [`UNRESOLVED_CLASS`]
+/// CSS class. The original reference is kept, so that the links can be
+/// resolved again if the content tree changes. The links recorded for the
+/// page are updated to match.
+/// 
+/// # Parameters
+/// 
+/// * `page`  - The page to resolve the wiki links in.
+/// * `index` - The index of pages to resolve the links against.
+/// 
+/// # Returns
+/// 
+/// The references of any wiki links that could not be resolved.
+/// 
+pub fn resolve_wiki_links(page: &mut CompiledPage, index: &PageIndex) -> Vec<String> {
+	if !page.html.contains("data-wikilink") {
+		return vec![];
+	}
+	let document       = Document::from(&page.html);
+	let mut unresolved = vec![];
+	for mut element in document.select("a[data-wikilink]").iter() {
+		let reference = element.attr(REFERENCE_ATTRIBUTE)
+			.map(|reference| reference.to_string())
+			.or_else(|| element.attr("href").map(|href| percent_decode(&href)))
+			.unwrap_or_default()
+		;
+		element.set_attr(REFERENCE_ATTRIBUTE, &reference);
+		if let Some(path) = index.resolve(&reference) {
+			element.set_attr("href", &format!("/{path}"));
+			element.remove_class(UNRESOLVED_CLASS);
+		} else {
+			element.remove_attr("href");
+			element.add_class(UNRESOLVED_CLASS);
+			unresolved.push(reference);
+		}
+	}
+	page.html  = document.html().to_string();
+	page.links = find_links(&document);
+	unresolved
+}
+
+
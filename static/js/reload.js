@@ -0,0 +1,27 @@
+//	Reloads the page when the local Markdown file backing it changes. This is
+//	only included when live reload is enabled.
+(function() {
+	var page   = decodeURIComponent(location.pathname.replace(/^\//, "")) || "index.md";
+	var key    = "rustmark-scroll:" + location.pathname;
+	var scroll = sessionStorage.getItem(key);
+	if (scroll !== null) {
+		sessionStorage.removeItem(key);
+		window.addEventListener("load", function() {
+			window.scrollTo(0, parseInt(scroll, 10));
+		});
+	}
+	function connect() {
+		var protocol = location.protocol === "https:" ? "wss://" : "ws://";
+		var socket   = new WebSocket(protocol + location.host + "/api/reload");
+		socket.addEventListener("message", function(event) {
+			if (event.data === page) {
+				sessionStorage.setItem(key, String(window.scrollY));
+				location.reload();
+			}
+		});
+		socket.addEventListener("close", function() {
+			setTimeout(connect, 2000);
+		});
+	}
+	connect();
+})();